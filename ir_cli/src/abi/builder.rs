@@ -0,0 +1,121 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    IRContractMethodInputMeta, IRContractMethodMeta, IRContractMethodOutputMeta, MetaCache,
+    METHOD_TYPE_CONSTRUCTOR, METHOD_TYPE_FUNCTION,
+};
+use smart_ir::abi::error::AbiError;
+
+/// MethodMetaBuilder assembles an `IRContractMethodMeta` without a `Contract`.
+///
+/// # Example
+///
+/// ```
+/// use ir_cli::abi::builder::MethodMetaBuilder;
+///
+/// let method = MethodMetaBuilder::default()
+///     .name("transfer")
+///     .function()
+///     .input("to", "str")
+///     .input("amount", "u64")
+///     .output("bool")
+///     .build()
+///     .unwrap();
+/// assert_eq!(method.inputs.len(), 2);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MethodMetaBuilder {
+    name: String,
+    r#type: String,
+    inputs: Vec<IRContractMethodInputMeta>,
+    outputs: Vec<IRContractMethodOutputMeta>,
+    qualified_name: String,
+    description: Option<String>,
+}
+
+impl MethodMetaBuilder {
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn constructor(mut self) -> Self {
+        self.r#type = METHOD_TYPE_CONSTRUCTOR.to_string();
+        self
+    }
+
+    pub fn function(mut self) -> Self {
+        self.r#type = METHOD_TYPE_FUNCTION.to_string();
+        self
+    }
+
+    pub fn qualified_name(mut self, qualified_name: &str) -> Self {
+        self.qualified_name = qualified_name.to_string();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Set the raw method type which is validated in `build`.
+    pub fn method_type(mut self, r#type: &str) -> Self {
+        self.r#type = r#type.to_string();
+        self
+    }
+
+    pub fn input(mut self, name: &str, ty: &str) -> Self {
+        self.inputs.push(IRContractMethodInputMeta {
+            name: name.to_string(),
+            r#type: ty.to_string(),
+            indexed: false,
+            description: None,
+        });
+        self
+    }
+
+    /// Add an input which is indexed for the off-chain filtering.
+    pub fn indexed_input(mut self, name: &str, ty: &str) -> Self {
+        self.inputs.push(IRContractMethodInputMeta {
+            name: name.to_string(),
+            r#type: ty.to_string(),
+            indexed: true,
+            description: None,
+        });
+        self
+    }
+
+    pub fn output(mut self, ty: &str) -> Self {
+        self.outputs.push(IRContractMethodOutputMeta {
+            r#type: ty.to_string(),
+        });
+        self
+    }
+
+    /// Build the method meta, the method name must be set and the method type
+    /// must be 'constructor' or 'function'.
+    pub fn build(self) -> Result<IRContractMethodMeta, AbiError> {
+        if self.name.is_empty() {
+            return Err(AbiError::MissingMethodName);
+        }
+        if self.r#type != METHOD_TYPE_CONSTRUCTOR && self.r#type != METHOD_TYPE_FUNCTION {
+            return Err(AbiError::InvalidMethodType {
+                name: self.name,
+                method_type: self.r#type,
+            });
+        }
+        Ok(IRContractMethodMeta {
+            name: self.name,
+            r#type: self.r#type,
+            inputs: self.inputs,
+            outputs: self.outputs,
+            qualified_name: self.qualified_name,
+            description: self.description,
+            inherited_from: None,
+            param_types: MetaCache::default(),
+        })
+    }
+}
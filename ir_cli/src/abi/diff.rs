@@ -0,0 +1,95 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{normalize_type_name, IRContractABIMeta, IRContractMethodMeta};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// The method differences between two abi metas, each method is described as
+/// `{type} {signature}->({outputs})`, e.g., `function transfer(string,u64)->(bool)`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IRContractABIDiff {
+    /// The methods only in the new abi meta.
+    pub added: Vec<String>,
+    /// The methods only in the old abi meta.
+    pub removed: Vec<String>,
+    /// The `(old, new)` methods with the same name but different types.
+    pub changed: Vec<(String, String)>,
+}
+
+impl IRContractABIDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for IRContractABIDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for method in &self.added {
+            writeln!(f, "added: {method}")?;
+        }
+        for method in &self.removed {
+            writeln!(f, "removed: {method}")?;
+        }
+        for (old, new) in &self.changed {
+            writeln!(f, "changed: {old} => {new}")?;
+        }
+        Ok(())
+    }
+}
+
+impl IRContractABIMeta {
+    /// Compare the methods with the `new` abi meta. The methods are matched by the name,
+    /// a name with a single method on both sides and different types is reported as
+    /// changed, the overloaded methods are reported as added and removed.
+    pub fn diff(&self, new: &IRContractABIMeta) -> IRContractABIDiff {
+        let old_methods = methods_by_name(self);
+        let new_methods = methods_by_name(new);
+        let names: BTreeSet<&str> = old_methods
+            .keys()
+            .chain(new_methods.keys())
+            .copied()
+            .collect();
+        let empty = BTreeSet::new();
+        let mut diff = IRContractABIDiff::default();
+        for name in names {
+            let old = old_methods.get(name).unwrap_or(&empty);
+            let new = new_methods.get(name).unwrap_or(&empty);
+            let removed: Vec<&String> = old.difference(new).collect();
+            let added: Vec<&String> = new.difference(old).collect();
+            if old.len() == 1 && new.len() == 1 && removed.len() == 1 {
+                diff.changed.push((removed[0].clone(), added[0].clone()));
+            } else {
+                diff.removed.extend(removed.into_iter().cloned());
+                diff.added.extend(added.into_iter().cloned());
+            }
+        }
+        diff
+    }
+}
+
+fn methods_by_name(abi: &IRContractABIMeta) -> BTreeMap<&str, BTreeSet<String>> {
+    let mut methods: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+    for method in &abi.methods {
+        methods
+            .entry(method.name.as_str())
+            .or_default()
+            .insert(describe_method(method));
+    }
+    methods
+}
+
+fn describe_method(method: &IRContractMethodMeta) -> String {
+    let output_types: Vec<String> = method
+        .outputs
+        .iter()
+        .map(|output| normalize_type_name(&output.r#type))
+        .collect();
+    format!(
+        "{} {}->({})",
+        method.r#type,
+        method.signature(),
+        output_types.join(",")
+    )
+}
@@ -0,0 +1,117 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    normalize_type_name, IRContractABIMeta, IRContractMethodMeta, METHOD_TYPE_CONSTRUCTOR,
+};
+
+/// A function or constructor entry of the Ethereum-compatible contract abi json.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EthABIEntry {
+    /// 'constructor' or 'function'
+    pub r#type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub inputs: Vec<EthABIParam>,
+    /// Constructors have no outputs in the Ethereum abi json.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<Vec<EthABIParam>>,
+    #[serde(rename = "stateMutability")]
+    pub state_mutability: String,
+}
+
+/// A input/output entry of the Ethereum-compatible contract abi json.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EthABIParam {
+    pub name: String,
+    pub r#type: String,
+    /// Only emitted for the indexed inputs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexed: Option<bool>,
+}
+
+/// Convert the ir abi type name to the Ethereum abi type name, e.g., `[u8]` to `uint8[]`.
+pub fn ir_type_to_eth_type(ir_type_name: &str) -> Result<String, String> {
+    let ir_type_name = normalize_type_name(ir_type_name);
+    let ir_type_name = ir_type_name.as_str();
+    match ir_type_name {
+        "bool" => Ok("bool".to_string()),
+        "string" => Ok("string".to_string()),
+        "parampack" | "bytes" => Ok("bytes".to_string()),
+        "u8" | "u16" | "u32" | "u64" | "u128" => Ok(format!("uint{}", &ir_type_name[1..])),
+        "i8" | "i16" | "i32" | "i64" | "i128" => Ok(format!("int{}", &ir_type_name[1..])),
+        _ => {
+            if ir_type_name.starts_with('[') && ir_type_name.ends_with(']') {
+                let inner_type_name = &ir_type_name[1..(ir_type_name.len() - 1)];
+                // fixed size array [T;N]
+                if let Some(sep_pos) = inner_type_name.rfind(';') {
+                    let elem_type = ir_type_to_eth_type(&inner_type_name[..sep_pos])?;
+                    let len = &inner_type_name[(sep_pos + 1)..];
+                    Ok(format!("{elem_type}[{len}]"))
+                } else {
+                    Ok(format!("{}[]", ir_type_to_eth_type(inner_type_name)?))
+                }
+            } else {
+                Err(format!(
+                    "abi type {ir_type_name} has no Ethereum abi equivalent"
+                ))
+            }
+        }
+    }
+}
+
+impl IRContractMethodMeta {
+    /// Convert the method meta to the Ethereum-compatible abi entry.
+    pub fn to_eth_entry(&self) -> Result<EthABIEntry, String> {
+        let mut inputs: Vec<EthABIParam> = vec![];
+        for input in &self.inputs {
+            inputs.push(EthABIParam {
+                name: input.name.clone(),
+                r#type: ir_type_to_eth_type(&input.r#type)
+                    .map_err(|err| format!("method {}: {err}", self.name))?,
+                indexed: if input.indexed { Some(true) } else { None },
+            });
+        }
+        let is_constructor = self.r#type == METHOD_TYPE_CONSTRUCTOR;
+        let outputs = if is_constructor {
+            None
+        } else {
+            let mut outputs: Vec<EthABIParam> = vec![];
+            for output in &self.outputs {
+                outputs.push(EthABIParam {
+                    name: "".to_string(),
+                    r#type: ir_type_to_eth_type(&output.r#type)
+                        .map_err(|err| format!("method {}: {err}", self.name))?,
+                    indexed: None,
+                });
+            }
+            Some(outputs)
+        };
+        Ok(EthABIEntry {
+            r#type: self.r#type.clone(),
+            name: if is_constructor {
+                None
+            } else {
+                Some(self.name.clone())
+            },
+            inputs,
+            outputs,
+            state_mutability: self.state_mutability().to_string(),
+        })
+    }
+}
+
+impl IRContractABIMeta {
+    /// Serialize the abi meta to the Ethereum-compatible abi json. It fails when
+    /// some method uses types without an Ethereum equivalent such as maps.
+    pub fn to_eth_json(&self) -> Result<Vec<u8>, String> {
+        let mut entries: Vec<EthABIEntry> = vec![];
+        for method in &self.methods {
+            entries.push(method.to_eth_entry()?);
+        }
+        serde_json::to_string_pretty(&entries)
+            .map(|json| json.into_bytes())
+            .map_err(|e| format!("could not serialize to json: {e}"))
+    }
+}
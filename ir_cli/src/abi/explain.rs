@@ -0,0 +1,163 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    normalize_type_name, IRContractABIMeta, IRContractMethodInputMeta, IRContractMethodMeta,
+    METHOD_TYPE_CONSTRUCTOR,
+};
+use std::fmt::Write;
+
+const BOLD: &str = "1";
+const CYAN: &str = "36";
+const DIM: &str = "2";
+
+/// Wrap the text with the ANSI color code when the color output is selected.
+fn paint(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("\x1b[{code}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Format the rows as a table with the left aligned columns, the column widths are
+/// computed before the colors are applied so that the colors don't break the alignment.
+fn write_table(out: &mut String, indent: &str, header: &[&str], rows: &[Vec<String>], color: bool) {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let format_row = |cells: &[String]| -> String {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        cells.join("  ").trim_end().to_string()
+    };
+    let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+    let _ = writeln!(out, "{indent}{}", paint(&format_row(&header), DIM, color));
+    for row in rows {
+        let _ = writeln!(out, "{indent}{}", format_row(row));
+    }
+}
+
+fn input_rows(inputs: &[IRContractMethodInputMeta]) -> Vec<Vec<String>> {
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(index, input)| {
+            vec![
+                index.to_string(),
+                input.name.clone(),
+                normalize_type_name(&input.r#type),
+                if input.indexed { "yes" } else { "" }.to_string(),
+            ]
+        })
+        .collect()
+}
+
+fn write_method(out: &mut String, method: &IRContractMethodMeta, color: bool) {
+    let _ = writeln!(
+        out,
+        "  {}  {}",
+        paint(&method.signature(), BOLD, color),
+        paint(
+            &format!("0x{}", hex::encode(method.selector())),
+            CYAN,
+            color
+        )
+    );
+    if let Some(description) = &method.description {
+        let _ = writeln!(out, "    description: {description}");
+    }
+    let _ = writeln!(out, "    mutability: {}", method.state_mutability());
+    if method.inputs.is_empty() {
+        let _ = writeln!(out, "    inputs: none");
+    } else {
+        let _ = writeln!(out, "    inputs:");
+        let mut header = vec!["#", "name", "type", "indexed"];
+        let mut rows = input_rows(&method.inputs);
+        // The description column is only shown when some input is described.
+        if method
+            .inputs
+            .iter()
+            .any(|input| input.description.is_some())
+        {
+            header.push("description");
+            for (row, input) in rows.iter_mut().zip(&method.inputs) {
+                row.push(input.description.clone().unwrap_or_default());
+            }
+        }
+        write_table(out, "      ", &header, &rows, color);
+    }
+    if method.outputs.is_empty() {
+        let _ = writeln!(out, "    outputs: none");
+    } else {
+        let _ = writeln!(out, "    outputs:");
+        let rows: Vec<Vec<String>> = method
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(index, output)| vec![index.to_string(), normalize_type_name(&output.r#type)])
+            .collect();
+        write_table(out, "      ", &["#", "type"], &rows, color);
+    }
+}
+
+impl IRContractABIMeta {
+    /// Explain the abi meta in the human-readable form for the `abi explain` command, the
+    /// contract name and version if any come first, then the constructors and the functions
+    /// are listed in separate sections with their canonical signatures, selectors, the
+    /// descriptions if any, the state mutability and the input and output tables, followed
+    /// by the errors. The IR has no events yet, so there is no events section. The ANSI
+    /// colors are only used when `color` is set.
+    pub fn explain(&self, color: bool) -> String {
+        let mut out = String::new();
+        if let Some(name) = &self.name {
+            let _ = writeln!(out, "contract: {}", paint(name, BOLD, color));
+        }
+        if let Some(version) = &self.version {
+            let _ = writeln!(out, "version: {version}");
+        }
+        let _ = writeln!(out, "abi version: {}", self.abi_version);
+        let (constructors, functions): (Vec<&IRContractMethodMeta>, Vec<&IRContractMethodMeta>) =
+            self.methods
+                .iter()
+                .partition(|m| m.r#type == METHOD_TYPE_CONSTRUCTOR);
+        for (title, methods) in [("constructors", constructors), ("functions", functions)] {
+            if methods.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "{}", paint(&format!("{title}:"), BOLD, color));
+            for method in methods {
+                write_method(&mut out, method, color);
+            }
+        }
+        if !self.errors.is_empty() {
+            let _ = writeln!(out, "{}", paint("errors:", BOLD, color));
+            for error in &self.errors {
+                let _ = writeln!(
+                    out,
+                    "  {}  {}",
+                    paint(&error.name, BOLD, color),
+                    paint(&format!("code {}", error.code), CYAN, color)
+                );
+                if !error.fields.is_empty() {
+                    let rows: Vec<Vec<String>> = input_rows(&error.fields)
+                        .into_iter()
+                        .map(|mut row| {
+                            row.truncate(3);
+                            row
+                        })
+                        .collect();
+                    write_table(&mut out, "    ", &["#", "name", "type"], &rows, color);
+                }
+            }
+        }
+        out
+    }
+}
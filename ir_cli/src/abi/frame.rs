@@ -0,0 +1,81 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The framed form of the encoded params for the transport over unreliable channels, which
+//! is `[len][payload][crc32]`: the little-endian `u32` payload length, the `encode_params`
+//! payload and the little-endian CRC-32 (IEEE) of the length and the payload. The receivers
+//! detect the truncation and corruption before decoding. The on-chain call data keeps the
+//! raw format because the transaction is already checked.
+
+use super::IRContractMethodMeta;
+use smart_ir::abi::error::AbiError;
+use smart_ir::abi::params::ABIParam;
+
+/// The byte length of the payload length prefix and the checksum trailer each.
+const FRAME_FIELD_LEN: usize = 4;
+
+/// The CRC-32 (IEEE 802.3) checksum, the same as the zlib and PNG one.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+impl IRContractMethodMeta {
+    /// Encode the params strings in the same way as `encode_params` and wrap the
+    /// payload in the frame.
+    pub fn encode_params_framed(&self, params_strings: &[&str]) -> Result<Vec<u8>, AbiError> {
+        let payload = self.encode_params(params_strings)?;
+        let payload_len = u32::try_from(payload.len()).map_err(|_| AbiError::LimitExceeded {
+            limit: "frame_payload_len".to_string(),
+            actual: payload.len(),
+            max: u32::MAX as usize,
+        })?;
+        let mut frame = Vec::with_capacity(payload.len() + 2 * FRAME_FIELD_LEN);
+        frame.extend(payload_len.to_le_bytes());
+        frame.extend(payload);
+        let checksum = crc32(&frame);
+        frame.extend(checksum.to_le_bytes());
+        Ok(frame)
+    }
+
+    /// Verify the frame produced by `encode_params_framed` and decode the payload in the
+    /// same way as `decode_params`. The frame must be exactly the declared length and the
+    /// checksum must match, otherwise nothing is decoded.
+    pub fn decode_params_framed(&self, frame: &[u8]) -> Result<Vec<ABIParam>, AbiError> {
+        if frame.len() < FRAME_FIELD_LEN {
+            return Err(AbiError::FrameLength {
+                expected: 2 * FRAME_FIELD_LEN,
+                got: frame.len(),
+            });
+        }
+        let payload_len = u32::from_le_bytes(frame[..FRAME_FIELD_LEN].try_into().unwrap()) as usize;
+        let expected = payload_len.saturating_add(2 * FRAME_FIELD_LEN);
+        if frame.len() != expected {
+            return Err(AbiError::FrameLength {
+                expected,
+                got: frame.len(),
+            });
+        }
+        let (checked, checksum_bytes) = frame.split_at(FRAME_FIELD_LEN + payload_len);
+        let expected_checksum = u32::from_le_bytes(checksum_bytes.try_into().unwrap());
+        let checksum = crc32(checked);
+        if checksum != expected_checksum {
+            return Err(AbiError::FrameChecksum {
+                expected: expected_checksum,
+                got: checksum,
+            });
+        }
+        self.decode_params(&checked[FRAME_FIELD_LEN..])
+    }
+}
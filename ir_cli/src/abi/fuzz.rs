@@ -0,0 +1,218 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The random valid params of the method inputs for stress-testing the contract decoders.
+//! The values are drawn from a seeded SplitMix64 generator written here instead of an RNG
+//! library, so that a seed reproduces the same params across the library versions and
+//! platforms. The collections are short and the strings are alphanumeric, which keeps the
+//! blobs readable, and every blob is valid for `decode_params`.
+
+use super::{encode_abi_params, IRContractMethodMeta};
+use smart_ir::abi::error::AbiError;
+use smart_ir::abi::params::{ABIParam, EncodingOptions};
+use smart_ir::abi::types::ABIParamType;
+use std::collections::BTreeMap;
+
+/// The max element count of the random arrays and maps and the max length of the random
+/// strings and bytes.
+const MAX_FUZZ_LEN: usize = 4;
+/// The characters of the random strings.
+const FUZZ_CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// The max random timestamp, which is 2100-01-01T00:00:00Z.
+const MAX_FUZZ_TIMESTAMP: u64 = 4_102_444_800;
+
+/// The seeded SplitMix64 pseudo-random generator, which is not cryptographically secure.
+#[derive(Debug, Clone)]
+pub struct FuzzRng {
+    state: u64,
+}
+
+impl FuzzRng {
+    pub fn new(seed: u64) -> Self {
+        FuzzRng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_u128(&mut self) -> u128 {
+        ((self.next_u64() as u128) << 64) | self.next_u64() as u128
+    }
+
+    /// A random value in `0..=max`.
+    fn next_up_to(&mut self, max: u64) -> u64 {
+        match max.checked_add(1) {
+            Some(bound) => self.next_u64() % bound,
+            None => self.next_u64(),
+        }
+    }
+
+    fn next_len(&mut self) -> usize {
+        self.next_up_to(MAX_FUZZ_LEN as u64) as usize
+    }
+
+    fn next_string(&mut self) -> String {
+        (0..self.next_len())
+            .map(|_| FUZZ_CHARS[self.next_up_to(FUZZ_CHARS.len() as u64 - 1) as usize] as char)
+            .collect()
+    }
+
+    fn next_bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next_u64() as u8).collect()
+    }
+
+    fn next_short_bytes(&mut self) -> Vec<u8> {
+        let len = self.next_len();
+        self.next_bytes(len)
+    }
+
+    /// A random finite float with at most 3 decimal places.
+    fn next_f64(&mut self) -> f64 {
+        self.next_u64() as i32 as f64 / 1000.0
+    }
+}
+
+macro_rules! fuzz_vec {
+    ($rng:expr, $len:expr, $id:ident, $array_id:ident) => {{
+        let elems = (0..$len)
+            .map(|_| match random_param(&ABIParamType::$id, $rng)? {
+                ABIParam::$id(v) => Ok(v),
+                param => unreachable!("random {} but got {param:?}", stringify!($id)),
+            })
+            .collect::<Result<Vec<_>, AbiError>>()?;
+        ABIParam::$array_id(elems)
+    }};
+}
+
+macro_rules! fuzz_map {
+    ($rng:expr, $id:ident, $map_id:ident) => {{
+        let mut entries = BTreeMap::new();
+        for _ in 0..$rng.next_len() {
+            let value = match random_param(&ABIParamType::$id, $rng)? {
+                ABIParam::$id(v) => v,
+                param => unreachable!("random {} but got {param:?}", stringify!($id)),
+            };
+            entries.insert($rng.next_string(), value);
+        }
+        ABIParam::$map_id(entries)
+    }};
+}
+
+/// The random array param of the dynamic array type with `len` elements.
+fn random_array(
+    array_ty: &ABIParamType,
+    len: usize,
+    rng: &mut FuzzRng,
+) -> Result<ABIParam, AbiError> {
+    Ok(match array_ty {
+        ABIParamType::U8Array => fuzz_vec!(rng, len, U8, U8Array),
+        ABIParamType::I8Array => fuzz_vec!(rng, len, I8, I8Array),
+        ABIParamType::U16Array => fuzz_vec!(rng, len, U16, U16Array),
+        ABIParamType::I16Array => fuzz_vec!(rng, len, I16, I16Array),
+        ABIParamType::U32Array => fuzz_vec!(rng, len, U32, U32Array),
+        ABIParamType::I32Array => fuzz_vec!(rng, len, I32, I32Array),
+        ABIParamType::U64Array => fuzz_vec!(rng, len, U64, U64Array),
+        ABIParamType::I64Array => fuzz_vec!(rng, len, I64, I64Array),
+        ABIParamType::U128Array => fuzz_vec!(rng, len, U128, U128Array),
+        ABIParamType::I128Array => fuzz_vec!(rng, len, I128, I128Array),
+        ABIParamType::BoolArray => fuzz_vec!(rng, len, Bool, BoolArray),
+        ABIParamType::StrArray => fuzz_vec!(rng, len, Str, StrArray),
+        ABIParamType::F64Array => fuzz_vec!(rng, len, F64, F64Array),
+        _ => return Err(AbiError::UnsupportedType(array_ty.canonical_string())),
+    })
+}
+
+/// A random valid param of the type. The type references such as `#0` have no types
+/// table here and are rejected with `AbiError::UnresolvedTypeRef`.
+pub fn random_param(ty: &ABIParamType, rng: &mut FuzzRng) -> Result<ABIParam, AbiError> {
+    Ok(match ty {
+        ABIParamType::U8 => ABIParam::U8(rng.next_u64() as u8),
+        ABIParamType::I8 => ABIParam::I8(rng.next_u64() as i8),
+        ABIParamType::U16 => ABIParam::U16(rng.next_u64() as u16),
+        ABIParamType::I16 => ABIParam::I16(rng.next_u64() as i16),
+        ABIParamType::U32 => ABIParam::U32(rng.next_u64() as u32),
+        ABIParamType::I32 => ABIParam::I32(rng.next_u64() as i32),
+        ABIParamType::U64 => ABIParam::U64(rng.next_u64()),
+        ABIParamType::I64 => ABIParam::I64(rng.next_u64() as i64),
+        ABIParamType::U128 => ABIParam::U128(rng.next_u128()),
+        ABIParamType::I128 => ABIParam::I128(rng.next_u128() as i128),
+        ABIParamType::Bool => ABIParam::Bool(rng.next_u64() & 1 == 1),
+        ABIParamType::Str => ABIParam::Str(rng.next_string()),
+        ABIParamType::Parampack => ABIParam::Parampack(rng.next_short_bytes()),
+        ABIParamType::F32 => ABIParam::F32(rng.next_f64() as f32),
+        ABIParamType::F64 => ABIParam::F64(rng.next_f64()),
+        ABIParamType::U8Array
+        | ABIParamType::I8Array
+        | ABIParamType::U16Array
+        | ABIParamType::I16Array
+        | ABIParamType::U32Array
+        | ABIParamType::I32Array
+        | ABIParamType::U64Array
+        | ABIParamType::I64Array
+        | ABIParamType::U128Array
+        | ABIParamType::I128Array
+        | ABIParamType::BoolArray
+        | ABIParamType::StrArray
+        | ABIParamType::F64Array => {
+            let len = rng.next_len();
+            random_array(ty, len, rng)?
+        }
+        ABIParamType::StrU8Map => fuzz_map!(rng, U8, StrU8Map),
+        ABIParamType::StrI8Map => fuzz_map!(rng, I8, StrI8Map),
+        ABIParamType::StrU16Map => fuzz_map!(rng, U16, StrU16Map),
+        ABIParamType::StrI16Map => fuzz_map!(rng, I16, StrI16Map),
+        ABIParamType::StrU32Map => fuzz_map!(rng, U32, StrU32Map),
+        ABIParamType::StrI32Map => fuzz_map!(rng, I32, StrI32Map),
+        ABIParamType::StrU64Map => fuzz_map!(rng, U64, StrU64Map),
+        ABIParamType::StrI64Map => fuzz_map!(rng, I64, StrI64Map),
+        ABIParamType::StrU128Map => fuzz_map!(rng, U128, StrU128Map),
+        ABIParamType::StrI128Map => fuzz_map!(rng, I128, StrI128Map),
+        ABIParamType::StrBoolMap => fuzz_map!(rng, Bool, StrBoolMap),
+        ABIParamType::StrStrMap => fuzz_map!(rng, Str, StrStrMap),
+        ABIParamType::Timestamp => ABIParam::Timestamp(rng.next_up_to(MAX_FUZZ_TIMESTAMP) as i64),
+        ABIParamType::Duration => ABIParam::Duration(rng.next_u64() as u32 as u64),
+        ABIParamType::Bytes => ABIParam::Bytes(rng.next_short_bytes()),
+        ABIParamType::Variant(variant_tys) => {
+            if variant_tys.is_empty() {
+                return Err(AbiError::UnsupportedType(ty.canonical_string()));
+            }
+            let tag = rng.next_up_to(variant_tys.len() as u64 - 1) as u32;
+            ABIParam::Variant {
+                tag,
+                value: Box::new(random_param(&variant_tys[tag as usize], rng)?),
+            }
+        }
+        ABIParamType::FixedArray(array_ty, len) => {
+            ABIParam::fixed_array(random_array(array_ty, *len, rng)?, *len)?
+        }
+        ABIParamType::Address(len) => {
+            let bytes = rng.next_bytes(len.unwrap_or(20));
+            ABIParam::address(bytes, *len)?
+        }
+        ABIParamType::Ref(id) => return Err(AbiError::UnresolvedTypeRef(*id)),
+    })
+}
+
+impl IRContractMethodMeta {
+    /// Generate `count` random valid param sets of the method inputs from the `seed` and
+    /// encode them like `encode_params`, the same seed generates the same blobs.
+    pub fn fuzz_params(&self, seed: u64, count: usize) -> Result<Vec<Vec<u8>>, AbiError> {
+        let input_param_types = self.input_param_types()?;
+        let mut rng = FuzzRng::new(seed);
+        (0..count)
+            .map(|_| {
+                let params = input_param_types
+                    .iter()
+                    .map(|param_ty| random_param(param_ty, &mut rng))
+                    .collect::<Result<Vec<ABIParam>, AbiError>>()?;
+                Ok(encode_abi_params(&params, EncodingOptions::default()))
+            })
+            .collect()
+    }
+}
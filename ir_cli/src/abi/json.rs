@@ -0,0 +1,323 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Encode the params given as a json object keyed by the input names, which is the usual
+//! form of the arguments in the web integrations. Arrays, including the fixed-size arrays,
+//! are json arrays, maps are json objects and variants are `{"tag": 1, "value": ...}`
+//! objects. The scalars are coerced to the declared types with the same parsers as
+//! `encode_params`, so the integers out of the json number range such as `u128` values can
+//! be given as json strings.
+//!
+//! The decoded params are rendered in the same form, the integers above `2^53` are json
+//! strings so that the json readers using `f64` numbers don't lose precision, and the
+//! `parampack`, `bytes` and address values are `0x` prefixed hex strings.
+
+use super::{abi_param_from_str, encode_abi_params, IRContractMethodMeta};
+use serde_json::Value;
+use serde_json::{Map, Number};
+use smart_ir::abi::error::AbiError;
+use smart_ir::abi::params::{encode_hex_str, ABIParam, EncodingOptions};
+use smart_ir::abi::types::ABIParamType;
+
+fn json_type_error(param_ty: &ABIParamType, value: &Value) -> AbiError {
+    AbiError::JsonType {
+        ty: param_ty.canonical_string(),
+        value: value.to_string(),
+    }
+}
+
+/// Coerce the json scalar to the param, the strings are parsed as the `encode_params`
+/// strings, and the numbers and bools are only accepted by the types they can represent.
+/// The bare integer numbers out of the exact `f64` range are rejected when
+/// `strict_numbers` is set, because the json writers may have rounded them.
+fn json_scalar_to_abi_param(
+    param_ty: &ABIParamType,
+    value: &Value,
+    strict_numbers: bool,
+) -> Result<ABIParam, AbiError> {
+    let param_str = match (param_ty, value) {
+        (_, Value::String(s)) => s.clone(),
+        (ABIParamType::Bool, Value::Bool(b)) => b.to_string(),
+        (
+            ABIParamType::Str
+            | ABIParamType::Parampack
+            | ABIParamType::Bytes
+            | ABIParamType::Bool
+            | ABIParamType::Address(_),
+            Value::Number(_),
+        ) => return Err(json_type_error(param_ty, value)),
+        (ABIParamType::F32 | ABIParamType::F64, Value::Number(n)) => n.to_string(),
+        (_, Value::Number(n)) if strict_numbers && !is_safe_json_int(n) => {
+            return Err(AbiError::UnsafeJsonNumber {
+                ty: param_ty.canonical_string(),
+                value: n.to_string(),
+            })
+        }
+        (_, Value::Number(n)) => n.to_string(),
+        _ => return Err(json_type_error(param_ty, value)),
+    };
+    abi_param_from_str(param_ty, &param_str)
+}
+
+macro_rules! impl_json_to_abi_param {
+    (
+        arrays: $($array_id:ident => $array_elem_id:ident),*;
+        maps: $($map_id:ident => $map_value_id:ident),*;
+    ) => {
+        /// Coerce the json value to the param of the abi param type.
+        fn json_to_abi_param(
+            param_ty: &ABIParamType,
+            value: &Value,
+            strict_numbers: bool,
+        ) -> Result<ABIParam, AbiError> {
+            let param = match param_ty {
+                $(ABIParamType::$array_id => {
+                    let items = value.as_array().ok_or_else(|| json_type_error(param_ty, value))?;
+                    let mut result = Vec::with_capacity(items.len());
+                    for (index, item) in items.iter().enumerate() {
+                        match json_scalar_to_abi_param(&ABIParamType::$array_elem_id, item, strict_numbers) {
+                            Ok(ABIParam::$array_elem_id(elem)) => result.push(elem),
+                            Ok(_) => unreachable!("the scalar param type is not coerced"),
+                            Err(error) => {
+                                return Err(AbiError::ArrayElement {
+                                    ty: param_ty.canonical_string(),
+                                    index,
+                                    error: Box::new(error),
+                                })
+                            }
+                        }
+                    }
+                    ABIParam::$array_id(result)
+                })*
+                $(ABIParamType::$map_id => {
+                    let entries = value.as_object().ok_or_else(|| json_type_error(param_ty, value))?;
+                    let mut result = std::collections::BTreeMap::new();
+                    for (key, value) in entries {
+                        match json_scalar_to_abi_param(&ABIParamType::$map_value_id, value, strict_numbers)? {
+                            ABIParam::$map_value_id(value) => result.insert(key.clone(), value),
+                            _ => unreachable!("the scalar param type is not coerced"),
+                        };
+                    }
+                    ABIParam::$map_id(result)
+                })*
+                ABIParamType::Variant(variant_tys) => {
+                    let malformed = || AbiError::MalformedVariant(value.to_string());
+                    let tag = value
+                        .get("tag")
+                        .and_then(Value::as_u64)
+                        .and_then(|tag| u32::try_from(tag).ok())
+                        .ok_or_else(malformed)?;
+                    let variant_value = value.get("value").ok_or_else(malformed)?;
+                    let variant_ty =
+                        variant_tys
+                            .get(tag as usize)
+                            .ok_or(AbiError::InvalidVariantTag {
+                                tag,
+                                count: variant_tys.len(),
+                            })?;
+                    ABIParam::Variant {
+                        tag,
+                        value: Box::new(json_to_abi_param(variant_ty, variant_value, strict_numbers)?),
+                    }
+                }
+                ABIParamType::FixedArray(array_ty, len) => {
+                    ABIParam::fixed_array(json_to_abi_param(array_ty, value, strict_numbers)?, *len)?
+                }
+                _ => json_scalar_to_abi_param(param_ty, value, strict_numbers)?,
+            };
+            Ok(param)
+        }
+    };
+}
+
+impl_json_to_abi_param!(
+    arrays: U8Array => U8, I8Array => I8, U16Array => U16, I16Array => I16, U32Array => U32,
+        I32Array => I32, U64Array => U64, I64Array => I64, U128Array => U128,
+        I128Array => I128, BoolArray => Bool, StrArray => Str, F64Array => F64;
+    maps: StrU8Map => U8, StrI8Map => I8, StrU16Map => U16, StrI16Map => I16,
+        StrU32Map => U32, StrI32Map => I32, StrU64Map => U64, StrI64Map => I64,
+        StrU128Map => U128, StrI128Map => I128, StrBoolMap => Bool, StrStrMap => Str;
+);
+
+/// The max magnitude of the integers which are exact in the `f64` json numbers.
+const MAX_SAFE_JSON_INT: u128 = 1 << 53;
+
+/// Whether the json number is an integer within `MAX_SAFE_JSON_INT`.
+fn is_safe_json_int(n: &Number) -> bool {
+    match (n.as_u64(), n.as_i64()) {
+        (Some(v), _) => v as u128 <= MAX_SAFE_JSON_INT,
+        (None, Some(v)) => v.unsigned_abs() as u128 <= MAX_SAFE_JSON_INT,
+        // The floats such as `1e30`.
+        (None, None) => false,
+    }
+}
+
+fn uint_to_json(v: u128) -> Value {
+    if v <= MAX_SAFE_JSON_INT {
+        Value::from(v as u64)
+    } else {
+        Value::String(v.to_string())
+    }
+}
+
+fn int_to_json(v: i128) -> Value {
+    if v.unsigned_abs() <= MAX_SAFE_JSON_INT {
+        Value::from(v as i64)
+    } else {
+        Value::String(v.to_string())
+    }
+}
+
+/// The non-finite floats have no json number, they are rendered as strings such as `NaN`.
+fn float_to_json(v: f64) -> Value {
+    Number::from_f64(v)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(v.to_string()))
+}
+
+macro_rules! array_to_json {
+    ($v:expr, $to_json:expr) => {
+        Value::Array($v.iter().map($to_json).collect())
+    };
+}
+
+macro_rules! map_to_json {
+    ($v:expr, $to_json:expr) => {
+        Value::Object(
+            $v.iter()
+                .map(|(key, value)| (key.clone(), $to_json(value)))
+                .collect::<Map<String, Value>>(),
+        )
+    };
+}
+
+/// Render the decoded param as the json value accepted by `encode_params_json`.
+fn abi_param_to_json(param: &ABIParam) -> Value {
+    match param {
+        ABIParam::U8(v) => uint_to_json(*v as u128),
+        ABIParam::I8(v) => int_to_json(*v as i128),
+        ABIParam::U16(v) => uint_to_json(*v as u128),
+        ABIParam::I16(v) => int_to_json(*v as i128),
+        ABIParam::U32(v) => uint_to_json(*v as u128),
+        ABIParam::I32(v) => int_to_json(*v as i128),
+        ABIParam::U64(v) => uint_to_json(*v as u128),
+        ABIParam::I64(v) => int_to_json(*v as i128),
+        ABIParam::U128(v) => uint_to_json(*v),
+        ABIParam::I128(v) => int_to_json(*v),
+        // Widen by the shortest literal, e.g., `1.1f32` to `1.1` rather than `1.100000023841858`.
+        ABIParam::F32(v) => float_to_json(v.to_string().parse().unwrap_or(*v as f64)),
+        ABIParam::F64(v) => float_to_json(*v),
+        ABIParam::Bool(v) => Value::Bool(*v),
+        ABIParam::Str(v) => Value::String(v.clone()),
+        ABIParam::Parampack(v) | ABIParam::Bytes(v) => Value::String(encode_hex_str(v, true)),
+        ABIParam::U8Array(v) => array_to_json!(v, |e| uint_to_json(*e as u128)),
+        ABIParam::I8Array(v) => array_to_json!(v, |e| int_to_json(*e as i128)),
+        ABIParam::U16Array(v) => array_to_json!(v, |e| uint_to_json(*e as u128)),
+        ABIParam::I16Array(v) => array_to_json!(v, |e| int_to_json(*e as i128)),
+        ABIParam::U32Array(v) => array_to_json!(v, |e| uint_to_json(*e as u128)),
+        ABIParam::I32Array(v) => array_to_json!(v, |e| int_to_json(*e as i128)),
+        ABIParam::U64Array(v) => array_to_json!(v, |e| uint_to_json(*e as u128)),
+        ABIParam::I64Array(v) => array_to_json!(v, |e| int_to_json(*e as i128)),
+        ABIParam::U128Array(v) => array_to_json!(v, |e| uint_to_json(*e)),
+        ABIParam::I128Array(v) => array_to_json!(v, |e| int_to_json(*e)),
+        ABIParam::BoolArray(v) => array_to_json!(v, |e| Value::Bool(*e)),
+        ABIParam::StrArray(v) => array_to_json!(v, |e| Value::String(e.clone())),
+        ABIParam::F64Array(v) => array_to_json!(v, |e| float_to_json(*e)),
+        ABIParam::StrU8Map(v) => map_to_json!(v, |e: &u8| uint_to_json(*e as u128)),
+        ABIParam::StrI8Map(v) => map_to_json!(v, |e: &i8| int_to_json(*e as i128)),
+        ABIParam::StrU16Map(v) => map_to_json!(v, |e: &u16| uint_to_json(*e as u128)),
+        ABIParam::StrI16Map(v) => map_to_json!(v, |e: &i16| int_to_json(*e as i128)),
+        ABIParam::StrU32Map(v) => map_to_json!(v, |e: &u32| uint_to_json(*e as u128)),
+        ABIParam::StrI32Map(v) => map_to_json!(v, |e: &i32| int_to_json(*e as i128)),
+        ABIParam::StrU64Map(v) => map_to_json!(v, |e: &u64| uint_to_json(*e as u128)),
+        ABIParam::StrI64Map(v) => map_to_json!(v, |e: &i64| int_to_json(*e as i128)),
+        ABIParam::StrU128Map(v) => map_to_json!(v, |e: &u128| uint_to_json(*e)),
+        ABIParam::StrI128Map(v) => map_to_json!(v, |e: &i128| int_to_json(*e)),
+        ABIParam::StrBoolMap(v) => map_to_json!(v, |e: &bool| Value::Bool(*e)),
+        ABIParam::StrStrMap(v) => map_to_json!(v, |e: &String| Value::String(e.clone())),
+        ABIParam::Timestamp(v) => int_to_json(*v as i128),
+        ABIParam::Duration(v) => uint_to_json(*v as u128),
+        ABIParam::Variant { tag, value } => serde_json::json!({
+            "tag": tag,
+            "value": abi_param_to_json(value),
+        }),
+        ABIParam::FixedArray(array) => abi_param_to_json(array),
+        ABIParam::Address { bytes, .. } => Value::String(encode_hex_str(bytes, true)),
+        // `ABIParam` is non-exhaustive, render the params added later in the readable form
+        // until they have their json form.
+        param => Value::String(param.to_readable()),
+    }
+}
+
+impl IRContractMethodMeta {
+    /// Encode the params given as a json object keyed by the input names in the declaration
+    /// order, e.g., `{"to": "alice", "amounts": [1, 2]}`. All the inputs must be named, and
+    /// the missing and unknown keys are errors. The errors of the param values are wrapped
+    /// in `AbiError::Param` with the input index.
+    pub fn encode_params_json(&self, obj: &Value) -> Result<Vec<u8>, AbiError> {
+        self.encode_params_json_with_numbers(obj, false)
+    }
+
+    /// The same as `encode_params_json`, but in the strict numeric mode with
+    /// `strict_numbers`, the integers out of `-2^53..=2^53` must be given as json strings
+    /// such as `"340282366920938463463374607431768211455"`, and the bare numbers are
+    /// rejected with `AbiError::UnsafeJsonNumber`. It guards against the web clients
+    /// which have rounded the big integers to `f64` numbers before sending them.
+    pub fn encode_params_json_with_numbers(
+        &self,
+        obj: &Value,
+        strict_numbers: bool,
+    ) -> Result<Vec<u8>, AbiError> {
+        let params = obj.as_object().ok_or_else(|| AbiError::JsonType {
+            ty: "params object".to_string(),
+            value: obj.to_string(),
+        })?;
+        if let Some(index) = self.inputs.iter().position(|input| input.name.is_empty()) {
+            return Err(AbiError::UnnamedParam(index));
+        }
+        if let Some(name) = params
+            .keys()
+            .find(|name| !self.inputs.iter().any(|input| &input.name == *name))
+        {
+            return Err(AbiError::UnknownParam(name.clone()));
+        }
+        let input_param_types = self.input_param_types()?;
+        let mut abi_input_params: Vec<ABIParam> = Vec::with_capacity(self.inputs.len());
+        for (index, (input_meta, param_ty)) in
+            self.inputs.iter().zip(&input_param_types).enumerate()
+        {
+            let value = params
+                .get(&input_meta.name)
+                .ok_or_else(|| AbiError::MissingParam(input_meta.name.clone()))?;
+            let param = json_to_abi_param(param_ty, value, strict_numbers).map_err(|error| {
+                AbiError::Param {
+                    index,
+                    name: input_meta.name.clone(),
+                    error: Box::new(error),
+                }
+            })?;
+            abi_input_params.push(param);
+        }
+        Ok(encode_abi_params(
+            &abi_input_params,
+            EncodingOptions::default(),
+        ))
+    }
+
+    /// Decode the params encoded by `encode_params` to a json object keyed by the input
+    /// names, which can be encoded back by `encode_params_json`.
+    pub fn decode_params_json(&self, bytes: &[u8]) -> Result<Value, AbiError> {
+        if let Some(index) = self.inputs.iter().position(|input| input.name.is_empty()) {
+            return Err(AbiError::UnnamedParam(index));
+        }
+        let params = self.decode_params(bytes)?;
+        Ok(Value::Object(
+            self.inputs
+                .iter()
+                .zip(&params)
+                .map(|(input_meta, param)| (input_meta.name.clone(), abi_param_to_json(param)))
+                .collect(),
+        ))
+    }
+}
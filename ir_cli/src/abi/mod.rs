@@ -2,16 +2,85 @@
 // Copyright (c) The Smart Intermediate Representation Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use smart_ir::abi::params::ABIParam;
-use smart_ir::ir::cfg::Contract;
-use std::collections::HashMap;
+pub mod builder;
+pub mod diff;
+pub mod eth;
+pub mod explain;
+pub mod frame;
+pub mod fuzz;
+pub mod json;
+pub mod normalize;
+pub mod registry;
+pub mod schema;
+pub mod selector;
+
+use bincode::Options;
+use keccak_hash::keccak;
+use num_traits::{Bounded, Num};
+use smart_ir::abi::address::AddressChecksum;
+use smart_ir::abi::error::AbiError;
+use smart_ir::abi::params::{
+    check_data_stream_version, decode_abi_param, decode_abi_param_with_limits, decode_hex_str,
+    ABIParam, DecodeLimits, EncodingOptions, EncodingVersion, Endianness, MAX_TYPE_REF_DEPTH,
+};
+pub use smart_ir::abi::types::normalize_type_name;
+use smart_ir::abi::types::{split_top_level, variant_type_names, ABIParamType, ABITypeTable};
+use smart_ir::encoding::datastream::ParamType;
+use smart_ir::ir::cfg::{BuiltinType, Contract, IntLiteral, Literal, Type};
+use smart_ir::ir::context::IRContext;
+use smart_ir::ir::frontend::parser::try_compile;
+use smart_ir::ir::frontend::translate::translate_main_module;
+use smart_ir::ir::metadata::abi_descriptions::AbiDescriptions;
+use smart_ir::ir::metadata::abi_indexed_params::AbiIndexedParams;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+use std::num::{IntErrorKind, ParseIntError};
+use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// The version of the abi json format, which is unrelated to the leading version byte
+/// `EncodingVersion` of the encoded params. Versions 0 and 1 only have the methods with
+/// their names, types, input names and types and output types, see `ABI_VERSION_2` for
+/// the fields added later.
+pub const CURRENT_IR_ABI_VERSION: u16 = 2;
+/// The abi version which adds the contract `constants`, `errors`, `name`, `version` and
+/// `types`, the method `qualified_name`, `description` and `inherited_from`, and the
+/// input `indexed` and `description`.
+pub const ABI_VERSION_2: u16 = 2;
+/// The method type of the contract constructor.
+pub const METHOD_TYPE_CONSTRUCTOR: &str = "constructor";
+/// The method type of the normal contract function.
+pub const METHOD_TYPE_FUNCTION: &str = "function";
 
-pub const CURRENT_IR_ABI_VERSION: u16 = 1;
+/// Serialize a meta struct with the `required` fields and then the `optional` fields, the
+/// optional ones are skipped by the human-readable formats such as json and yaml when
+/// they are empty, so that the metas without the fields of the newer abi versions are
+/// serialized in the shape of the older versions. The binary CBOR and bincode keep every
+/// field, because bincode is not self-describing and can't decode the skipped fields.
+macro_rules! serialize_meta {
+    ($serializer:expr, $name:literal, [$($key:literal => $value:expr),*],
+     [$($opt_key:literal => $opt_value:expr, $is_empty:expr),*]) => {{
+        use serde::ser::SerializeStruct;
+        let human_readable = $serializer.is_human_readable();
+        let mut len = [$($key),*].len();
+        $(len += usize::from(!(human_readable && $is_empty));)*
+        let mut state = $serializer.serialize_struct($name, len)?;
+        $(state.serialize_field($key, $value)?;)*
+        $(
+            if human_readable && $is_empty {
+                state.skip_field($opt_key)?;
+            } else {
+                state.serialize_field($opt_key, $opt_value)?;
+            }
+        )*
+        state.end()
+    }};
+}
 
 /// The contract meta information for app, including the meta info of the contract,
 /// generated by the ir compiler
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Default, Clone, serde::Deserialize)]
 pub struct IRContractABIMeta {
     /// The version information of ContractABIMeta, because the structure is generated by
     /// the compiler, may add fields, so you need to select the corresponding schema
@@ -20,14 +89,65 @@ pub struct IRContractABIMeta {
     /// A list of functions in the contract that can be called by transactions and
     /// triggered by special scenarios.
     pub methods: Vec<IRContractMethodMeta>,
+    /// Compile-time constants of the contract, older abi files without this field
+    /// are decoded with an empty list.
+    #[serde(default)]
+    pub constants: Vec<IRConstantMeta>,
+    /// Custom error types which can be carried by the contract revert data.
+    #[serde(default)]
+    pub errors: Vec<IRContractErrorMeta>,
+    /// The contract name, which tells apart the abi metas in a registry, older abi files
+    /// without this field are decoded with `None`.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The contract version, the textual ir has no contract version declaration yet, so
+    /// it is only set by the tools.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// The composite type names referenced by the method inputs and outputs such as `#0`
+    /// in the normalized form produced by `normalize`, it is empty for the denormalized
+    /// abi metas and the older abi files.
+    #[serde(default)]
+    pub types: Vec<String>,
+    /// The lazily built method name to the first method position index used by `get_method`,
+    /// it is not serialized and will be rebuilt after the deserialization.
+    #[serde(skip)]
+    pub method_index: MetaCache<HashMap<String, usize>>,
 }
 
-impl Default for IRContractABIMeta {
-    fn default() -> IRContractABIMeta {
-        IRContractABIMeta {
-            abi_version: 0,
-            methods: Vec::new(),
-        }
+impl serde::Serialize for IRContractABIMeta {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_meta!(
+            serializer,
+            "IRContractABIMeta",
+            ["abi_version" => &self.abi_version, "methods" => &self.methods],
+            [
+                "constants" => &self.constants, self.constants.is_empty(),
+                "errors" => &self.errors, self.errors.is_empty(),
+                "name" => &self.name, self.name.is_none(),
+                "version" => &self.version, self.version.is_none(),
+                "types" => &self.types, self.types.is_empty()
+            ]
+        )
+    }
+}
+
+/// A lazily built cache of the data derived from an abi meta such as the method index of
+/// `get_method`, which is empty in `Default::default()`, so that the abi metas can still
+/// be built by the struct literals with `..Default::default()`. It is `Sync`, so that one
+/// abi meta can be shared across threads.
+#[derive(Debug, Clone)]
+pub struct MetaCache<T>(OnceLock<T>);
+
+impl<T> Default for MetaCache<T> {
+    fn default() -> MetaCache<T> {
+        MetaCache(OnceLock::new())
+    }
+}
+
+impl<T> MetaCache<T> {
+    fn get_or_init(&self, init: impl FnOnce() -> T) -> &T {
+        self.0.get_or_init(init)
     }
 }
 
@@ -39,14 +159,275 @@ impl IRContractABIMeta {
             .into_bytes()
     }
 
+    /// Serialize the abi meta to the canonical pretty json for committing the abi files,
+    /// so that regenerating an unchanged abi produces no diff. Unlike `to_json`, which
+    /// keeps the struct field order and the method declaration order, the object keys are
+    /// sorted, the methods are sorted by `signature`, the errors by name and the constants
+    /// by type and data, and the json ends with a newline. The method inputs stay in the
+    /// positional order and the `types` table in the id order, and the map constants are
+    /// already encoded in the key order. It is decoded by `from_json` like `to_json`.
+    pub fn to_json_canonical(&self) -> Vec<u8> {
+        let mut abi = self.clone();
+        abi.methods.sort_by_cached_key(|method| method.signature());
+        abi.errors
+            .sort_by(|left, right| (&left.name, left.code).cmp(&(&right.name, right.code)));
+        abi.constants
+            .sort_by(|left, right| (&left.r#type, &left.data).cmp(&(&right.r#type, &right.data)));
+        // The objects of `serde_json::Value` are key-ordered maps.
+        let value = serde_json::to_value(&abi)
+            .map_err(|e| anyhow::anyhow!("could not serialize to json: {}", e))
+            .unwrap();
+        let mut json = serde_json::to_string_pretty(&value)
+            .map_err(|e| anyhow::anyhow!("could not serialize to json: {}", e))
+            .unwrap();
+        json.push('\n');
+        json.into_bytes()
+    }
+
     pub fn from_json(json_bytes: &[u8]) -> IRContractABIMeta {
         serde_json::from_slice(json_bytes).unwrap()
     }
 
+    /// Deserialize the abi json like `from_json`, but reject the fields unknown to the
+    /// `json_schema` of the `CURRENT_IR_ABI_VERSION` such as a misspelled `methdos`,
+    /// which `from_json` ignores to be forward compatible with the newer abi files.
+    pub fn from_json_strict(json_bytes: &[u8]) -> Result<IRContractABIMeta, anyhow::Error> {
+        let value: serde_json::Value = serde_json::from_slice(json_bytes)
+            .map_err(|e| anyhow::anyhow!("could not deserialize from json: {}", e))?;
+        if let Some(path) = schema::unknown_field_path(&value) {
+            return Err(anyhow::anyhow!("unknown field {}", path));
+        }
+        serde_json::from_value(value)
+            .map_err(|e| anyhow::anyhow!("could not deserialize from json: {}", e))
+    }
+
+    /// Serialize the abi meta to yaml, the field names are the same as the json.
+    pub fn to_yaml(&self) -> Result<String, anyhow::Error> {
+        serde_yaml::to_string(self)
+            .map_err(|e| anyhow::anyhow!("could not serialize to yaml: {}", e))
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<IRContractABIMeta, anyhow::Error> {
+        serde_yaml::from_str(yaml)
+            .map_err(|e| anyhow::anyhow!("could not deserialize from yaml: {}", e))
+    }
+
+    /// Serialize the abi meta to the compact CBOR bytes, the field semantics are the same
+    /// as the json, and `abi_version` is always the first field of the CBOR map, so that
+    /// readers can branch on the version before fully decoding.
+    pub fn to_cbor(&self) -> Result<Vec<u8>, anyhow::Error> {
+        let mut buf: Vec<u8> = vec![];
+        ciborium::ser::into_writer(self, &mut buf)
+            .map_err(|e| anyhow::anyhow!("could not serialize to cbor: {}", e))?;
+        Ok(buf)
+    }
+
+    pub fn from_cbor(cbor_bytes: &[u8]) -> Result<IRContractABIMeta, anyhow::Error> {
+        ciborium::de::from_reader(cbor_bytes)
+            .map_err(|e| anyhow::anyhow!("could not deserialize from cbor: {}", e))
+    }
+
+    /// Serialize the abi meta to bincode bytes with a pinned configuration (fixed-int
+    /// encoding, little-endian), so that the bytes are reproducible across machines.
+    /// Note that the bincode format is not self-describing, the caller must check
+    /// the `abi_version` before using the bytes produced by another version.
+    pub fn to_bincode(&self) -> Result<Vec<u8>, anyhow::Error> {
+        bincode_options()
+            .serialize(self)
+            .map_err(|e| anyhow::anyhow!("could not serialize to bincode: {}", e))
+    }
+
+    pub fn from_bincode(bincode_bytes: &[u8]) -> Result<IRContractABIMeta, anyhow::Error> {
+        bincode_options()
+            .deserialize(bincode_bytes)
+            .map_err(|e| anyhow::anyhow!("could not deserialize from bincode: {}", e))
+    }
+
     pub fn get_method(&self, abi_method_name: &str) -> Option<&IRContractMethodMeta> {
-        self.methods.iter().find(|&m| m.name == abi_method_name)
+        let method_index = self.method_index.get_or_init(|| {
+            let mut method_index = HashMap::with_capacity(self.methods.len());
+            for (i, m) in self.methods.iter().enumerate() {
+                method_index.entry(m.name.clone()).or_insert(i);
+            }
+            method_index
+        });
+        match method_index
+            .get(abi_method_name)
+            .and_then(|&i| self.methods.get(i))
+        {
+            Some(m) if m.name == abi_method_name => Some(m),
+            // The methods have been modified after the index is built, fallback to the
+            // linear search.
+            _ => self.methods.iter().find(|&m| m.name == abi_method_name),
+        }
+    }
+
+    /// Get the method by the name case-insensitively for the user-facing dispatch, e.g.,
+    /// `Transfer` matches `transfer`. The overloaded methods are resolved to the first one
+    /// in the same way as `get_method`, and it is an error when the methods of different
+    /// names collide under the case folding such as `transfer` and `Transfer`, even if one
+    /// of them matches exactly.
+    pub fn get_method_ci(
+        &self,
+        abi_method_name: &str,
+    ) -> Result<Option<&IRContractMethodMeta>, String> {
+        let folded_name = abi_method_name.to_lowercase();
+        let mut matched: Option<&IRContractMethodMeta> = None;
+        for method in self
+            .methods
+            .iter()
+            .filter(|m| m.name.to_lowercase() == folded_name)
+        {
+            match matched {
+                Some(first) if first.name != method.name => {
+                    return Err(format!(
+                        "method name {abi_method_name} is ambiguous, it matches {} and {}",
+                        first.name, method.name
+                    ));
+                }
+                Some(_) => {}
+                None => matched = Some(method),
+            }
+        }
+        Ok(matched)
+    }
+
+    /// Validate the abi meta, e.g., the input names of each method are unique and
+    /// there is at most one constructor.
+    pub fn validate(&self) -> Result<(), String> {
+        for method in &self.methods {
+            method.validate()?;
+        }
+        let constructors: Vec<String> = self
+            .methods
+            .iter()
+            .filter(|m| m.r#type == METHOD_TYPE_CONSTRUCTOR)
+            .map(|m| m.signature())
+            .collect();
+        if constructors.len() > 1 {
+            return Err(format!(
+                "expect at most one constructor, but got {}: {}",
+                constructors.len(),
+                constructors.join(", ")
+            ));
+        }
+        Ok(())
     }
 
+    /// Get all the methods named `abi_method_name`, which are more than one for
+    /// overloaded functions.
+    pub fn get_methods(&self, abi_method_name: &str) -> Vec<&IRContractMethodMeta> {
+        self.methods
+            .iter()
+            .filter(|m| m.name == abi_method_name)
+            .collect()
+    }
+
+    /// Whether the abi meta has no `function` methods, i.e., the contract is uncallable by
+    /// the transactions after the deployment even if it has a constructor, which usually
+    /// means that the compilation produced no callable surface.
+    pub fn is_empty(&self) -> bool {
+        self.functions().next().is_none()
+    }
+
+    /// Iterate over the `function` methods, the constructor is skipped.
+    pub fn functions(&self) -> impl Iterator<Item = &IRContractMethodMeta> {
+        self.methods
+            .iter()
+            .filter(|m| m.r#type == METHOD_TYPE_FUNCTION)
+    }
+
+    /// Get the constructor method, which is the first one if the abi meta is invalid and
+    /// has more than one constructor.
+    pub fn constructor(&self) -> Option<&IRContractMethodMeta> {
+        self.methods
+            .iter()
+            .find(|m| m.r#type == METHOD_TYPE_CONSTRUCTOR)
+    }
+
+    /// Get the method by the fully-qualified name such as `Token.a.transfer`, which is used
+    /// when more than one module defines a method with the same short name.
+    pub fn get_method_by_qualified_name(
+        &self,
+        qualified_name: &str,
+    ) -> Option<&IRContractMethodMeta> {
+        self.methods
+            .iter()
+            .find(|m| m.qualified_name == qualified_name)
+    }
+
+    /// Get the method matching both the name and the input type list, it is used to
+    /// tell the overloaded functions apart.
+    pub fn get_method_by_signature(
+        &self,
+        abi_method_name: &str,
+        input_types: &[&str],
+    ) -> Option<&IRContractMethodMeta> {
+        self.methods.iter().find(|m| {
+            m.name == abi_method_name
+                && m.inputs.len() == input_types.len()
+                && m.inputs
+                    .iter()
+                    .zip(input_types)
+                    .all(|(input, ty)| input.r#type == *ty)
+        })
+    }
+
+    /// All the distinct normalized type names used by the method inputs and outputs,
+    /// including the inner type names of composite types, e.g., `{string:u64}` also
+    /// references `string` and `u64`.
+    pub fn referenced_types(&self) -> BTreeSet<String> {
+        let mut type_names = BTreeSet::new();
+        for method in &self.methods {
+            let input_types = method.inputs.iter().map(|input| &input.r#type);
+            let output_types = method.outputs.iter().map(|output| &output.r#type);
+            for type_name in input_types.chain(output_types) {
+                collect_type_names(&normalize_type_name(type_name), &mut type_names);
+            }
+        }
+        type_names
+    }
+
+    /// The canonical bytes of the abi interface, which are the lines of the sorted method
+    /// signatures with their output types such as `transfer(string,u64)->(bool)`, after
+    /// the `name:` and `version:` lines of the contract name and version when they are set.
+    /// The type names are normalized and the method declaration order is ignored. The
+    /// bytes are built by hand rather than by serde, so that they are stable across the
+    /// serde library versions. It is a commitment format to be hashed and anchored on
+    /// chain, not for the round-trip decoding: the input names, the constants and the
+    /// errors are not included.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut lines: Vec<String> = self
+            .methods
+            .iter()
+            .map(|method| {
+                let output_types: Vec<String> = method
+                    .outputs
+                    .iter()
+                    .map(|output| normalize_type_name(&output.r#type))
+                    .collect();
+                format!("{}->({})", method.signature(), output_types.join(","))
+            })
+            .collect();
+        lines.sort();
+        if let Some(version) = &self.version {
+            lines.insert(0, format!("version:{version}"));
+        }
+        if let Some(name) = &self.name {
+            lines.insert(0, format!("name:{name}"));
+        }
+        lines.join("\n").into_bytes()
+    }
+
+    /// The keccak hash of the `canonical_bytes`, so that two abi metas with the same
+    /// interface have the same fingerprint regardless of the method declaration order.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        keccak(self.canonical_bytes()).0
+    }
+
+    /// Generate the abi meta of the contract functions in the declaration order. A contract
+    /// without functions is not an error, it gives the abi meta of the current abi version
+    /// without methods, which `is_empty` tells.
     pub fn from_contract(contract: &Contract) -> IRContractABIMeta {
         let mut methods: Vec<IRContractMethodMeta> = vec![];
         // get methods
@@ -55,13 +436,15 @@ impl IRContractABIMeta {
             for p in &func_def.params {
                 inputs.push(IRContractMethodInputMeta {
                     name: "".to_string(),
-                    r#type: p.to_string(),
+                    r#type: normalize_type_name(&p.to_string()),
+                    indexed: false,
+                    description: None,
                 });
             }
             let mut outputs: Vec<IRContractMethodOutputMeta> = vec![];
             if !func_def.ret.is_void() {
                 outputs.push(IRContractMethodOutputMeta {
-                    r#type: func_def.ret.to_string(),
+                    r#type: normalize_type_name(&func_def.ret.to_string()),
                 });
             }
             let abi_name = if let Some(last_dot_pos) = func_name.rfind('.') {
@@ -72,17 +455,230 @@ impl IRContractABIMeta {
             methods.push(IRContractMethodMeta {
                 name: abi_name.to_string(),
                 r#type: if abi_name == "init" {
-                    "constructor".to_string()
+                    METHOD_TYPE_CONSTRUCTOR.to_string()
                 } else {
-                    "function".to_string()
+                    METHOD_TYPE_FUNCTION.to_string()
                 },
                 inputs,
                 outputs,
+                qualified_name: func_name.clone(),
+                description: None,
+                inherited_from: None,
+                param_types: MetaCache::default(),
             });
         }
         IRContractABIMeta {
             abi_version: CURRENT_IR_ABI_VERSION,
             methods,
+            // The textual ir has no contract level constant declarations yet, so there
+            // is nothing to collect here, use `IRConstantMeta::from_literal` to fill it.
+            constants: vec![],
+            // The same as constants, there are no error type declarations in the ir.
+            errors: vec![],
+            name: Some(contract.name.clone()),
+            version: None,
+            types: vec![],
+            method_index: MetaCache::default(),
+        }
+    }
+
+    /// The same as `from_contract`, but also reads the function metadata of the contract
+    /// in the context, such as the indexed inputs marked by `ir_abi_indexed_params` and
+    /// the method and input descriptions of `ir_abi_descriptions`.
+    pub fn from_contract_in_context(ctx: &IRContext, contract: &Contract) -> IRContractABIMeta {
+        let mut abi = IRContractABIMeta::from_contract(contract);
+        abi.read_function_metadata(ctx, contract);
+        abi
+    }
+
+    /// The same as `from_contract`, but also includes the methods of the imported contracts
+    /// such as the interfaces, flagged by `inherited_from` with the imported contract name.
+    /// The constructors of the imports are not inherited. The methods with the same
+    /// `signature` in the contract and the imports, or in two imports, are collisions, e.g.,
+    /// `method transfer(string,u64) of Base collides with Token`.
+    pub fn from_contract_with_imports(
+        contract: &Contract,
+        imports: &[&Contract],
+    ) -> Result<IRContractABIMeta, String> {
+        let mut abi = IRContractABIMeta::from_contract(contract);
+        // The contract name of every method signature for the collision errors.
+        let mut sources: HashMap<String, String> = abi
+            .methods
+            .iter()
+            .map(|method| (method.signature(), contract.name.clone()))
+            .collect();
+        for import in imports {
+            for mut method in IRContractABIMeta::from_contract(import).methods {
+                if method.r#type == METHOD_TYPE_CONSTRUCTOR {
+                    continue;
+                }
+                let signature = method.signature();
+                if let Some(source) = sources.get(&signature) {
+                    return Err(format!(
+                        "method {signature} of {} collides with {source}",
+                        import.name
+                    ));
+                }
+                sources.insert(signature, import.name.clone());
+                method.inherited_from = Some(import.name.clone());
+                abi.methods.push(method);
+            }
+        }
+        Ok(abi)
+    }
+
+    fn read_function_metadata(&mut self, ctx: &IRContext, contract: &Contract) {
+        for method in &mut self.methods {
+            let func_def = match contract.functions.get(&method.qualified_name) {
+                Some(func_def) => func_def,
+                None => continue,
+            };
+            if let Some(indexed_params) = AbiIndexedParams::get_from_context(ctx, func_def) {
+                for index in indexed_params.get_indexes() {
+                    if let Some(input) = method.inputs.get_mut(*index as usize) {
+                        input.indexed = true;
+                    }
+                }
+            }
+            if let Some(descriptions) = AbiDescriptions::get_from_context(ctx, func_def) {
+                method.description = non_empty(descriptions.get_method());
+                for (input, description) in method.inputs.iter_mut().zip(descriptions.get_params())
+                {
+                    input.description = non_empty(description);
+                }
+            }
+        }
+    }
+
+    /// The same as `from_contract`, but fails fast when some method input or output
+    /// type can't be encoded, the error lists every unencodable method and its types.
+    /// The generated abi meta is also checked by `validate`.
+    pub fn from_contract_checked(contract: &Contract) -> Result<IRContractABIMeta, String> {
+        let abi = IRContractABIMeta::from_contract(contract);
+        let mut errors: Vec<String> = vec![];
+        for method in &abi.methods {
+            if let Err(unsupported_types) = method.is_encodable() {
+                errors.push(format!(
+                    "method {} has unencodable types {}",
+                    method.name,
+                    unsupported_types.join(", ")
+                ));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+        abi.validate()?;
+        Ok(abi)
+    }
+
+    /// Generate the abi meta shaped for an older `version` for the targets which haven't
+    /// upgraded. The fields introduced after `version` are left empty, so they are not
+    /// written to the json and yaml, see `ABI_VERSION_2`. Versions newer than
+    /// `CURRENT_IR_ABI_VERSION` are rejected.
+    pub fn from_contract_with_version(
+        contract: &Contract,
+        version: u16,
+    ) -> Result<IRContractABIMeta, String> {
+        if version > CURRENT_IR_ABI_VERSION {
+            return Err(format!(
+                "abi version {version} is newer than the current abi version {CURRENT_IR_ABI_VERSION}"
+            ));
+        }
+        let mut abi = IRContractABIMeta::from_contract(contract);
+        abi.abi_version = version;
+        if version < ABI_VERSION_2 {
+            abi.constants.clear();
+            abi.errors.clear();
+            abi.name = None;
+            abi.version = None;
+            abi.types.clear();
+            for method in &mut abi.methods {
+                method.qualified_name.clear();
+                method.description = None;
+                method.inherited_from = None;
+                for input in &mut method.inputs {
+                    input.indexed = false;
+                    input.description = None;
+                }
+            }
+        }
+        Ok(abi)
+    }
+
+    /// Upgrade the abi meta decoded from an older version to `CURRENT_IR_ABI_VERSION`
+    /// without re-compiling the contract. The fields introduced after the old version
+    /// are already filled with the serde defaults (empty constants, errors, types and
+    /// qualified method names, and no names or descriptions), so only the version is
+    /// updated. It does nothing when the meta is current or newer.
+    pub fn migrate_to_current(mut self) -> IRContractABIMeta {
+        if self.abi_version < CURRENT_IR_ABI_VERSION {
+            self.abi_version = CURRENT_IR_ABI_VERSION;
+        }
+        self
+    }
+
+    /// Decode the call data produced by `IRContractMethodMeta::encode_call`, the method
+    /// is looked up by the leading 4-byte selector and the rest are its params.
+    pub fn decode_call(
+        &self,
+        bytes: &[u8],
+    ) -> Result<(&IRContractMethodMeta, Vec<ABIParam>), AbiError> {
+        if bytes.len() < 4 {
+            return Err(AbiError::Decode {
+                ty: "call".to_string(),
+                reason: "call data is too short to contain a method selector".to_string(),
+            });
+        }
+        let selector = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        let method = match self.methods.iter().find(|m| m.selector() == selector) {
+            Some(method) => method,
+            None => return Err(AbiError::UnknownSelector(selector)),
+        };
+        Ok((method, method.decode_params(&bytes[4..])?))
+    }
+
+    /// Decode the revert data, which is the little-endian u32 error code followed by
+    /// the error fields encoded in the same way as method params.
+    pub fn decode_error(
+        &self,
+        bytes: &[u8],
+    ) -> Result<(&IRContractErrorMeta, Vec<ABIParam>), String> {
+        if bytes.len() < 4 {
+            return Err("revert data is too short to contain an error code".to_string());
+        }
+        let code = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let error = match self.errors.iter().find(|e| e.code == code) {
+            Some(error) => error,
+            None => return Err(format!("unknown error code {code}")),
+        };
+        let mut offset = 4;
+        let mut fields: Vec<ABIParam> = vec![];
+        for field in &error.fields {
+            let param_ty = type_name_to_abi_param_type(&field.r#type)?;
+            let param = decode_abi_param(&param_ty, bytes, &mut offset)
+                .map_err(|err| format!("decode error {} field: {err}", error.name))?;
+            fields.push(param);
+        }
+        Ok((error, fields))
+    }
+
+    /// Compile the textual ir source and generate the abi meta of its main contract.
+    pub fn from_ir_source(src: &str) -> Result<IRContractABIMeta, String> {
+        let module = try_compile(src).map_err(|err| format!("failed to parse ir: {err}"))?;
+        let mut ctx = IRContext::default();
+        translate_main_module(&mut ctx, &module);
+        let main_module = match ctx.get_main_module() {
+            Some(main_module) => main_module,
+            None => return Err("main module not found".to_string()),
+        };
+        match &main_module.contract {
+            Some(main_contract) => {
+                let mut abi = IRContractABIMeta::from_contract_checked(main_contract)?;
+                abi.read_function_metadata(&ctx, main_contract);
+                Ok(abi)
+            }
+            None => Err(format!("no contract found in module {}", main_module.name)),
         }
     }
 }
@@ -96,15 +692,96 @@ pub struct IRConstantMeta {
     pub readable: String, // formatted constant value
 }
 
-/// meta info of contract method input/param
+impl IRConstantMeta {
+    /// Build the constant meta from an ir literal, `data` is the hex of the abi
+    /// param encoding of the value and `readable` is the formatted value.
+    pub fn from_literal(literal: &Literal) -> IRConstantMeta {
+        let (param, readable) = match literal {
+            Literal::Str(v) => (ABIParam::Str(v.clone()), format!("{v:?}")),
+            Literal::Bool(v) => (ABIParam::Bool(*v), v.to_string()),
+            Literal::Int(int_literal) => (
+                match int_literal {
+                    IntLiteral::I8(v) => ABIParam::I8(*v),
+                    IntLiteral::I16(v) => ABIParam::I16(*v),
+                    IntLiteral::I32(v) => ABIParam::I32(*v),
+                    IntLiteral::I64(v) => ABIParam::I64(*v),
+                    IntLiteral::I128(v) => ABIParam::I128(*v),
+                    IntLiteral::U8(v) => ABIParam::U8(*v),
+                    IntLiteral::U16(v) => ABIParam::U16(*v),
+                    IntLiteral::U32(v) => ABIParam::U32(*v),
+                    IntLiteral::U64(v) => ABIParam::U64(*v),
+                    IntLiteral::U128(v) => ABIParam::U128(*v),
+                },
+                literal.to_string(),
+            ),
+        };
+        IRConstantMeta {
+            r#type: literal.literal_type().to_string(),
+            data: param.to_hex(),
+            readable,
+        }
+    }
+}
+
+/// The pinned bincode configuration of the abi meta.
+fn bincode_options() -> impl bincode::Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_little_endian()
+        .reject_trailing_bytes()
+}
+
+/// The metadata description, an empty string means no description.
+fn non_empty(description: &str) -> Option<String> {
+    if description.is_empty() {
+        None
+    } else {
+        Some(description.to_string())
+    }
+}
+
+/// A custom error type of the contract, the revert data starts with `code` and
+/// then carries the `fields`.
 #[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct IRContractErrorMeta {
+    pub name: String,
+    pub code: u32,
+    pub fields: Vec<IRContractMethodInputMeta>,
+}
+
+/// meta info of contract method input/param
+#[derive(Debug, Default, Clone, serde::Deserialize)]
 pub struct IRContractMethodInputMeta {
     pub name: String,
     pub r#type: String,
+    /// Whether the input is indexed for the off-chain filtering, which is set by the
+    /// `ir_abi_indexed_params` function metadata. Older abi files without this field
+    /// are decoded with `false`.
+    #[serde(default)]
+    pub indexed: bool,
+    /// The description of the input for the client docs, which is set by the
+    /// `ir_abi_descriptions` function metadata. Older abi files without this field
+    /// are decoded with `None`.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl serde::Serialize for IRContractMethodInputMeta {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_meta!(
+            serializer,
+            "IRContractMethodInputMeta",
+            ["name" => &self.name, "type" => &self.r#type],
+            [
+                "indexed" => &self.indexed, !self.indexed,
+                "description" => &self.description, self.description.is_none()
+            ]
+        )
+    }
 }
 
 /// A method meta info in the corresponding contract for the transaction to call.
-#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Default, Clone, serde::Deserialize)]
 pub struct IRContractMethodMeta {
     pub name: String,
     /// contract abi type
@@ -120,375 +797,1171 @@ pub struct IRContractMethodMeta {
     /// output: The schema of the return value of the method, used to decipher the
     /// return value, similar to input.
     pub outputs: Vec<IRContractMethodOutputMeta>, // // vector of method return types names
+    /// The fully-qualified ir function name with the module path such as `Token.a.transfer`,
+    /// which tells apart the methods with the same short `name` in different modules.
+    #[serde(default)]
+    pub qualified_name: String,
+    /// The description of the method for the client docs, which is set by the
+    /// `ir_abi_descriptions` function metadata. Older abi files without this field
+    /// are decoded with `None`.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The name of the imported contract the method is inherited from, which is set by
+    /// `from_contract_with_imports`, or `None` for the methods of the contract itself.
+    #[serde(default)]
+    pub inherited_from: Option<String>,
+    /// The lazily parsed input and output param types used by the encoders and decoders,
+    /// they are not serialized and will be rebuilt after the deserialization.
+    #[serde(skip)]
+    pub param_types: MetaCache<MethodParamTypes>,
 }
 
-fn input_type_to_abi_param(input_type_name: &str, param_str: &str) -> Result<ABIParam, String> {
-    match input_type_name {
-        "bool" => Ok(ABIParam::Bool(param_str == "true")),
-        "str" | "string" => Ok(ABIParam::Str(param_str.to_string())),
-        "parampack" => {
-            let bs = hex::decode(param_str);
-            if bs.is_err() {
-                return Err(bs.err().unwrap().to_string());
-            }
-            let bs = bs.unwrap();
-            Ok(ABIParam::Parampack(bs))
+impl serde::Serialize for IRContractMethodMeta {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_meta!(
+            serializer,
+            "IRContractMethodMeta",
+            [
+                "name" => &self.name,
+                "type" => &self.r#type,
+                "inputs" => &self.inputs,
+                "outputs" => &self.outputs
+            ],
+            [
+                "qualified_name" => &self.qualified_name, self.qualified_name.is_empty(),
+                "description" => &self.description, self.description.is_none(),
+                "inherited_from" => &self.inherited_from, self.inherited_from.is_none()
+            ]
+        )
+    }
+}
+
+/// The method metas are equal when their `signature_key`s are equal, i.e., the same name
+/// and the same normalized input types, so that the metas differing only in the input
+/// names, the type spellings such as `str` and `string`, the outputs or the qualified
+/// names are the same method identity for the caches. Compare the fields to tell them apart.
+impl PartialEq for IRContractMethodMeta {
+    fn eq(&self, other: &Self) -> bool {
+        self.signature_key() == other.signature_key()
+    }
+}
+
+impl Eq for IRContractMethodMeta {}
+
+impl std::hash::Hash for IRContractMethodMeta {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.signature_key().hash(state);
+    }
+}
+
+/// The ir function signature of a method, which is the stub of the function definition
+/// without the body, e.g., for generating the bindings from an abi.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSignature {
+    pub name: String,
+    pub params: Vec<Type>,
+    /// The return type, which is `void` for the methods without outputs.
+    pub ret: Type,
+}
+
+/// The parsed input and output param types of a method with the type names they are
+/// parsed from, so that the stale types are rebuilt after the inputs or outputs are
+/// modified. The errors are kept so that an unsupported type is reported on every use.
+#[derive(Debug, Clone)]
+pub struct MethodParamTypes {
+    input_type_names: Vec<String>,
+    output_type_names: Vec<String>,
+    inputs: Result<Vec<ABIParamType>, AbiError>,
+    outputs: Result<Vec<ABIParamType>, AbiError>,
+}
+
+impl MethodParamTypes {
+    fn new(method: &IRContractMethodMeta) -> MethodParamTypes {
+        MethodParamTypes {
+            input_type_names: method.inputs.iter().map(|i| i.r#type.clone()).collect(),
+            output_type_names: method.outputs.iter().map(|o| o.r#type.clone()).collect(),
+            inputs: method
+                .inputs
+                .iter()
+                .map(|input| type_name_to_abi_param_type(&input.r#type))
+                .collect(),
+            outputs: method
+                .outputs
+                .iter()
+                .map(|output| type_name_to_abi_param_type(&output.r#type))
+                .collect(),
         }
-        "u8" => {
-            let int_value = u8::from_str(param_str);
-            if int_value.is_err() {
-                return Err(int_value.err().unwrap().to_string());
-            }
-            let int_value = int_value.unwrap();
-            Ok(ABIParam::U8(int_value))
+    }
+
+    fn is_parsed_from(&self, method: &IRContractMethodMeta) -> bool {
+        self.input_type_names
+            .iter()
+            .eq(method.inputs.iter().map(|i| &i.r#type))
+            && self
+                .output_type_names
+                .iter()
+                .eq(method.outputs.iter().map(|o| &o.r#type))
+    }
+}
+
+/// Collect the normalized type name and its inner type names of arrays `[T]`,
+/// fixed size arrays `[T;N]`, maps `{K:V}`, tuples `(T1,T2)` and enums `enum<T1,T2>`.
+fn collect_type_names(type_name: &str, type_names: &mut BTreeSet<String>) {
+    if type_name.is_empty() || !type_names.insert(type_name.to_string()) {
+        return;
+    }
+    let inner_type_names = if type_name.starts_with('[') && type_name.ends_with(']') {
+        let inner = &type_name[1..(type_name.len() - 1)];
+        // The length of the fixed size array is not a type.
+        split_top_level(inner, ';').into_iter().take(1).collect()
+    } else if type_name.starts_with('{') && type_name.ends_with('}') {
+        split_top_level(&type_name[1..(type_name.len() - 1)], ':')
+    } else if type_name.starts_with('(') && type_name.ends_with(')') {
+        split_top_level(&type_name[1..(type_name.len() - 1)], ',')
+    } else {
+        variant_type_names(type_name).unwrap_or_default()
+    };
+    for inner_type_name in inner_type_names {
+        collect_type_names(inner_type_name, type_names);
+    }
+}
+
+/// Get the abi param type of the abi type name, which supports the time types and the
+/// composite types without data stream param types such as `enum<u8,string,bool>`.
+pub fn type_name_to_abi_param_type(type_name: &str) -> Result<ABIParamType, AbiError> {
+    ABIParamType::from_type_str(type_name)
+}
+
+/// Get the data stream param type of the abi type name, e.g., `[u8]` to `U8Array`, the
+/// types without their own param type codes such as `timestamp` are not supported.
+pub fn type_name_to_param_type(type_name: &str) -> Result<ParamType, AbiError> {
+    match ABIParamType::from_type_str(type_name)? {
+        ABIParamType::Timestamp
+        | ABIParamType::Duration
+        | ABIParamType::Bytes
+        | ABIParamType::Variant(_)
+        | ABIParamType::FixedArray(..)
+        | ABIParamType::Address(_)
+        | ABIParamType::Ref(_) => Err(AbiError::UnsupportedType(normalize_type_name(type_name))),
+        param_ty => Ok(param_ty
+            .to_param_type()
+            .expect("the abi param type has a param type code")),
+    }
+}
+
+macro_rules! impl_abi_param_type_to_ir_type {
+    (
+        scalars: $($scalar_id:ident => $scalar_ty:expr),*;
+        arrays: $($array_id:ident => $array_elem_id:ident),*;
+        maps: $($map_id:ident => $map_value_id:ident),*;
+    ) => {
+        /// Get the ir type of the abi param type, which is the inverse of the type names
+        /// generated by `IRContractABIMeta::from_contract`. The abi types without the ir
+        /// representations such as floats, time types, bytes and variants are not supported.
+        fn abi_param_type_to_ir_type(param_ty: &ABIParamType) -> Result<Type, AbiError> {
+            let unsupported = || AbiError::UnsupportedType(param_ty.canonical_string());
+            let inner_ty = |inner_param_ty: ABIParamType| {
+                abi_param_type_to_ir_type(&inner_param_ty)
+                    .map(Rc::new)
+                    .map_err(|_| unsupported())
+            };
+            let ty = match param_ty {
+                $(ABIParamType::$scalar_id => $scalar_ty,)*
+                $(ABIParamType::$array_id => Type::Array {
+                    elem: inner_ty(ABIParamType::$array_elem_id)?,
+                    len: None,
+                },)*
+                $(ABIParamType::$map_id => Type::Map {
+                    key: Rc::new(Type::str()),
+                    value: inner_ty(ABIParamType::$map_value_id)?,
+                },)*
+                ABIParamType::FixedArray(array_ty, len) => {
+                    match abi_param_type_to_ir_type(array_ty).map_err(|_| unsupported())? {
+                        Type::Array { elem, .. } => Type::Array {
+                            elem,
+                            len: Some(u32::try_from(*len).map_err(|_| unsupported())?),
+                        },
+                        _ => return Err(unsupported()),
+                    }
+                }
+                _ => return Err(unsupported()),
+            };
+            Ok(ty)
         }
-        "i8" => {
-            let int_value = i8::from_str(param_str);
-            if int_value.is_err() {
-                return Err(int_value.err().unwrap().to_string());
-            }
-            let int_value = int_value.unwrap();
-            Ok(ABIParam::I8(int_value))
+    };
+}
+
+impl_abi_param_type_to_ir_type!(
+    scalars: U8 => Type::u8(), I8 => Type::i8(), U16 => Type::u16(), I16 => Type::i16(),
+        U32 => Type::u32(), I32 => Type::i32(), U64 => Type::u64(), I64 => Type::i64(),
+        U128 => Type::u128(), I128 => Type::i128(), Bool => Type::bool(), Str => Type::str(),
+        Parampack => Type::Builtin(BuiltinType::Parampack);
+    arrays: U8Array => U8, I8Array => I8, U16Array => U16, I16Array => I16, U32Array => U32,
+        I32Array => I32, U64Array => U64, I64Array => I64, U128Array => U128,
+        I128Array => I128, BoolArray => Bool, StrArray => Str, F64Array => F64;
+    maps: StrU8Map => U8, StrI8Map => I8, StrU16Map => U16, StrI16Map => I16,
+        StrU32Map => U32, StrI32Map => I32, StrU64Map => U64, StrI64Map => I64,
+        StrU128Map => U128, StrI128Map => I128, StrBoolMap => Bool, StrStrMap => Str;
+);
+
+/// Parse the float abi param, NaN and infinite values are rejected because they
+/// have no portable meaning for the contract.
+fn parse_float<T>(type_name: &str, param_str: &str) -> Result<T, AbiError>
+where
+    T: FromStr + Copy + Into<f64>,
+{
+    let value = match T::from_str(param_str) {
+        Ok(value) => value,
+        Err(_) => {
+            return Err(AbiError::ParseFloat {
+                ty: type_name.to_string(),
+                value: param_str.to_string(),
+            })
         }
-        "u16" => {
-            let int_value = u16::from_str(param_str);
-            if int_value.is_err() {
-                return Err(int_value.err().unwrap().to_string());
+    };
+    if !value.into().is_finite() {
+        return Err(AbiError::NonFiniteFloat {
+            ty: type_name.to_string(),
+            value: param_str.to_string(),
+        });
+    }
+    Ok(value)
+}
+
+/// Parse the integer abi param, decimal, `0x` hex and `0b` binary literals are accepted
+/// with an optional leading `-` for signed types.
+fn parse_int<T>(type_name: &str, param_str: &str) -> Result<T, AbiError>
+where
+    T: Num<FromStrRadixErr = ParseIntError> + Bounded + Display,
+{
+    let (sign, literal) = match param_str.strip_prefix('-') {
+        Some(literal) => ("-", literal),
+        None => ("", param_str),
+    };
+    // Unsigned types can't be negative, report it instead of the opaque `invalid digit`.
+    if !sign.is_empty() && T::min_value().is_zero() {
+        return Err(AbiError::NegativeUnsigned {
+            ty: type_name.to_string(),
+            value: param_str.to_string(),
+        });
+    }
+    let (radix, digits) = if let Some(digits) = literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        (16, digits)
+    } else if let Some(digits) = literal
+        .strip_prefix("0b")
+        .or_else(|| literal.strip_prefix("0B"))
+    {
+        (2, digits)
+    } else {
+        (10, literal)
+    };
+    // Reject the sign after the radix prefix such as `0x-1`.
+    let parse_int_err = || AbiError::ParseInt {
+        ty: type_name.to_string(),
+        value: param_str.to_string(),
+    };
+    if radix != 10 && digits.starts_with(['-', '+']) {
+        return Err(parse_int_err());
+    }
+    T::from_str_radix(&format!("{sign}{digits}"), radix).map_err(|err| match err.kind() {
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => AbiError::IntOutOfRange {
+            ty: type_name.to_string(),
+            value: param_str.to_string(),
+            min: T::min_value().to_string(),
+            max: T::max_value().to_string(),
+        },
+        _ => parse_int_err(),
+    })
+}
+
+/// Parse the timestamp in unix seconds such as `1704067200` or the RFC 3339 (ISO 8601)
+/// date time such as `2024-01-01T00:00:00Z` and `2024-01-01T08:00:00+08:00`.
+fn parse_timestamp(param_str: &str) -> Result<i64, AbiError> {
+    let param_str = param_str.trim();
+    if let Ok(date_time) = chrono::DateTime::parse_from_rfc3339(param_str) {
+        return Ok(date_time.timestamp());
+    }
+    match parse_int("timestamp", param_str) {
+        Err(AbiError::ParseInt { ty, value }) => Err(AbiError::ParseTime { ty, value }),
+        result => result,
+    }
+}
+
+/// Parse the duration in seconds such as `3600` or the suffixed duration with the
+/// units `d`, `h`, `m` and `s` such as `3600s`, `90m` and `1h30m`.
+fn parse_duration(param_str: &str) -> Result<u64, AbiError> {
+    let param_str = param_str.trim();
+    if param_str.ends_with(|c: char| c.is_ascii_digit()) {
+        return parse_int("duration", param_str);
+    }
+    let parse_error = || AbiError::ParseTime {
+        ty: "duration".to_string(),
+        value: param_str.to_string(),
+    };
+    if param_str.is_empty() {
+        return Err(parse_error());
+    }
+    let mut seconds: u64 = 0;
+    let mut rest = param_str;
+    while !rest.is_empty() {
+        let digits_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (digits, unit_and_rest) = rest.split_at(digits_len);
+        let mut unit_chars = unit_and_rest.chars();
+        let unit: u64 = match unit_chars.next() {
+            Some('d') => 86400,
+            Some('h') => 3600,
+            Some('m') => 60,
+            Some('s') => 1,
+            _ => return Err(parse_error()),
+        };
+        let value: u64 = digits.parse().map_err(|_| parse_error())?;
+        seconds = value
+            .checked_mul(unit)
+            .and_then(|v| seconds.checked_add(v))
+            .ok_or_else(parse_error)?;
+        rest = unit_chars.as_str();
+    }
+    Ok(seconds)
+}
+
+/// Parse the bool params and the bool elements of arrays and maps strictly, `true`,
+/// `false` in any case and `1`, `0` are accepted, anything else is an error rather than
+/// `false`.
+fn parse_bool(param_str: &str) -> Result<bool, AbiError> {
+    if param_str.eq_ignore_ascii_case("true") || param_str == "1" {
+        Ok(true)
+    } else if param_str.eq_ignore_ascii_case("false") || param_str == "0" {
+        Ok(false)
+    } else {
+        Err(AbiError::ParseBool {
+            value: param_str.to_string(),
+        })
+    }
+}
+
+/// The empty input string or the `[]` literal of arrays and the `{}` literal of maps
+/// denote the empty collections, so a string array with a single empty string element
+/// can't be expressed, which is rare in practice.
+fn is_empty_collection_literal(param_str: &str, empty_literal: &str) -> bool {
+    let param_str = param_str.trim();
+    param_str.is_empty() || param_str == empty_literal
+}
+
+/// Split the array params string `1,2,3` into the untrimmed elements.
+fn split_array_items(param_str: &str) -> Vec<&str> {
+    if is_empty_collection_literal(param_str, "[]") {
+        vec![]
+    } else {
+        param_str.split(',').collect()
+    }
+}
+
+/// The `0x` prefixed hex string such as `0xdeadbeef` for the `[u8]` params, which is a
+/// shorthand of the comma list `222,173,190,239`. It is told apart from the comma list
+/// of hex elements such as `0xde,0xad` by the absence of commas, and from the single hex
+/// element such as `0x5` by the even number of hex digits.
+fn hex_u8_array_shorthand(param_str: &str) -> Option<&str> {
+    let param_str = param_str.trim();
+    let is_hex = param_str.starts_with("0x") || param_str.starts_with("0X");
+    (is_hex && !param_str.contains(',') && param_str.len() & 1 == 0).then_some(param_str)
+}
+
+/// Parse the array elements, tolerating spaces around the elements such as `1, 2, 3`.
+/// The element errors are wrapped in `AbiError::ArrayElement` with the element index.
+fn parse_array<T>(
+    param_ty: &ABIParamType,
+    param_str: &str,
+    parse_item: impl Fn(&str) -> Result<T, AbiError>,
+) -> Result<Vec<T>, AbiError> {
+    let items = split_array_items(param_str);
+    // Collecting the results can't reserve the elements up front, allocate them once.
+    let mut result = Vec::with_capacity(items.len());
+    for (index, item) in items.into_iter().enumerate() {
+        let elem = parse_item(item.trim()).map_err(|error| AbiError::ArrayElement {
+            ty: param_ty.canonical_string(),
+            index,
+            error: Box::new(error),
+        })?;
+        result.push(elem);
+    }
+    Ok(result)
+}
+
+/// Split the map params string `k1:v1,k2:v2` into the trimmed keys and untrimmed values.
+fn split_map_entries(param_str: &str) -> Result<Vec<(&str, &str)>, AbiError> {
+    if is_empty_collection_literal(param_str, "{}") {
+        return Ok(vec![]);
+    }
+    param_str
+        .split(',')
+        .map(|entry| match entry.split_once(':') {
+            // Tolerate spaces around keys such as `a : 1`.
+            Some((key, value)) => Ok((key.trim(), value)),
+            None => Err(AbiError::MalformedMapEntry(entry.to_string())),
+        })
+        .collect()
+}
+
+/// Parse the map entries, tolerating spaces around the keys and values such as `a : 1`.
+fn parse_map<T>(
+    param_str: &str,
+    parse_value: impl Fn(&str) -> Result<T, AbiError>,
+) -> Result<BTreeMap<String, T>, AbiError> {
+    split_map_entries(param_str)?
+        .into_iter()
+        .map(|(key, value)| Ok((key.to_string(), parse_value(value.trim())?)))
+        .collect()
+}
+
+fn input_type_to_abi_param(input_type_name: &str, param_str: &str) -> Result<ABIParam, AbiError> {
+    abi_param_from_str(&type_name_to_abi_param_type(input_type_name)?, param_str)
+}
+
+/// Parse the params string of the abi param type. The whitespace of string elements
+/// and values is kept because it may be intentional.
+fn abi_param_from_str(param_ty: &ABIParamType, param_str: &str) -> Result<ABIParam, AbiError> {
+    abi_param_from_str_in(param_ty, param_str, None, 0)
+}
+
+/// The same as `abi_param_from_str`, but the type references such as `#0` are resolved by
+/// the types table, and `depth` is the nesting depth of the arrays and variants. The same
+/// as `decode_abi_param_with_types`, the references are resolved up to the nesting depth
+/// `MAX_TYPE_REF_DEPTH`, so that the deeply nested strings can't overflow the stack.
+fn abi_param_from_str_in(
+    param_ty: &ABIParamType,
+    param_str: &str,
+    types: Option<&ABITypeTable>,
+    depth: usize,
+) -> Result<ABIParam, AbiError> {
+    let param = match param_ty {
+        ABIParamType::Variant(variant_tys) => {
+            // tag:value, the value may contain ':' such as maps.
+            let (tag, value) = match param_str.split_once(':') {
+                Some((tag, value)) => (tag.trim(), value),
+                None => return Err(AbiError::MalformedVariant(param_str.to_string())),
+            };
+            let tag: u32 = parse_int("u32", tag)?;
+            let variant_ty = match variant_tys.get(tag as usize) {
+                Some(variant_ty) => variant_ty,
+                None => {
+                    return Err(AbiError::InvalidVariantTag {
+                        tag,
+                        count: variant_tys.len(),
+                    })
+                }
+            };
+            ABIParam::Variant {
+                tag,
+                value: Box::new(abi_param_from_str_in(variant_ty, value, types, depth + 1)?),
             }
-            let int_value = int_value.unwrap();
-            Ok(ABIParam::U16(int_value))
         }
-        "i16" => {
-            let int_value = i16::from_str(param_str);
-            if int_value.is_err() {
-                return Err(int_value.err().unwrap().to_string());
-            }
-            let int_value = int_value.unwrap();
-            Ok(ABIParam::I16(int_value))
+        ABIParamType::FixedArray(array_ty, len) => {
+            let array = abi_param_from_str_in(array_ty, param_str, types, depth + 1)?;
+            ABIParam::fixed_array(array, *len)?
         }
-        "u32" => {
-            let int_value = u32::from_str(param_str);
-            if int_value.is_err() {
-                return Err(int_value.err().unwrap().to_string());
+        ABIParamType::Ref(id) => {
+            let types = types.ok_or(AbiError::UnresolvedTypeRef(*id))?;
+            if depth > MAX_TYPE_REF_DEPTH {
+                return Err(AbiError::LimitExceeded {
+                    limit: "max_depth".to_string(),
+                    actual: depth,
+                    max: MAX_TYPE_REF_DEPTH,
+                });
             }
-            let int_value = int_value.unwrap();
-            Ok(ABIParam::U32(int_value))
+            abi_param_from_str_in(types.resolve(param_ty)?, param_str, Some(types), depth)?
         }
-        "i32" => {
-            let int_value = i32::from_str(param_str);
-            if int_value.is_err() {
-                return Err(int_value.err().unwrap().to_string());
-            }
-            let int_value = int_value.unwrap();
-            Ok(ABIParam::I32(int_value))
+        _ => leaf_param_from_str(param_ty, param_str)?,
+    };
+    Ok(param)
+}
+
+/// Parse the params string of the abi param type without nested types, which is kept out
+/// of the recursion of `abi_param_from_str_in` so that the nested frames stay small.
+fn leaf_param_from_str(param_ty: &ABIParamType, param_str: &str) -> Result<ABIParam, AbiError> {
+    let param = match param_ty {
+        ABIParamType::Bool => ABIParam::Bool(parse_bool(param_str)?),
+        ABIParamType::Str => ABIParam::Str(param_str.to_string()),
+        ABIParamType::Parampack => ABIParam::parampack_from_hex(param_str)?,
+        ABIParamType::Bytes => ABIParam::bytes_from_hex(param_str)?,
+        ABIParamType::U8 => ABIParam::U8(parse_int("u8", param_str)?),
+        ABIParamType::I8 => ABIParam::I8(parse_int("i8", param_str)?),
+        ABIParamType::U16 => ABIParam::U16(parse_int("u16", param_str)?),
+        ABIParamType::I16 => ABIParam::I16(parse_int("i16", param_str)?),
+        ABIParamType::U32 => ABIParam::U32(parse_int("u32", param_str)?),
+        ABIParamType::I32 => ABIParam::I32(parse_int("i32", param_str)?),
+        ABIParamType::U64 => ABIParam::U64(parse_int("u64", param_str)?),
+        ABIParamType::I64 => ABIParam::I64(parse_int("i64", param_str)?),
+        ABIParamType::U128 => ABIParam::U128(parse_int("u128", param_str)?),
+        ABIParamType::I128 => ABIParam::I128(parse_int("i128", param_str)?),
+        ABIParamType::F32 => ABIParam::F32(parse_float("f32", param_str)?),
+        ABIParamType::F64 => ABIParam::F64(parse_float("f64", param_str)?),
+        ABIParamType::Timestamp => ABIParam::Timestamp(parse_timestamp(param_str)?),
+        ABIParamType::Duration => ABIParam::Duration(parse_duration(param_str)?),
+        ABIParamType::U8Array => match hex_u8_array_shorthand(param_str) {
+            Some(hex_str) => ABIParam::U8Array(decode_hex_str(hex_str)?),
+            None => ABIParam::U8Array(parse_array(param_ty, param_str, |s| parse_int("u8", s))?),
+        },
+        ABIParamType::I8Array => {
+            ABIParam::I8Array(parse_array(param_ty, param_str, |s| parse_int("i8", s))?)
         }
-        "u64" => {
-            let int_value = u64::from_str(param_str);
-            if int_value.is_err() {
-                return Err(int_value.err().unwrap().to_string());
-            }
-            let int_value = int_value.unwrap();
-            Ok(ABIParam::U64(int_value))
+        ABIParamType::U16Array => {
+            ABIParam::U16Array(parse_array(param_ty, param_str, |s| parse_int("u16", s))?)
         }
-        "i64" => {
-            let int_value = i64::from_str(param_str);
-            if int_value.is_err() {
-                return Err(int_value.err().unwrap().to_string());
-            }
-            let int_value = int_value.unwrap();
-            Ok(ABIParam::I64(int_value))
+        ABIParamType::I16Array => {
+            ABIParam::I16Array(parse_array(param_ty, param_str, |s| parse_int("i16", s))?)
         }
-        "u128" => {
-            let int_value = u128::from_str(param_str);
-            if int_value.is_err() {
-                return Err(int_value.err().unwrap().to_string());
-            }
-            let int_value = int_value.unwrap();
-            Ok(ABIParam::U128(int_value))
+        ABIParamType::U32Array => {
+            ABIParam::U32Array(parse_array(param_ty, param_str, |s| parse_int("u32", s))?)
         }
-        "i128" => {
-            let int_value = i128::from_str(param_str);
-            if int_value.is_err() {
-                return Err(int_value.err().unwrap().to_string());
+        ABIParamType::I32Array => {
+            ABIParam::I32Array(parse_array(param_ty, param_str, |s| parse_int("i32", s))?)
+        }
+        ABIParamType::U64Array => {
+            ABIParam::U64Array(parse_array(param_ty, param_str, |s| parse_int("u64", s))?)
+        }
+        ABIParamType::I64Array => {
+            ABIParam::I64Array(parse_array(param_ty, param_str, |s| parse_int("i64", s))?)
+        }
+        ABIParamType::U128Array => {
+            ABIParam::U128Array(parse_array(param_ty, param_str, |s| parse_int("u128", s))?)
+        }
+        ABIParamType::I128Array => {
+            ABIParam::I128Array(parse_array(param_ty, param_str, |s| parse_int("i128", s))?)
+        }
+        ABIParamType::BoolArray => {
+            ABIParam::BoolArray(parse_array(param_ty, param_str, parse_bool)?)
+        }
+        ABIParamType::StrArray => ABIParam::StrArray(
+            split_array_items(param_str)
+                .into_iter()
+                .map(|item| item.to_string())
+                .collect(),
+        ),
+        ABIParamType::F64Array => {
+            ABIParam::F64Array(parse_array(param_ty, param_str, |s| parse_float("f64", s))?)
+        }
+        ABIParamType::StrU8Map => ABIParam::StrU8Map(parse_map(param_str, |s| parse_int("u8", s))?),
+        ABIParamType::StrI8Map => ABIParam::StrI8Map(parse_map(param_str, |s| parse_int("i8", s))?),
+        ABIParamType::StrU16Map => {
+            ABIParam::StrU16Map(parse_map(param_str, |s| parse_int("u16", s))?)
+        }
+        ABIParamType::StrI16Map => {
+            ABIParam::StrI16Map(parse_map(param_str, |s| parse_int("i16", s))?)
+        }
+        ABIParamType::StrU32Map => {
+            ABIParam::StrU32Map(parse_map(param_str, |s| parse_int("u32", s))?)
+        }
+        ABIParamType::StrI32Map => {
+            ABIParam::StrI32Map(parse_map(param_str, |s| parse_int("i32", s))?)
+        }
+        ABIParamType::StrU64Map => {
+            ABIParam::StrU64Map(parse_map(param_str, |s| parse_int("u64", s))?)
+        }
+        ABIParamType::StrI64Map => {
+            ABIParam::StrI64Map(parse_map(param_str, |s| parse_int("i64", s))?)
+        }
+        ABIParamType::StrU128Map => {
+            ABIParam::StrU128Map(parse_map(param_str, |s| parse_int("u128", s))?)
+        }
+        ABIParamType::StrI128Map => {
+            ABIParam::StrI128Map(parse_map(param_str, |s| parse_int("i128", s))?)
+        }
+        ABIParamType::StrBoolMap => ABIParam::StrBoolMap(parse_map(param_str, parse_bool)?),
+        ABIParamType::StrStrMap => ABIParam::StrStrMap(
+            split_map_entries(param_str)?
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        ),
+        // The mixed-case 20-byte addresses are EIP-55 checksummed.
+        ABIParamType::Address(len) => {
+            ABIParam::address_from_hex(param_str, *len, AddressChecksum::for_len(*len))?
+        }
+        ABIParamType::Variant(_) | ABIParamType::FixedArray(..) | ABIParamType::Ref(_) => {
+            unreachable!("{param_ty:?} has nested types")
+        }
+    };
+    Ok(param)
+}
+
+impl IRContractMethodMeta {
+    /// Encode the params strings according to the method inputs, arrays are written as
+    /// `1,2,3`, maps as `k1:v1,k2:v2`, and the empty collections as `[]`, `{}` or the
+    /// empty string.
+    pub fn encode_params(&self, params_strings: &[&str]) -> Result<Vec<u8>, AbiError> {
+        self.encode_params_iter(params_strings)
+    }
+
+    /// The same as `encode_params`, but accepts any params strings such as `Vec<String>`,
+    /// slices and iterators, so that the callers owning the strings needn't rebind them.
+    pub fn encode_params_iter<S: AsRef<str>>(
+        &self,
+        params_strings: impl IntoIterator<Item = S>,
+    ) -> Result<Vec<u8>, AbiError> {
+        self.encode_params_with_endianness(params_strings, Endianness::Little)
+    }
+
+    /// The same as `encode_params_iter`, but with the byte order of the fixed-width
+    /// numbers. `encode_params` uses little-endian, and the bytes must be decoded by
+    /// `decode_params_with_endianness` with the same byte order.
+    pub fn encode_params_with_endianness<S: AsRef<str>>(
+        &self,
+        params_strings: impl IntoIterator<Item = S>,
+        endianness: Endianness,
+    ) -> Result<Vec<u8>, AbiError> {
+        self.encode_params_with_options(
+            params_strings,
+            EncodingOptions::with_endianness(endianness),
+        )
+    }
+
+    /// The same as `encode_params_iter`, but with the encoding options such as the varint
+    /// mode for the small integers. The bytes must be decoded by
+    /// `decode_params_with_options` with the same options.
+    pub fn encode_params_with_options<S: AsRef<str>>(
+        &self,
+        params_strings: impl IntoIterator<Item = S>,
+        options: EncodingOptions,
+    ) -> Result<Vec<u8>, AbiError> {
+        let params_strings: Vec<S> = params_strings.into_iter().collect();
+        let abi_input_params = self.parse_input_params(&params_strings, None)?;
+        Ok(encode_abi_params(&abi_input_params, options))
+    }
+
+    /// The same as `encode_params`, but the type references such as `#0` of the inputs are
+    /// resolved by the types table of `IRContractABIMeta::type_table`, e.g., the recursive
+    /// types. The references are the ids of the table, there are no named references. The
+    /// values nested deeper than `MAX_TYPE_REF_DEPTH` are rejected with
+    /// `AbiError::LimitExceeded`, and the bytes are decoded by `decode_abi_param_with_types`.
+    pub fn encode_params_with_type_table(
+        &self,
+        params_strings: &[&str],
+        types: &ABITypeTable,
+    ) -> Result<Vec<u8>, AbiError> {
+        let abi_input_params = self.parse_input_params(params_strings, Some(types))?;
+        Ok(encode_abi_params(
+            &abi_input_params,
+            EncodingOptions::default(),
+        ))
+    }
+
+    /// The start offsets of the params in the bytes encoded by `encode_params`, which
+    /// account for the version byte and the length prefixes, so that the tooling can slice
+    /// a param out of the bytes or patch it in place without decoding the others. Param `i`
+    /// ends where param `i + 1` starts, and the last one ends at the end of the bytes.
+    pub fn param_offsets(&self, params_strings: &[&str]) -> Result<Vec<usize>, AbiError> {
+        let abi_input_params = self.parse_input_params(params_strings, None)?;
+        // The first param starts after the data stream version byte.
+        let mut offset = 1;
+        Ok(abi_input_params
+            .iter()
+            .map(|param| {
+                let start = offset;
+                offset += param.encoded_len();
+                start
+            })
+            .collect())
+    }
+
+    /// Reorder the `name=value` params strings given in any order into the positional
+    /// params strings of the method inputs, which can be encoded by `encode_params`. The
+    /// value is split at the first `=`, so it may contain `=` itself. All the inputs must be
+    /// named, and the missing, unknown and duplicate names are errors.
+    pub fn named_params_to_positional<'a>(
+        &self,
+        named_params: &[&'a str],
+    ) -> Result<Vec<&'a str>, AbiError> {
+        if let Some(index) = self.inputs.iter().position(|input| input.name.is_empty()) {
+            return Err(AbiError::UnnamedParam(index));
+        }
+        let mut values: HashMap<&str, &'a str> = HashMap::with_capacity(named_params.len());
+        for named_param in named_params {
+            let (name, value) = named_param
+                .split_once('=')
+                .ok_or_else(|| AbiError::MalformedNamedParam(named_param.to_string()))?;
+            if !self.inputs.iter().any(|input| input.name == name) {
+                return Err(AbiError::UnknownParam(name.to_string()));
             }
-            let int_value = int_value.unwrap();
-            Ok(ABIParam::I128(int_value))
-        }
-        _ => {
-            if input_type_name.starts_with('[') {
-                let inner_type_name = input_type_name[1..(input_type_name.len() - 1)].to_string();
-                let inner_type_name = inner_type_name.as_str();
-                let array_params: Vec<&str> = param_str.split(',').collect();
-
-                match inner_type_name {
-                    "bool" => {
-                        let mut values: Vec<bool> = vec![];
-                        for item in array_params {
-                            values.push(bool::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::BoolArray(values));
-                    }
-                    "str" | "string" => {
-                        let mut values: Vec<String> = vec![];
-                        for item in array_params {
-                            values.push(item.to_string());
-                        }
-                        return Ok(ABIParam::StrArray(values));
-                    }
-                    "i8" => {
-                        let mut values: Vec<i8> = vec![];
-                        for item in array_params {
-                            values.push(i8::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::I8Array(values));
-                    }
-                    "u8" => {
-                        let mut values: Vec<u8> = vec![];
-                        for item in array_params {
-                            values.push(u8::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::U8Array(values));
-                    }
-                    "i16" => {
-                        let mut values: Vec<i16> = vec![];
-                        for item in array_params {
-                            values.push(i16::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::I16Array(values));
-                    }
-                    "u16" => {
-                        let mut values: Vec<u16> = vec![];
-                        for item in array_params {
-                            values.push(u16::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::U16Array(values));
-                    }
-                    "i32" => {
-                        let mut values: Vec<i32> = vec![];
-                        for item in array_params {
-                            values.push(i32::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::I32Array(values));
-                    }
-                    "u32" => {
-                        let mut values: Vec<u32> = vec![];
-                        for item in array_params {
-                            values.push(u32::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::U32Array(values));
-                    }
-                    "i64" => {
-                        let mut values: Vec<i64> = vec![];
-                        for item in array_params {
-                            values.push(i64::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::I64Array(values));
-                    }
-                    "u64" => {
-                        let mut values: Vec<u64> = vec![];
-                        for item in array_params {
-                            values.push(u64::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::U64Array(values));
-                    }
-                    "i128" => {
-                        let mut values: Vec<i128> = vec![];
-                        for item in array_params {
-                            values.push(i128::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::I128Array(values));
-                    }
-                    "u128" => {
-                        let mut values: Vec<u128> = vec![];
-                        for item in array_params {
-                            values.push(u128::from_str(item).unwrap());
-                        }
-                        return Ok(ABIParam::U128Array(values));
-                    }
-                    _ => {
-                        return Err("not supported input param type".to_string());
-                    }
-                }
-            } else if input_type_name.starts_with('{') {
-                if let Some(sep_pos) = input_type_name.find(':') {
-                    let inner_type_name =
-                        input_type_name[(sep_pos + 1)..(input_type_name.len() - 1)].to_string();
-                    let inner_type_name = inner_type_name.as_str();
-                    // k1:v1,k2:v2...
-                    let params_pairs: Vec<&str> = param_str.split(',').collect();
-                    let mut params_keys: Vec<&str> = vec![];
-                    let mut params_values: Vec<&str> = vec![];
-                    for p in params_pairs {
-                        let splited: Vec<&str> = p.split(':').collect();
-                        if splited.len() < 2 {
-                            return Err("invalid map entry, expected k1:v1,k2:v2,...".to_string());
-                        }
-                        params_keys.push(splited[0]);
-                        params_values.push(splited[1]);
-                    }
+            if values.insert(name, value).is_some() {
+                return Err(AbiError::DuplicateParam(name.to_string()));
+            }
+        }
+        self.inputs
+            .iter()
+            .map(|input| {
+                values
+                    .get(input.name.as_str())
+                    .copied()
+                    .ok_or_else(|| AbiError::MissingParam(input.name.clone()))
+            })
+            .collect()
+    }
+
+    /// Encode the args of the `run` command, which are the `name=value` pairs in any order
+    /// reordered by `named_params_to_positional` when `named` is set, or the positional
+    /// params strings otherwise. No args are encoded as the empty params, and all the bad
+    /// params are reported at once by `validate_params`.
+    pub fn encode_run_args(&self, args: &[&str], named: bool) -> Result<Vec<u8>, Vec<AbiError>> {
+        let positional_args;
+        let args = if named {
+            positional_args = self
+                .named_params_to_positional(args)
+                .map_err(|err| vec![err])?;
+            &positional_args
+        } else {
+            args
+        };
+        if args.is_empty() {
+            return Ok(encode_abi_params(&[], EncodingOptions::default()));
+        }
+        self.validate_params(args)?;
+        self.encode_params(args).map_err(|err| vec![err])
+    }
 
-                    match inner_type_name {
-                        "bool" => {
-                            let mut values: HashMap<String, bool> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    bool::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrBoolMap(values));
-                        }
-                        "str" | "string" => {
-                            let mut values: HashMap<String, String> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(params_keys[i].to_string(), item.to_string());
-                            }
-                            return Ok(ABIParam::StrStrMap(values));
-                        }
-                        "i8" => {
-                            let mut values: HashMap<String, i8> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    i8::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrI8Map(values));
-                        }
-                        "u8" => {
-                            let mut values: HashMap<String, u8> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    u8::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrU8Map(values));
-                        }
-                        "i16" => {
-                            let mut values: HashMap<String, i16> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    i16::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrI16Map(values));
-                        }
-                        "u16" => {
-                            let mut values: HashMap<String, u16> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    u16::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrU16Map(values));
-                        }
-                        "i32" => {
-                            let mut values: HashMap<String, i32> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    i32::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrI32Map(values));
-                        }
-                        "u32" => {
-                            let mut values: HashMap<String, u32> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    u32::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrU32Map(values));
-                        }
-                        "i64" => {
-                            let mut values: HashMap<String, i64> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    i64::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrI64Map(values));
-                        }
-                        "u64" => {
-                            let mut values: HashMap<String, u64> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    u64::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrU64Map(values));
-                        }
-                        "i128" => {
-                            let mut values: HashMap<String, i128> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    i128::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrI128Map(values));
-                        }
-                        "u128" => {
-                            let mut values: HashMap<String, u128> = HashMap::new();
-                            for i in 0..params_keys.len() {
-                                let item = params_values[i];
-                                values.insert(
-                                    params_keys[i].to_string(),
-                                    u128::from_str(item).unwrap(),
-                                );
-                            }
-                            return Ok(ABIParam::StrU128Map(values));
-                        }
-                        _ => {
-                            return Err(format!(
-                                "not supported input param type {inner_type_name}"
-                            ));
-                        }
+    /// Parse the params strings according to the method inputs, the type references are
+    /// resolved by the optional types table.
+    fn parse_input_params<S: AsRef<str>>(
+        &self,
+        params_strings: &[S],
+        types: Option<&ABITypeTable>,
+    ) -> Result<Vec<ABIParam>, AbiError> {
+        if self.inputs.len() != params_strings.len() {
+            return Err(AbiError::ArityMismatch {
+                expected: self.inputs.len(),
+                got: params_strings.len(),
+            });
+        }
+        let input_param_types = self.input_param_types()?;
+        let mut abi_input_params: Vec<ABIParam> = Vec::with_capacity(params_strings.len());
+        for (param_ty, param_str) in input_param_types.iter().zip(params_strings) {
+            abi_input_params.push(abi_param_from_str_in(
+                param_ty,
+                param_str.as_ref(),
+                types,
+                0,
+            )?);
+        }
+        Ok(abi_input_params)
+    }
+
+    /// Encode the params strings with the optional per-param type overrides such as
+    /// `[("alice", None), ("7", Some("u32"))]`, which is an escape hatch to encode a value
+    /// as a narrower type than the declared one without editing the abi. The override must
+    /// be a subtype of the declared type checked by `ABIParamType::is_subtype_of`, and the
+    /// bytes can only be decoded with the same overrides because the layouts differ. The
+    /// override errors are wrapped in `AbiError::Param` with the input index.
+    pub fn encode_params_with_types(
+        &self,
+        params: &[(&str, Option<&str>)],
+    ) -> Result<Vec<u8>, AbiError> {
+        if self.inputs.len() != params.len() {
+            return Err(AbiError::ArityMismatch {
+                expected: self.inputs.len(),
+                got: params.len(),
+            });
+        }
+        let input_param_types = self.input_param_types()?;
+        let mut abi_input_params: Vec<ABIParam> = Vec::with_capacity(params.len());
+        for (index, (declared_ty, (param_str, override_type_name))) in
+            input_param_types.iter().zip(params).enumerate()
+        {
+            let param_ty = match override_type_name {
+                Some(override_type_name) => {
+                    let wrap = |error| AbiError::Param {
+                        index,
+                        name: self.inputs[index].name.clone(),
+                        error: Box::new(error),
+                    };
+                    let override_ty =
+                        type_name_to_abi_param_type(override_type_name).map_err(wrap)?;
+                    if !override_ty.is_subtype_of(declared_ty) {
+                        return Err(wrap(AbiError::IncompatibleTypeOverride {
+                            declared: declared_ty.canonical_string(),
+                            overridden: override_ty.canonical_string(),
+                        }));
                     }
-                } else {
-                    return Err("not supported map param type".to_string());
+                    override_ty
                 }
-            }
-            Err(format!("not supported abi param type {input_type_name}"))
+                None => declared_ty.clone(),
+            };
+            abi_input_params.push(abi_param_from_str(&param_ty, param_str)?);
         }
+        Ok(encode_abi_params(
+            &abi_input_params,
+            EncodingOptions::default(),
+        ))
     }
-}
 
-impl IRContractMethodMeta {
-    pub fn encode_params(&self, params_strings: &[&str]) -> Result<Vec<u8>, String> {
+    /// Parse every params string without encoding and collect all the failures, each
+    /// wrapped in `AbiError::Param` with its index, so that all the bad params can be
+    /// reported at once. `encode_params` still fails fast on the first bad param.
+    pub fn validate_params(&self, params_strings: &[&str]) -> Result<(), Vec<AbiError>> {
         if self.inputs.len() != params_strings.len() {
-            return Err("params count not match".to_string());
-        }
-        let mut result: Vec<u8> = vec![0x00]; // first byte is abi version
-        for (i, param_str) in params_strings.iter().enumerate() {
-            let input_meta = &self.inputs[i];
-            let abi_input_param = input_type_to_abi_param(&input_meta.r#type, param_str);
-            if abi_input_param.is_err() {
-                return Err(abi_input_param.err().unwrap());
-            }
-            let mut abi_input_param_bytes = abi_input_param.unwrap().as_bytes();
-            result.append(&mut abi_input_param_bytes);
+            return Err(vec![AbiError::ArityMismatch {
+                expected: self.inputs.len(),
+                got: params_strings.len(),
+            }]);
         }
+        let errors: Vec<AbiError> = self
+            .inputs
+            .iter()
+            .zip(params_strings)
+            .enumerate()
+            .filter_map(|(index, (input_meta, param_str))| {
+                input_type_to_abi_param(&input_meta.r#type, param_str)
+                    .err()
+                    .map(|error| AbiError::Param {
+                        index,
+                        name: input_meta.name.clone(),
+                        error: Box::new(error),
+                    })
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Encode the call data, which is the 4-byte method selector followed by the
+    /// params encoded by `encode_params`.
+    pub fn encode_call(&self, params_strings: &[&str]) -> Result<Vec<u8>, AbiError> {
+        let mut result = self.selector().to_vec();
+        result.append(&mut self.encode_params(params_strings)?);
         Ok(result)
     }
+
+    /// The state mutability of the method. The IR has no mutability of the methods yet, so
+    /// every method is `nonpayable`, which is shown by `abi explain` and exported to the
+    /// Ethereum-compatible abi.
+    pub fn state_mutability(&self) -> &'static str {
+        "nonpayable"
+    }
+
+    /// The canonical signature `name(type1,type2,...)` of the method built from the
+    /// input types with normalized type names, it is the single source of truth of
+    /// the method signature for the display and the selector computation.
+    pub fn signature(&self) -> String {
+        let input_types: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|input| normalize_type_name(&input.r#type))
+            .collect();
+        format!("{}({})", self.name, input_types.join(","))
+    }
+
+    /// The stable identity of the method for the `Hash` and `Eq` implementations, which is
+    /// the canonical `signature`.
+    pub fn signature_key(&self) -> String {
+        self.signature()
+    }
+
+    /// The 4-byte method selector, which is the first 4 bytes of the keccak256 hash
+    /// of the canonical method signature, i.e., `selector_with` of the `DefaultSelectorHasher`.
+    pub fn selector(&self) -> [u8; 4] {
+        self.selector_with(&selector::DefaultSelectorHasher::default())
+    }
+
+    /// The input names in the param order. The unnamed inputs are returned as the empty
+    /// names rather than the made-up ones, so that the names line up with the positional
+    /// params, and the callers fall back to the indexes to label them.
+    pub fn input_names(&self) -> Vec<&str> {
+        self.inputs
+            .iter()
+            .map(|input| input.name.as_str())
+            .collect()
+    }
+
+    /// The number of the params taken by `encode_params`.
+    pub fn input_count(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// The number of the return values decoded by `decode_outputs`.
+    pub fn output_count(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Parse the input and output types back into the ir function signature. The ir
+    /// functions return at most one value, so the methods with multiple outputs are not
+    /// supported, as well as the abi types without the ir representations.
+    pub fn to_function_signature(&self) -> Result<FunctionSignature, AbiError> {
+        let params = self
+            .input_param_types()?
+            .iter()
+            .map(abi_param_type_to_ir_type)
+            .collect::<Result<Vec<Type>, AbiError>>()?;
+        let output_param_types = self.output_param_types()?;
+        let ret = match output_param_types.as_slice() {
+            [] => Type::void(),
+            [output_param_ty] => abi_param_type_to_ir_type(output_param_ty)?,
+            _ => {
+                let output_type_strs: Vec<String> = output_param_types
+                    .iter()
+                    .map(|ty| ty.canonical_string())
+                    .collect();
+                return Err(AbiError::UnsupportedType(format!(
+                    "({})",
+                    output_type_strs.join(",")
+                )));
+            }
+        };
+        Ok(FunctionSignature {
+            name: self.name.clone(),
+            params,
+            ret,
+        })
+    }
+
+    /// Validate the method meta, the non-empty input names must be unique, so that
+    /// the params can be bound by name.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut input_names: HashSet<&str> = HashSet::new();
+        for input in &self.inputs {
+            if !input.name.is_empty() && !input_names.insert(input.name.as_str()) {
+                return Err(format!(
+                    "duplicate input name {} in method {}",
+                    input.name, self.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that every input and output type name of the method can be parsed as an abi
+    /// param type, without the param values, e.g., before building a form of the method.
+    /// The error lists the unsupported type names in the declaration order, inputs first.
+    pub fn is_encodable(&self) -> Result<(), Vec<String>> {
+        let input_types = self.inputs.iter().map(|input| &input.r#type);
+        let output_types = self.outputs.iter().map(|output| &output.r#type);
+        let unsupported_types: Vec<String> = input_types
+            .chain(output_types)
+            .filter(|type_name| type_name_to_abi_param_type(type_name).is_err())
+            .cloned()
+            .collect();
+        if unsupported_types.is_empty() {
+            Ok(())
+        } else {
+            Err(unsupported_types)
+        }
+    }
+
+    fn with_param_types<R>(&self, f: impl FnOnce(&MethodParamTypes) -> R) -> R {
+        let param_types = self.param_types.get_or_init(|| MethodParamTypes::new(self));
+        if param_types.is_parsed_from(self) {
+            f(param_types)
+        } else {
+            // The type names are modified after the types are cached.
+            f(&MethodParamTypes::new(self))
+        }
+    }
+
+    /// The param types of the method inputs, which are parsed from the type names once
+    /// and cached, the type names modified after that are parsed again on every use.
+    pub fn input_param_types(&self) -> Result<Vec<ABIParamType>, AbiError> {
+        self.with_param_types(|param_types| param_types.inputs.clone())
+    }
+
+    /// The param types of the method outputs, which are parsed and cached in the same
+    /// way as `input_param_types`.
+    pub fn output_param_types(&self) -> Result<Vec<ABIParamType>, AbiError> {
+        self.with_param_types(|param_types| param_types.outputs.clone())
+    }
+
+    /// Decode the params encoded by `encode_params` according to the method inputs, the
+    /// bytes left after the last param are rejected with `AbiError::TrailingBytes`.
+    pub fn decode_params(&self, bytes: &[u8]) -> Result<Vec<ABIParam>, AbiError> {
+        self.decode_params_with_endianness(bytes, Endianness::Little)
+    }
+
+    /// Decode the params encoded by `encode_params_with_endianness` with the same byte
+    /// order.
+    pub fn decode_params_with_endianness(
+        &self,
+        bytes: &[u8],
+        endianness: Endianness,
+    ) -> Result<Vec<ABIParam>, AbiError> {
+        self.decode_params_with_options(bytes, EncodingOptions::with_endianness(endianness))
+    }
+
+    /// Decode the params encoded by `encode_params_with_options` with the same options.
+    pub fn decode_params_with_options(
+        &self,
+        bytes: &[u8],
+        options: EncodingOptions,
+    ) -> Result<Vec<ABIParam>, AbiError> {
+        self.decode_params_with_limits(bytes, options, DecodeLimits::default())
+    }
+
+    /// Decode the untrusted params bytes within the limits, exceeding any limit returns
+    /// `AbiError::LimitExceeded` before the oversized data is allocated.
+    pub fn decode_params_with_limits(
+        &self,
+        bytes: &[u8],
+        options: EncodingOptions,
+        limits: DecodeLimits,
+    ) -> Result<Vec<ABIParam>, AbiError> {
+        let type_names: Vec<&str> = self.inputs.iter().map(|i| i.r#type.as_str()).collect();
+        decode_param_types(
+            &type_names,
+            &self.input_param_types()?,
+            bytes,
+            options,
+            limits,
+            true,
+        )
+    }
+
+    /// The same as `decode_params`, but the bytes left after the last param are ignored
+    /// when `strict_trailing` is false, e.g., for the call data padded by the transport.
+    pub fn decode_params_with_trailing(
+        &self,
+        bytes: &[u8],
+        strict_trailing: bool,
+    ) -> Result<Vec<ABIParam>, AbiError> {
+        let type_names: Vec<&str> = self.inputs.iter().map(|i| i.r#type.as_str()).collect();
+        decode_param_types(
+            &type_names,
+            &self.input_param_types()?,
+            bytes,
+            EncodingOptions::default(),
+            DecodeLimits::default(),
+            strict_trailing,
+        )
+    }
+
+    /// Decode the return data according to the method outputs.
+    ///
+    /// A void method is described by an empty `outputs` rather than an explicit unit
+    /// type, its return data must be empty or only contain the leading version byte,
+    /// and it is decoded to an empty list. Note that a method returning an empty array
+    /// still has one output and is decoded to one empty array param.
+    pub fn decode_outputs(&self, bytes: &[u8]) -> Result<Vec<ABIParam>, AbiError> {
+        let type_names: Vec<&str> = self.outputs.iter().map(|o| o.r#type.as_str()).collect();
+        decode_param_types(
+            &type_names,
+            &self.output_param_types()?,
+            bytes,
+            EncodingOptions::default(),
+            DecodeLimits::default(),
+            true,
+        )
+    }
+
+    /// Decode the params and render them as `name: value` with `ABIParam::to_readable`
+    /// for the audit logs, the values of the inputs named in `redact` are masked as `***`.
+    /// The unnamed inputs are labeled by their indexes and never redacted.
+    pub fn decode_params_redacted(
+        &self,
+        bytes: &[u8],
+        redact: &HashSet<String>,
+    ) -> Result<Vec<String>, AbiError> {
+        let params = self.decode_params(bytes)?;
+        Ok(self
+            .inputs
+            .iter()
+            .zip(&params)
+            .enumerate()
+            .map(|(index, (input, param))| {
+                if input.name.is_empty() {
+                    format!("{index}: {}", param.to_readable())
+                } else {
+                    format!(
+                        "{}: {}",
+                        input.name,
+                        param.to_readable_redacted(&input.name, redact)
+                    )
+                }
+            })
+            .collect())
+    }
+}
+
+/// Encode the abi params to the data stream bytes with the leading version byte.
+fn encode_abi_params(params: &[ABIParam], options: EncodingOptions) -> Vec<u8> {
+    // Allocate the whole buffer once, the first byte is the data stream version.
+    let encoded_len: usize = params.iter().map(|p| p.encoded_len()).sum();
+    let mut result: Vec<u8> = Vec::with_capacity(1 + encoded_len);
+    result.push(EncodingVersion::CURRENT.as_u8());
+    for param in params {
+        result.extend_from_slice(&param.as_bytes_with_options(options));
+    }
+    result
+}
+
+/// Decode the data stream bytes with the leading version byte according to the abi param
+/// types, the type names are only used in the errors. The bytes left after the last param
+/// are rejected when `strict_trailing` is set.
+fn decode_param_types(
+    type_names: &[&str],
+    param_tys: &[ABIParamType],
+    bytes: &[u8],
+    options: EncodingOptions,
+    limits: DecodeLimits,
+    strict_trailing: bool,
+) -> Result<Vec<ABIParam>, AbiError> {
+    limits.check_bytes(bytes.len())?;
+    check_data_stream_version(bytes)?;
+    if param_tys.is_empty() {
+        if strict_trailing && bytes.len() > 1 {
+            return Err(AbiError::Decode {
+                ty: "void".to_string(),
+                reason: format!("expect no params, but got {} bytes", bytes.len() - 1),
+            });
+        }
+        return Ok(vec![]);
+    }
+    if bytes.is_empty() {
+        return Err(AbiError::Decode {
+            ty: "params".to_string(),
+            reason: "failed to decode empty bytes".to_string(),
+        });
+    }
+    // Skip the first byte version.
+    let mut offset = 1;
+    let mut params: Vec<ABIParam> = vec![];
+    for (type_name, param_ty) in type_names.iter().zip(param_tys) {
+        // Decode the param bytes alone so that the error offsets are relative to the param.
+        let mut consumed = 0;
+        let param = decode_abi_param_with_limits(
+            param_ty,
+            &bytes[offset..],
+            &mut consumed,
+            options,
+            limits,
+        )
+        .map_err(|err| match err.downcast::<AbiError>() {
+            Ok(err @ AbiError::LimitExceeded { .. }) => err,
+            // The param bytes are decoded alone, report the offset in the whole bytes.
+            Ok(AbiError::InvalidUtf8 {
+                offset: utf8_offset,
+            }) => AbiError::InvalidUtf8 {
+                offset: offset + utf8_offset,
+            },
+            Ok(err) => AbiError::Decode {
+                ty: type_name.to_string(),
+                reason: err.to_string(),
+            },
+            Err(err) => AbiError::Decode {
+                ty: type_name.to_string(),
+                reason: err.to_string(),
+            },
+        })?;
+        offset += consumed;
+        params.push(param);
+    }
+    if strict_trailing && offset < bytes.len() {
+        return Err(AbiError::TrailingBytes {
+            remaining: bytes.len() - offset,
+        });
+    }
+    Ok(params)
 }
 
 #[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
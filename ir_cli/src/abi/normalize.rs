@@ -0,0 +1,110 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The normalized form of the abi meta, where the composite type names repeated across
+//! the method inputs and outputs, i.e., the variants such as `enum<u8,[u8;32]>` and the
+//! fixed-size arrays, are hoisted into the `types` table and referenced by the ids such
+//! as `#0`. It shrinks the large abi files and makes the type level diffs cleaner.
+
+use super::{type_name_to_abi_param_type, IRContractABIMeta};
+use smart_ir::abi::error::AbiError;
+use smart_ir::abi::types::{ABIParamType, ABITypeTable};
+use std::collections::HashMap;
+
+/// The prefix of the type references to the `types` table, e.g., `#0`.
+pub use smart_ir::abi::types::TYPE_REF_PREFIX;
+
+fn is_composite_type(type_name: &str) -> bool {
+    matches!(
+        type_name_to_abi_param_type(type_name),
+        Ok(ABIParamType::Variant(_) | ABIParamType::FixedArray(..))
+    )
+}
+
+impl IRContractABIMeta {
+    /// The type names of the method inputs and outputs in the declaration order.
+    fn type_names_mut(&mut self) -> impl Iterator<Item = &mut String> {
+        self.methods.iter_mut().flat_map(|method| {
+            let input_types = method.inputs.iter_mut().map(|input| &mut input.r#type);
+            let output_types = method.outputs.iter_mut().map(|output| &mut output.r#type);
+            input_types.chain(output_types)
+        })
+    }
+
+    /// Hoist the composite type names used more than once into the `types` table in the
+    /// order of their first use and replace them with the references. The encoders and
+    /// decoders work on the denormalized view, so call `denormalize` before using the
+    /// methods. It does nothing for the already normalized abi metas.
+    pub fn normalize(&mut self) {
+        if !self.types.is_empty() {
+            return;
+        }
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut types: Vec<String> = vec![];
+        for type_name in self.type_names_mut() {
+            let count = counts.entry(type_name.clone()).or_default();
+            *count += 1;
+            if *count == 2 && is_composite_type(type_name) {
+                types.push(type_name.clone());
+            }
+        }
+        let ids: HashMap<String, usize> = types
+            .iter()
+            .enumerate()
+            .map(|(id, type_name)| (type_name.clone(), id))
+            .collect();
+        for type_name in self.type_names_mut() {
+            if let Some(id) = ids.get(type_name.as_str()) {
+                *type_name = format!("{TYPE_REF_PREFIX}{id}");
+            }
+        }
+        self.types = types;
+    }
+
+    /// The types table of the `types`, which resolves the type references such as `#0` of
+    /// the method inputs and outputs, including the recursive types such as `enum<u64,#0>`
+    /// which `denormalize` can't inline. The params strings of them are encoded by
+    /// `IRContractMethodMeta::encode_params_with_type_table`.
+    pub fn type_table(&self) -> Result<ABITypeTable, AbiError> {
+        ABITypeTable::from_type_strs(&self.types)
+    }
+
+    /// Replace the type references with the type names of the `types` table and clear it,
+    /// which reverts `normalize`. The references out of the table and the types with type
+    /// references such as the recursive ones are errors, and the abi meta is unchanged then.
+    pub fn denormalize(&mut self) -> Result<(), AbiError> {
+        let types = std::mem::take(&mut self.types);
+        let lookup = |type_name: &str| -> Result<Option<&String>, AbiError> {
+            let id = match type_name.strip_prefix(TYPE_REF_PREFIX) {
+                Some(id) => id
+                    .parse::<usize>()
+                    .map_err(|_| AbiError::UnsupportedType(type_name.to_string()))?,
+                None => return Ok(None),
+            };
+            let referenced = types.get(id).ok_or(AbiError::UnresolvedTypeRef(id))?;
+            if referenced.contains(TYPE_REF_PREFIX) {
+                return Err(AbiError::UninlinableTypeRef(id));
+            }
+            Ok(Some(referenced))
+        };
+        // Check all the references before replacing any of them.
+        let mut error = None;
+        for type_name in self.type_names_mut() {
+            if let Err(err) = lookup(type_name) {
+                error = Some(err);
+                break;
+            }
+        }
+        if let Some(err) = error {
+            self.types = types;
+            return Err(err);
+        }
+        for type_name in self.type_names_mut() {
+            if let Ok(Some(referenced)) = lookup(type_name) {
+                *type_name = referenced.clone();
+            }
+        }
+        Ok(())
+    }
+}
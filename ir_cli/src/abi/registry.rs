@@ -0,0 +1,102 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{IRContractABIMeta, IRContractMethodMeta};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The file name suffix of the abi json files loaded by `AbiRegistry::load_dir`.
+pub const ABI_FILE_SUFFIX: &str = ".abi.json";
+
+/// The abi metas of the contracts in a workspace keyed by the contract names, so that
+/// a router can dispatch the call data across the contracts.
+#[derive(Debug, Default, Clone)]
+pub struct AbiRegistry {
+    pub contracts: HashMap<String, IRContractABIMeta>,
+}
+
+impl AbiRegistry {
+    /// Load all the `*.abi.json` files in the directory, the contracts are named by the
+    /// `name` of the abi metas, or the file names without the suffix for the older abi
+    /// files without names. Two abi files of the same contract name are an error.
+    pub fn load_dir(path: impl AsRef<Path>) -> Result<AbiRegistry, anyhow::Error> {
+        let path = path.as_ref();
+        let mut file_paths = vec![];
+        for entry in std::fs::read_dir(path)
+            .map_err(|e| anyhow::anyhow!("could not read dir {}: {}", path.display(), e))?
+        {
+            let file_path = entry?.path();
+            let is_abi_file = file_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(ABI_FILE_SUFFIX));
+            if is_abi_file && file_path.is_file() {
+                file_paths.push(file_path);
+            }
+        }
+        // Load the files in order so that the duplicate errors are reproducible.
+        file_paths.sort();
+        let mut registry = AbiRegistry::default();
+        for file_path in file_paths {
+            let json = std::fs::read(&file_path)
+                .map_err(|e| anyhow::anyhow!("could not read {}: {}", file_path.display(), e))?;
+            let abi: IRContractABIMeta = serde_json::from_slice(&json).map_err(|e| {
+                anyhow::anyhow!("could not deserialize {}: {}", file_path.display(), e)
+            })?;
+            let name = match &abi.name {
+                Some(name) => name.clone(),
+                None => file_path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_suffix(ABI_FILE_SUFFIX))
+                    .unwrap_or_default()
+                    .to_string(),
+            };
+            if registry.insert(name.clone(), abi).is_some() {
+                return Err(anyhow::anyhow!(
+                    "duplicate contract {} in {}",
+                    name,
+                    file_path.display()
+                ));
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Add the abi meta of the contract, returns the replaced abi meta of the same name.
+    pub fn insert(
+        &mut self,
+        contract: String,
+        abi: IRContractABIMeta,
+    ) -> Option<IRContractABIMeta> {
+        self.contracts.insert(contract, abi)
+    }
+
+    /// Get the method of the contract by the method name such as `transfer`, which is
+    /// resolved to the first one of the overloaded methods in the same way as
+    /// `IRContractABIMeta::get_method`, or by the signature such as `transfer(string,u64)`.
+    pub fn get(&self, contract: &str, method: &str) -> Option<&IRContractMethodMeta> {
+        let abi = self.contracts.get(contract)?;
+        abi.get_method(method)
+            .or_else(|| abi.methods.iter().find(|m| m.signature() == method))
+    }
+
+    /// Map the 4-byte method selectors to the contract names and the method signatures,
+    /// which tell the overloaded methods apart and are resolved by `get`. When the
+    /// selectors of the methods in different contracts collide, the one of the contract
+    /// name which sorts first is kept.
+    pub fn selector_index(&self) -> HashMap<[u8; 4], (String, String)> {
+        let mut contract_names: Vec<&String> = self.contracts.keys().collect();
+        contract_names.sort();
+        let mut index = HashMap::new();
+        for contract_name in contract_names {
+            for method in &self.contracts[contract_name].methods {
+                index
+                    .entry(method.selector())
+                    .or_insert_with(|| (contract_name.clone(), method.signature()));
+            }
+        }
+        index
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    IRContractABIMeta, CURRENT_IR_ABI_VERSION, METHOD_TYPE_CONSTRUCTOR, METHOD_TYPE_FUNCTION,
+};
+use serde_json::{json, Value};
+
+/// The JSON Schema dialect of the generated abi schema.
+pub const ABI_JSON_SCHEMA_DIALECT: &str = "http://json-schema.org/draft-07/schema#";
+
+impl IRContractABIMeta {
+    /// Generate the JSON Schema of the abi json format for the `CURRENT_IR_ABI_VERSION`,
+    /// so that hand-written abi files can be linted before feeding them to the tools.
+    /// The older abi versions are accepted since their fields are a subset of the current
+    /// ones. Keep it in sync with the serde representation: the fields since
+    /// `ABI_VERSION_2` are optional because they are decoded with `#[serde(default)]`
+    /// and not written when empty, and
+    /// unknown fields are allowed because serde ignores them, they are rejected by
+    /// `from_json_strict` instead.
+    pub fn json_schema() -> Value {
+        let type_name = json!({ "type": "string", "minLength": 1 });
+        json!({
+            "$schema": ABI_JSON_SCHEMA_DIALECT,
+            "title": "IRContractABIMeta",
+            "type": "object",
+            "required": ["abi_version", "methods"],
+            "properties": {
+                "abi_version": { "type": "integer", "minimum": 0, "maximum": CURRENT_IR_ABI_VERSION },
+                "methods": { "type": "array", "items": { "$ref": "#/definitions/method" } },
+                "constants": { "type": "array", "items": { "$ref": "#/definitions/constant" } },
+                "errors": { "type": "array", "items": { "$ref": "#/definitions/error" } },
+                "name": { "type": ["string", "null"] },
+                "version": { "type": ["string", "null"] },
+                "types": { "type": "array", "items": type_name },
+            },
+            "definitions": {
+                "method": {
+                    "type": "object",
+                    "required": ["name", "type", "inputs", "outputs"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "type": { "enum": [METHOD_TYPE_CONSTRUCTOR, METHOD_TYPE_FUNCTION] },
+                        "inputs": { "type": "array", "items": { "$ref": "#/definitions/input" } },
+                        "outputs": { "type": "array", "items": { "$ref": "#/definitions/output" } },
+                        "qualified_name": { "type": "string" },
+                        "description": { "type": ["string", "null"] },
+                        "inherited_from": { "type": ["string", "null"] },
+                    },
+                },
+                "input": {
+                    "type": "object",
+                    "required": ["name", "type"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "type": type_name,
+                        "indexed": { "type": "boolean" },
+                        "description": { "type": ["string", "null"] },
+                    },
+                },
+                "output": {
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": {
+                        "type": type_name,
+                    },
+                },
+                "constant": {
+                    "type": "object",
+                    "required": ["type", "data", "readable"],
+                    "properties": {
+                        "type": type_name,
+                        "data": { "type": "string", "pattern": "^([0-9a-fA-F]{2})*$" },
+                        "readable": { "type": "string" },
+                    },
+                },
+                "error": {
+                    "type": "object",
+                    "required": ["name", "code", "fields"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "code": { "type": "integer", "minimum": 0, "maximum": u32::MAX },
+                        "fields": { "type": "array", "items": { "$ref": "#/definitions/input" } },
+                    },
+                },
+            },
+        })
+    }
+}
+
+/// The json path such as `$.methods[0].inptus` of the first field which is not declared
+/// in the `json_schema`. The values mismatching the schema types are not reported here,
+/// they are left to the deserialization errors.
+pub(crate) fn unknown_field_path(value: &Value) -> Option<String> {
+    let schema = IRContractABIMeta::json_schema();
+    find_unknown_field(&schema, &schema, value, "$".to_string())
+}
+
+fn find_unknown_field(root: &Value, schema: &Value, value: &Value, path: String) -> Option<String> {
+    let schema = match schema.get("$ref").and_then(Value::as_str) {
+        Some(reference) => root.pointer(reference.trim_start_matches('#'))?,
+        None => schema,
+    };
+    match value {
+        Value::Object(fields) => {
+            let properties = schema.get("properties")?.as_object()?;
+            fields.iter().find_map(|(key, field)| {
+                let path = format!("{path}.{key}");
+                match properties.get(key) {
+                    Some(field_schema) => find_unknown_field(root, field_schema, field, path),
+                    None => Some(path),
+                }
+            })
+        }
+        Value::Array(items) => {
+            let items_schema = schema.get("items")?;
+            items.iter().enumerate().find_map(|(i, item)| {
+                find_unknown_field(root, items_schema, item, format!("{path}[{i}]"))
+            })
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The hash functions of the method selectors. The chains hash the canonical method
+//! signatures differently, so the selector is the first 4 bytes of the hash given by a
+//! `SelectorHasher`. The crate default is keccak256, which is the Ethereum-compatible
+//! selector used by `IRContractMethodMeta::selector`, `encode_call` and `decode_call`.
+
+use super::IRContractMethodMeta;
+use crypto::blake2b::Blake2b;
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use keccak_hash::keccak;
+
+/// The 256-bit hash function of the canonical method signatures.
+pub trait SelectorHasher {
+    /// Hash the canonical method signature bytes such as `transfer(string,u64)`.
+    fn hash(&self, signature: &[u8]) -> [u8; 32];
+}
+
+/// The keccak256 hash of Ethereum, which is the crate default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Keccak256Hasher;
+
+/// The SHA-256 hash.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha256Hasher;
+
+/// The BLAKE2b hash with the 32-byte output, i.e., BLAKE2b-256.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blake2b256Hasher;
+
+/// The selector hasher of `IRContractMethodMeta::selector`.
+pub type DefaultSelectorHasher = Keccak256Hasher;
+
+impl SelectorHasher for Keccak256Hasher {
+    fn hash(&self, signature: &[u8]) -> [u8; 32] {
+        keccak(signature).0
+    }
+}
+
+fn digest_hash(mut digest: impl Digest, signature: &[u8]) -> [u8; 32] {
+    let mut hash = [0; 32];
+    digest.input(signature);
+    digest.result(&mut hash);
+    hash
+}
+
+impl SelectorHasher for Sha256Hasher {
+    fn hash(&self, signature: &[u8]) -> [u8; 32] {
+        digest_hash(Sha256::new(), signature)
+    }
+}
+
+impl SelectorHasher for Blake2b256Hasher {
+    fn hash(&self, signature: &[u8]) -> [u8; 32] {
+        digest_hash(Blake2b::new(32), signature)
+    }
+}
+
+impl IRContractMethodMeta {
+    /// The 4-byte method selector of the `hasher`, which is the first 4 bytes of its hash
+    /// of the canonical method signature, so that the calls can target the chains not
+    /// using keccak256, e.g., `selector_with(&Sha256Hasher)`.
+    pub fn selector_with(&self, hasher: &impl SelectorHasher) -> [u8; 4] {
+        let hash = hasher.hash(self.signature().as_bytes());
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+}
@@ -10,6 +10,7 @@ use compiler_base_span::{FilePathMapping, SourceMap};
 use ir_cli::abi::IRContractABIMeta;
 use ir_cli::vm::WASM_IR;
 use ir_cli::vm::{init_mock_runtime, MockRuntime};
+use smart_ir::abi::error::AbiError;
 use smart_ir::ir::context::IRContext;
 use smart_ir::ir::frontend::translate::translate_main_module;
 use smart_ir::ir::printer::IRPrinter;
@@ -22,8 +23,17 @@ use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use wasmi::*;
 
+/// Load the abi json file of the `abi verify`, `abi explain` and `abi fuzz` commands.
+fn load_abi_file(path: &str) -> anyhow::Result<IRContractABIMeta> {
+    let json =
+        fs::read(path).map_err(|e| anyhow::anyhow!("could not read abi file {}: {}", path, e))?;
+    serde_json::from_slice(&json)
+        .map_err(|e| anyhow::anyhow!("could not deserialize abi file {}: {}", path, e))
+}
+
 fn main() {
     let matches = clap_app!(ir_cli =>
         (version: "0.1.0")
@@ -31,11 +41,33 @@ fn main() {
             (@arg INPUT: +required +multiple "Sets the input wasm file and ir abi json and args to run")
             (@arg FUNC: -f --func +takes_value +required "Set the func name to call")
             (@arg VERBOSE: -v --verbose "Print test information verbosely")
+            (@arg NAMED: -n --named "Pass the args as name=value pairs in any order")
         )
         (@subcommand build =>
             (@arg INPUT: +required +multiple "Sets the input textual ir file to build")
             (@arg VERBOSE: -v --verbose "Print test information verbosely")
         )
+        (@subcommand abi =>
+            (@subcommand gen =>
+                (@arg INPUT: -i --input +takes_value +required "Sets the input textual ir file to generate the abi")
+                (@arg OUTPUT: -o --output +takes_value "Sets the output abi json file, default is a.out.abi.json")
+                (@arg ETH: --eth "Emit the Ethereum-compatible abi json")
+            )
+            (@subcommand verify =>
+                (@arg IR: --ir +takes_value +required "Sets the input textual ir file of the contract")
+                (@arg ABI: --abi +takes_value +required "Sets the published abi json file to verify")
+            )
+            (@subcommand explain =>
+                (@arg ABI: --abi +takes_value +required "Sets the abi json file to explain")
+                (@arg COLOR: --color "Highlight the output with the ANSI colors")
+            )
+            (@subcommand fuzz =>
+                (@arg ABI: --abi +takes_value +required "Sets the abi json file of the method")
+                (@arg METHOD: --method +takes_value +required "Sets the method name to fuzz")
+                (@arg COUNT: --count +takes_value "Sets the number of the arg sets, default is 16")
+                (@arg SEED: --seed +takes_value "Sets the random seed, default is from the clock")
+            )
+        )
     ).get_matches();
 
     if let Some(matches) = matches.subcommand_matches("run") {
@@ -106,15 +138,35 @@ fn main() {
             let abi_method = abi_method.unwrap();
 
             // inputs should be strings, and encode them by ir_abi_meta_info
-            if !input_args.is_empty() {
-                let encoded = abi_method.encode_params(&input_args);
-                if encoded.is_err() {
-                    println!("error: encode params error {}", encoded.err().unwrap());
+            match abi_method.encode_run_args(&input_args, matches.is_present("NAMED")) {
+                Ok(encoded) => mock_runtime.vm.input = encoded,
+                Err(errors) => {
+                    for err in errors {
+                        println!("error: encode params error {err}");
+                        match err {
+                            AbiError::ArityMismatch { expected, got } => {
+                                let (action, count) = if got < expected {
+                                    ("add", expected - got)
+                                } else {
+                                    ("remove", got - expected)
+                                };
+                                println!(
+                                    "hint: {action} {count} args to call {}",
+                                    abi_method.signature()
+                                );
+                            }
+                            AbiError::UnnamedParam(_)
+                            | AbiError::MalformedNamedParam(_)
+                            | AbiError::UnknownParam(_)
+                            | AbiError::DuplicateParam(_)
+                            | AbiError::MissingParam(_) => {
+                                println!("hint: pass the args of {}", abi_method.signature());
+                            }
+                            _ => {}
+                        }
+                    }
                     std::process::exit(1);
                 }
-                mock_runtime.vm.input = encoded.unwrap();
-            } else {
-                mock_runtime.vm.input = hex::decode("00").unwrap();
             }
 
             if let Err(err) = module_ref.invoke_export(func_name, &[], &mut mock_runtime) {
@@ -162,7 +214,8 @@ fn main() {
             if let Some(ctx_main_module) = ctx_main_module {
                 if let Some(main_contract) = &ctx_main_module.contract {
                     // dump contract meta json (IRContractABIMeta)
-                    let ir_contract_abi_info = IRContractABIMeta::from_contract(main_contract);
+                    let ir_contract_abi_info =
+                        IRContractABIMeta::from_contract_in_context(&ctx, main_contract);
                     println!("ir_contract_abi_info: {ir_contract_abi_info:?}");
                     let ir_contract_abi_json = ir_contract_abi_info.to_json();
                     let ir_abi_json_filepath = "a.out.abi.json";
@@ -171,6 +224,143 @@ fn main() {
                 }
             }
         }
+    } else if let Some(matches) = matches.subcommand_matches("abi") {
+        if let Some(matches) = matches.subcommand_matches("gen") {
+            let filename = matches.value_of("INPUT").unwrap();
+            let ir_abi_json_filepath = matches.value_of("OUTPUT").unwrap_or("a.out.abi.json");
+            let src = match std::fs::read_to_string(filename) {
+                Ok(src) => src,
+                Err(err) => {
+                    println!("error: failed to load source file {filename}: {err}");
+                    std::process::exit(1);
+                }
+            };
+            let ir_contract_abi_info = match IRContractABIMeta::from_ir_source(&src) {
+                Ok(ir_contract_abi_info) => ir_contract_abi_info,
+                Err(err) => {
+                    println!("error: {filename}: {err}");
+                    std::process::exit(1);
+                }
+            };
+            if ir_contract_abi_info.constructor().is_none() {
+                println!("warning: {filename}: the contract has no constructor");
+            }
+            if ir_contract_abi_info.is_empty() {
+                println!("warning: {filename}: the contract has no callable functions");
+            }
+            let ir_contract_abi_json = if matches.is_present("ETH") {
+                match ir_contract_abi_info.to_eth_json() {
+                    Ok(json) => json,
+                    Err(err) => {
+                        println!("error: {err}");
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                ir_contract_abi_info.to_json()
+            };
+            if let Err(err) = std::fs::write(ir_abi_json_filepath, ir_contract_abi_json) {
+                println!("error: failed to write {ir_abi_json_filepath}: {err}");
+                std::process::exit(1);
+            }
+            println!("writen file {ir_abi_json_filepath}");
+        } else if let Some(matches) = matches.subcommand_matches("verify") {
+            let ir_filename = matches.value_of("IR").unwrap();
+            let abi_filename = matches.value_of("ABI").unwrap();
+            let src = match std::fs::read_to_string(ir_filename) {
+                Ok(src) => src,
+                Err(err) => {
+                    println!("error: failed to load source file {ir_filename}: {err}");
+                    std::process::exit(1);
+                }
+            };
+            let ir_contract_abi_info = match IRContractABIMeta::from_ir_source(&src) {
+                Ok(ir_contract_abi_info) => ir_contract_abi_info,
+                Err(err) => {
+                    println!("error: {ir_filename}: {err}");
+                    std::process::exit(1);
+                }
+            };
+            let published_abi_info = match load_abi_file(abi_filename) {
+                Ok(published_abi_info) => published_abi_info,
+                Err(err) => {
+                    println!("error: {err}");
+                    std::process::exit(1);
+                }
+            };
+            let diff = published_abi_info.diff(&ir_contract_abi_info);
+            if !diff.is_empty() {
+                println!("error: {abi_filename} does not match {ir_filename}");
+                print!("{diff}");
+                std::process::exit(1);
+            }
+            println!("{abi_filename} matches {ir_filename}");
+        } else if let Some(matches) = matches.subcommand_matches("explain") {
+            let abi_filename = matches.value_of("ABI").unwrap();
+            let abi_info = match load_abi_file(abi_filename) {
+                Ok(abi_info) => abi_info,
+                Err(err) => {
+                    println!("error: {err}");
+                    std::process::exit(1);
+                }
+            };
+            print!("{}", abi_info.explain(matches.is_present("COLOR")));
+        } else if let Some(matches) = matches.subcommand_matches("fuzz") {
+            let abi_filename = matches.value_of("ABI").unwrap();
+            let method_name = matches.value_of("METHOD").unwrap();
+            let abi_info = match load_abi_file(abi_filename) {
+                Ok(abi_info) => abi_info,
+                Err(err) => {
+                    println!("error: {err}");
+                    std::process::exit(1);
+                }
+            };
+            let method = match abi_info.get_method(method_name) {
+                Some(method) => method,
+                None => {
+                    println!("error: method {method_name} not found in {abi_filename}");
+                    std::process::exit(1);
+                }
+            };
+            let count = match matches.value_of("COUNT").map(str::parse::<usize>) {
+                None => 16,
+                Some(Ok(count)) => count,
+                Some(Err(err)) => {
+                    println!("error: invalid count: {err}");
+                    std::process::exit(1);
+                }
+            };
+            let seed = match matches.value_of("SEED").map(str::parse::<u64>) {
+                Some(Ok(seed)) => seed,
+                Some(Err(err)) => {
+                    println!("error: invalid seed: {err}");
+                    std::process::exit(1);
+                }
+                None => {
+                    let seed = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|elapsed| elapsed.as_nanos() as u64)
+                        .unwrap_or_default();
+                    // Printed to stderr to keep the stdout blobs only, rerun with the seed.
+                    eprintln!("seed: {seed}");
+                    seed
+                }
+            };
+            match method.fuzz_params(seed, count) {
+                // One hex blob per line, which can be piped to a runner.
+                Ok(blobs) => {
+                    for blob in blobs {
+                        println!("{}", hex::encode(blob));
+                    }
+                }
+                Err(err) => {
+                    println!("error: fuzz {} error {err}", method.signature());
+                    std::process::exit(1);
+                }
+            }
+        } else {
+            println!("{}", matches.usage());
+        }
     } else {
         println!("{}", matches.usage());
     }
@@ -4,6 +4,8 @@
 
 pub mod abi;
 pub mod vm;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 #[cfg(test)]
 pub mod tests;
@@ -0,0 +1,3270 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::abi::builder::MethodMetaBuilder;
+use crate::abi::diff::IRContractABIDiff;
+use crate::abi::frame::crc32;
+use crate::abi::registry::AbiRegistry;
+use crate::abi::selector::{Blake2b256Hasher, Keccak256Hasher, SelectorHasher, Sha256Hasher};
+use crate::abi::{
+    FunctionSignature, IRConstantMeta, IRContractABIMeta, IRContractErrorMeta,
+    IRContractMethodInputMeta, IRContractMethodMeta, IRContractMethodOutputMeta,
+    CURRENT_IR_ABI_VERSION,
+};
+use keccak_hash::keccak;
+use smart_ir::abi::error::AbiError;
+use smart_ir::abi::params::{
+    decode, decode_abi_param_with_types, decode_version, ABIParam, DecodeLimits, EncodingOptions,
+    EncodingVersion, Endianness, MAX_TYPE_REF_DEPTH,
+};
+use smart_ir::abi::types::{ABIParamType, ABITypeTable};
+use smart_ir::encoding::datastream::{ParamType, DEFAULT_VERSION};
+use smart_ir::ir::cfg::{Contract, IntLiteral, Literal, Type};
+use smart_ir::ir::context::IRContext;
+use smart_ir::ir::frontend::parser::compile;
+use smart_ir::ir::frontend::translate::translate_main_module;
+use std::rc::Rc;
+
+/// Compile the ir source and call `f` with its main contract.
+fn with_contract(src: &str, f: impl FnOnce(&Contract)) {
+    let module = compile(src);
+    let mut ctx = IRContext::default();
+    translate_main_module(&mut ctx, &module);
+    let main_module = ctx.get_main_module().unwrap();
+    f(main_module.contract.as_ref().unwrap());
+}
+
+#[test]
+fn constant_meta_from_literal() {
+    let int_constant = IRConstantMeta::from_literal(&Literal::Int(IntLiteral::U32(258)));
+    assert_eq!(int_constant.r#type, "u32");
+    assert_eq!(int_constant.data, "02010000");
+    assert_eq!(int_constant.readable, "258");
+
+    let str_constant = IRConstantMeta::from_literal(&Literal::Str("hi".to_string()));
+    assert_eq!(str_constant.r#type, "string");
+    assert_eq!(str_constant.data, "026869");
+    assert_eq!(str_constant.readable, "\"hi\"");
+}
+
+#[test]
+fn abi_without_constants_field() {
+    let abi = IRContractABIMeta::from_json(br#"{"abi_version": 1, "methods": []}"#);
+    assert!(abi.constants.is_empty());
+}
+
+#[test]
+fn decode_contract_error() {
+    let abi = IRContractABIMeta {
+        errors: vec![IRContractErrorMeta {
+            name: "InsufficientBalance".to_string(),
+            code: 2,
+            fields: vec![
+                IRContractMethodInputMeta {
+                    name: "account".to_string(),
+                    r#type: "str".to_string(),
+                    ..Default::default()
+                },
+                IRContractMethodInputMeta {
+                    name: "balance".to_string(),
+                    r#type: "u64".to_string(),
+                    ..Default::default()
+                },
+            ],
+        }],
+        ..Default::default()
+    };
+    let mut revert_data = 2u32.to_le_bytes().to_vec();
+    revert_data.append(&mut ABIParam::Str("alice".to_string()).as_bytes());
+    revert_data.append(&mut ABIParam::U64(10).as_bytes());
+    let (error, fields) = abi.decode_error(&revert_data).unwrap();
+    assert_eq!(error.name, "InsufficientBalance");
+    assert_eq!(
+        fields,
+        vec![ABIParam::Str("alice".to_string()), ABIParam::U64(10)]
+    );
+    assert!(abi.decode_error(&3u32.to_le_bytes()).is_err());
+}
+
+#[test]
+fn get_method_from_large_abi() {
+    let mut abi = IRContractABIMeta::default();
+    for i in 0..500 {
+        abi.methods.push(IRContractMethodMeta {
+            name: format!("method{i}"),
+            r#type: "function".to_string(),
+            ..Default::default()
+        });
+    }
+    assert_eq!(abi.get_method("method0").unwrap().name, "method0");
+    assert_eq!(abi.get_method("method499").unwrap().name, "method499");
+    assert!(abi.get_method("method500").is_none());
+    // The index is rebuilt after the deserialization.
+    let abi = IRContractABIMeta::from_json(&abi.to_json());
+    assert_eq!(abi.get_method("method250").unwrap().name, "method250");
+}
+
+#[test]
+fn get_overloaded_method() {
+    let mut abi = IRContractABIMeta::default();
+    for input_types in [vec!["str", "u64"], vec!["str", "u64", "str"]] {
+        abi.methods.push(IRContractMethodMeta {
+            name: "transfer".to_string(),
+            r#type: "function".to_string(),
+            inputs: input_types
+                .iter()
+                .map(|ty| IRContractMethodInputMeta {
+                    name: "".to_string(),
+                    r#type: ty.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        });
+    }
+    assert_eq!(abi.get_methods("transfer").len(), 2);
+    let method = abi
+        .get_method_by_signature("transfer", &["str", "u64", "str"])
+        .unwrap();
+    assert_eq!(method.inputs.len(), 3);
+    assert!(abi
+        .get_method_by_signature("transfer", &["str", "u32"])
+        .is_none());
+}
+
+#[test]
+fn get_method_case_insensitive() {
+    let mut abi = IRContractABIMeta::default();
+    for (name, input_type) in [("transfer", "u64"), ("transfer", "u32"), ("balance", "str")] {
+        abi.methods.push(
+            MethodMetaBuilder::default()
+                .name(name)
+                .function()
+                .input("a", input_type)
+                .build()
+                .unwrap(),
+        );
+    }
+    let method = abi.get_method_ci("Transfer").unwrap().unwrap();
+    assert_eq!(method.signature(), "transfer(u64)");
+    assert_eq!(
+        abi.get_method_ci("BALANCE").unwrap().unwrap().name,
+        "balance"
+    );
+    assert!(abi.get_method_ci("mint").unwrap().is_none());
+    // The case-sensitive lookup is unchanged.
+    assert!(abi.get_method("Transfer").is_none());
+
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("Transfer")
+            .function()
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(
+        abi.get_method_ci("transfer").unwrap_err(),
+        "method name transfer is ambiguous, it matches transfer and Transfer"
+    );
+    assert_eq!(abi.get_method("Transfer").unwrap().inputs.len(), 0);
+}
+
+#[test]
+fn cbor_round_trip() {
+    let abi = IRContractABIMeta::from_json(
+        br#"{
+            "abi_version": 1,
+            "methods": [
+                {
+                    "name": "transfer",
+                    "type": "function",
+                    "inputs": [{"name": "to", "type": "str"}, {"name": "amount", "type": "u64"}],
+                    "outputs": [{"type": "bool"}]
+                }
+            ]
+        }"#,
+    );
+    let cbor_bytes = abi.to_cbor().unwrap();
+    assert!(cbor_bytes.len() < abi.to_json().len());
+    let cbor_abi = IRContractABIMeta::from_cbor(&cbor_bytes).unwrap();
+    assert_eq!(cbor_abi.to_json(), abi.to_json());
+    assert!(IRContractABIMeta::from_cbor(&cbor_bytes[1..]).is_err());
+}
+
+#[test]
+fn encode_float_params() {
+    let method = IRContractMethodMeta {
+        name: "report".to_string(),
+        r#type: "function".to_string(),
+        inputs: ["f32", "f64", "[f64]"]
+            .iter()
+            .map(|ty| IRContractMethodInputMeta {
+                name: "".to_string(),
+                r#type: ty.to_string(),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let encoded = method.encode_params(&["1.5", "-0.25", "1,2.5"]).unwrap();
+    let decoded = decode(
+        &[ParamType::F32, ParamType::F64, ParamType::F64Array],
+        &encoded,
+    )
+    .unwrap();
+    assert_eq!(
+        decoded,
+        vec![
+            ABIParam::F32(1.5),
+            ABIParam::F64(-0.25),
+            ABIParam::F64Array(vec![1.0, 2.5])
+        ]
+    );
+    assert!(method.encode_params(&["abc", "1", "1"]).is_err());
+    assert!(method.encode_params(&["1", "NaN", "1"]).is_err());
+    assert!(method.encode_params(&["1", "1", "1,inf"]).is_err());
+}
+
+#[test]
+fn encode_bytes_params() {
+    let method = MethodMetaBuilder::default()
+        .name("store")
+        .function()
+        .input("empty", "bytes")
+        .input("blob", "bytes")
+        .build()
+        .unwrap();
+    let blob: Vec<u8> = (0..8192).map(|i| (i % 251) as u8).collect();
+    let encoded = method
+        .encode_params(&["", &format!("0x{}", hex::encode(&blob))])
+        .unwrap();
+    assert_eq!(
+        method.decode_params(&encoded).unwrap(),
+        [ABIParam::Bytes(vec![]), ABIParam::Bytes(blob)]
+    );
+    assert_eq!(
+        method.encode_params(&["", "0xzz"]).unwrap_err().to_string(),
+        "invalid hex string 0xzz: non-hex character 'z' at offset 2"
+    );
+}
+
+#[test]
+fn encode_fixed_array_params() {
+    let method = MethodMetaBuilder::default()
+        .name("store")
+        .function()
+        .input("key", "[u8; 4]")
+        .input("tail", "u8")
+        .build()
+        .unwrap();
+    let encoded = method.encode_params(&["1,2,3,4", "5"]).unwrap();
+    // The fixed-size array elements are not length-prefixed.
+    assert_eq!(encoded, [DEFAULT_VERSION, 1, 2, 3, 4, 5]);
+    assert_eq!(
+        method.decode_params(&encoded).unwrap(),
+        [
+            ABIParam::fixed_array(ABIParam::U8Array(vec![1, 2, 3, 4]), 4).unwrap(),
+            ABIParam::U8(5)
+        ]
+    );
+    assert_eq!(method.signature(), "store([u8;4],u8)");
+    for (key, got) in [("1,2,3", 3), ("1,2,3,4,5", 5), ("", 0)] {
+        assert_eq!(
+            method.encode_params(&[key, "5"]).unwrap_err(),
+            AbiError::FixedArrayLength { expected: 4, got }
+        );
+    }
+    assert_eq!(
+        method.validate_params(&["1,2,3", "5"]).unwrap_err()[0].to_string(),
+        "param 0 key: expect 4 fixed-size array elements, but got 3"
+    );
+    let json_encoded = method
+        .encode_params_json(&serde_json::json!({"key": [1, 2, 3, 4], "tail": 5}))
+        .unwrap();
+    assert_eq!(json_encoded, encoded);
+    assert_eq!(
+        method.decode_params_json(&encoded).unwrap(),
+        serde_json::json!({"key": [1, 2, 3, 4], "tail": 5})
+    );
+}
+
+#[test]
+fn method_meta_hash_eq_on_signature() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let hash = |method: &IRContractMethodMeta| {
+        let mut hasher = DefaultHasher::new();
+        method.hash(&mut hasher);
+        hasher.finish()
+    };
+    let transfer = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .output("bool")
+        .build()
+        .unwrap();
+    let renamed = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("receiver", "string")
+        .input("value", "u64")
+        .build()
+        .unwrap();
+    assert_eq!(transfer.signature_key(), "transfer(string,u64)");
+    assert_eq!(transfer, renamed);
+    assert_eq!(hash(&transfer), hash(&renamed));
+    let overloaded = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .build()
+        .unwrap();
+    assert_ne!(transfer, overloaded);
+    let cache: std::collections::HashSet<IRContractMethodMeta> =
+        [transfer, renamed, overloaded].into_iter().collect();
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn decode_invalid_utf8_string() {
+    let method = MethodMetaBuilder::default()
+        .name("set")
+        .function()
+        .input("id", "u8")
+        .input("key", "str")
+        .build()
+        .unwrap();
+    // The strings are prefixed with the uleb128 byte length.
+    let encoded = method.encode_params(&["7", "ab"]).unwrap();
+    assert_eq!(encoded, [DEFAULT_VERSION, 7, 2, b'a', b'b']);
+    assert_eq!(
+        method.decode_params(&[DEFAULT_VERSION, 7, 2, b'a', 0xff]),
+        Err(AbiError::InvalidUtf8 { offset: 4 })
+    );
+    let map_method = MethodMetaBuilder::default()
+        .name("set")
+        .function()
+        .input("flags", "{str:bool}")
+        .build()
+        .unwrap();
+    assert_eq!(
+        map_method
+            .decode_params(&[DEFAULT_VERSION, 1, 2, 0xc3, 0x28, 1])
+            .unwrap_err()
+            .to_string(),
+        "invalid utf-8 string bytes at offset 3"
+    );
+}
+
+#[test]
+fn encode_parampack_hex() {
+    let method = IRContractMethodMeta {
+        name: "call".to_string(),
+        r#type: "function".to_string(),
+        inputs: vec![IRContractMethodInputMeta {
+            name: "data".to_string(),
+            r#type: "parampack".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    assert_eq!(
+        method.encode_params(&["0xdeadBEEF"]).unwrap(),
+        method.encode_params(&["deadbeef"]).unwrap()
+    );
+    assert_eq!(
+        method.encode_params(&["0xabc"]).unwrap_err().to_string(),
+        "invalid hex string 0xabc: odd number of hex digits 3"
+    );
+    assert_eq!(
+        method.encode_params(&["0x12zz"]).unwrap_err().to_string(),
+        "invalid hex string 0x12zz: non-hex character 'z' at offset 4"
+    );
+}
+
+#[test]
+fn encode_u8_array_hex() {
+    let method = MethodMetaBuilder::default()
+        .name("call")
+        .function()
+        .input("data", "[u8]")
+        .build()
+        .unwrap();
+    assert_eq!(
+        method.encode_params(&["0xdeadbeef"]).unwrap(),
+        method.encode_params(&["222,173,190,239"]).unwrap()
+    );
+    assert_eq!(
+        method.encode_params(&[" 0XDEADBEEF "]).unwrap(),
+        method.encode_params(&["0xde,0xad,0xbe,0xef"]).unwrap()
+    );
+    assert_eq!(
+        method.encode_params(&["0x"]).unwrap(),
+        method.encode_params(&["[]"]).unwrap()
+    );
+    // The odd number of hex digits is a single hex element.
+    assert_eq!(
+        method.encode_params(&["0x5"]).unwrap(),
+        method.encode_params(&["5"]).unwrap()
+    );
+    assert_eq!(
+        method.encode_params(&["0xabc"]).unwrap_err().to_string(),
+        "[u8] element #0 out of range: 0xabc, expect [0, 255]"
+    );
+    // The fixed-size arrays accept the shorthand too.
+    let fixed_method = MethodMetaBuilder::default()
+        .name("call")
+        .function()
+        .input("data", "[u8;4]")
+        .build()
+        .unwrap();
+    assert_eq!(
+        fixed_method.encode_params(&["0xdeadbeef"]).unwrap(),
+        fixed_method.encode_params(&["222,173,190,239"]).unwrap()
+    );
+}
+
+#[test]
+fn param_offsets() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("amount", "u32")
+        .input("to", "str")
+        .input("memo", "str")
+        .input("flag", "bool")
+        .build()
+        .unwrap();
+    let params = ["7", "alice", "", "true"];
+    let offsets = method.param_offsets(&params).unwrap();
+    // The version byte, the 4-byte u32, the 1-byte length prefixes of the strs.
+    assert_eq!(offsets, [1, 5, 11, 12]);
+    let encoded = method.encode_params(&params).unwrap();
+    assert_eq!(encoded.len(), 13);
+    assert_eq!(&encoded[offsets[0]..offsets[1]], 7u32.to_le_bytes());
+    assert_eq!(&encoded[offsets[1]..offsets[2]], b"\x05alice");
+    assert_eq!(&encoded[offsets[3]..], [1]);
+    // The long strings have multi-byte length prefixes.
+    let long = "a".repeat(200);
+    assert_eq!(
+        method.param_offsets(&["7", &long, "", "true"]).unwrap(),
+        [1, 5, 207, 208]
+    );
+    assert_eq!(
+        method.param_offsets(&["7"]),
+        Err(AbiError::ArityMismatch {
+            expected: 4,
+            got: 1
+        })
+    );
+}
+
+#[test]
+fn encode_named_params() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .input("memo", "str")
+        .build()
+        .unwrap();
+    let named = ["memo=a=b", "amount=7", "to=alice"];
+    let positional = method.named_params_to_positional(&named).unwrap();
+    assert_eq!(positional, ["alice", "7", "a=b"]);
+    assert_eq!(
+        method.encode_params(&positional).unwrap(),
+        method.encode_params(&["alice", "7", "a=b"]).unwrap()
+    );
+    assert_eq!(
+        method.named_params_to_positional(&["to=alice", "amount=7"]),
+        Err(AbiError::MissingParam("memo".to_string()))
+    );
+    assert_eq!(
+        method.named_params_to_positional(&["to=alice", "amount=7", "memo=", "fee=1"]),
+        Err(AbiError::UnknownParam("fee".to_string()))
+    );
+    assert_eq!(
+        method.named_params_to_positional(&["to=alice", "to=bob"]),
+        Err(AbiError::DuplicateParam("to".to_string()))
+    );
+    assert_eq!(
+        method
+            .named_params_to_positional(&["alice"])
+            .unwrap_err()
+            .to_string(),
+        "invalid named param alice, expected name=value"
+    );
+}
+
+#[test]
+fn encode_run_args() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .build()
+        .unwrap();
+    let positional = method.encode_params(&["alice", "7"]).unwrap();
+    assert_eq!(
+        method.encode_run_args(&["amount=7", "to=alice"], true),
+        Ok(positional.clone())
+    );
+    assert_eq!(
+        method.encode_run_args(&["alice", "7"], false),
+        Ok(positional)
+    );
+    assert_eq!(
+        method.encode_run_args(&["amount=7"], true),
+        Err(vec![AbiError::MissingParam("to".to_string())])
+    );
+    assert_eq!(
+        method.encode_run_args(&["amount=7", "to=alice", "fee=1"], true),
+        Err(vec![AbiError::UnknownParam("fee".to_string())])
+    );
+    // The named values are validated as the positional ones.
+    assert!(matches!(
+        method
+            .encode_run_args(&["amount=x", "to=alice"], true)
+            .unwrap_err()[..],
+        [AbiError::Param { index: 1, .. }]
+    ));
+    assert_eq!(
+        method.encode_run_args(&[], false),
+        Ok(vec![DEFAULT_VERSION])
+    );
+}
+
+#[test]
+fn encode_address_params() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "address20")
+        .input("account", "address32")
+        .input("memo", "address")
+        .build()
+        .unwrap();
+    let to = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    let account = format!("0x{}", "Ab".repeat(32));
+    let encoded = method.encode_params(&[to, &account, "0x0102"]).unwrap();
+    // Only the variable-length address is length-prefixed.
+    assert_eq!(encoded.len(), 1 + 20 + 32 + 3);
+    assert_eq!(
+        method.decode_params(&encoded).unwrap(),
+        [
+            ABIParam::Address {
+                bytes: hex::decode(&to[2..]).unwrap(),
+                len: Some(20)
+            },
+            ABIParam::Address {
+                bytes: vec![0xab; 32],
+                len: Some(32)
+            },
+            ABIParam::Address {
+                bytes: vec![1, 2],
+                len: None
+            },
+        ]
+    );
+    // The lowercase 20-byte address carries no checksum.
+    assert_eq!(
+        method
+            .encode_params(&[&to.to_lowercase(), &account, "0x0102"])
+            .unwrap(),
+        encoded
+    );
+    let bad_to = "0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    assert_eq!(
+        method
+            .encode_params(&[bad_to, &account, "0x0102"])
+            .unwrap_err()
+            .to_string(),
+        format!("invalid checksum of address {bad_to}, expect {to}")
+    );
+    assert_eq!(
+        method
+            .encode_params(&[to, "0x0102", "0x0102"])
+            .unwrap_err()
+            .to_string(),
+        "expect 32 address bytes, but got 2"
+    );
+}
+
+#[test]
+fn encode_params_with_type_overrides() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .build()
+        .unwrap();
+    let narrow_method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u32")
+        .build()
+        .unwrap();
+    assert_eq!(
+        method
+            .encode_params_with_types(&[("alice", None), ("7", Some("u32"))])
+            .unwrap(),
+        narrow_method.encode_params(&["alice", "7"]).unwrap()
+    );
+    assert_eq!(
+        method
+            .encode_params_with_types(&[("alice", None), ("7", None)])
+            .unwrap(),
+        method.encode_params(&["alice", "7"]).unwrap()
+    );
+    assert_eq!(
+        method
+            .encode_params_with_types(&[("alice", None), ("7", Some("u128"))])
+            .unwrap_err()
+            .to_string(),
+        "param 1 amount: type u128 can't override the declared type u64"
+    );
+    assert!(matches!(
+        method.encode_params_with_types(&[("alice", None), ("4294967296", Some("u32"))]),
+        Err(AbiError::IntOutOfRange { .. })
+    ));
+    assert!(matches!(
+        method.encode_params_with_types(&[("alice", None)]),
+        Err(AbiError::ArityMismatch { .. })
+    ));
+}
+
+#[test]
+fn decode_params_trailing_bytes() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .build()
+        .unwrap();
+    let bytes = method.encode_params(&["alice", "7"]).unwrap();
+    let params = vec![ABIParam::Str("alice".to_string()), ABIParam::U64(7)];
+    assert_eq!(method.decode_params(&bytes).unwrap(), params);
+    assert_eq!(
+        method.decode_params_with_trailing(&bytes, true).unwrap(),
+        params
+    );
+
+    let mut padded = bytes.clone();
+    padded.extend([0, 0, 0]);
+    assert_eq!(
+        method.decode_params(&padded),
+        Err(AbiError::TrailingBytes { remaining: 3 })
+    );
+    assert_eq!(
+        method
+            .decode_params_with_trailing(&padded, true)
+            .unwrap_err()
+            .to_string(),
+        "unexpected 3 trailing bytes after the params"
+    );
+    assert_eq!(
+        method.decode_params_with_trailing(&padded, false).unwrap(),
+        params
+    );
+
+    let empty_method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .build()
+        .unwrap();
+    assert!(empty_method.decode_params(&[DEFAULT_VERSION, 1]).is_err());
+    assert_eq!(
+        empty_method
+            .decode_params_with_trailing(&[DEFAULT_VERSION, 1], false)
+            .unwrap(),
+        []
+    );
+}
+
+#[test]
+fn encode_params_framed() {
+    // The check value of the CRC-32 (IEEE).
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .build()
+        .unwrap();
+    let payload = method.encode_params(&["alice", "7"]).unwrap();
+    let frame = method.encode_params_framed(&["alice", "7"]).unwrap();
+    assert_eq!(&frame[..4], (payload.len() as u32).to_le_bytes());
+    assert_eq!(&frame[4..frame.len() - 4], payload);
+    assert_eq!(
+        frame[frame.len() - 4..],
+        crc32(&frame[..frame.len() - 4]).to_le_bytes()
+    );
+    assert_eq!(
+        method.decode_params_framed(&frame).unwrap(),
+        method.decode_params(&payload).unwrap()
+    );
+
+    let mut corrupt = frame.clone();
+    corrupt[6] ^= 1;
+    assert!(matches!(
+        method.decode_params_framed(&corrupt),
+        Err(AbiError::FrameChecksum { .. })
+    ));
+    let mut corrupt_checksum = frame.clone();
+    *corrupt_checksum.last_mut().unwrap() ^= 0xff;
+    assert!(matches!(
+        method.decode_params_framed(&corrupt_checksum),
+        Err(AbiError::FrameChecksum { .. })
+    ));
+    assert_eq!(
+        method.decode_params_framed(&frame[..frame.len() - 1]),
+        Err(AbiError::FrameLength {
+            expected: frame.len(),
+            got: frame.len() - 1
+        })
+    );
+    assert_eq!(
+        method
+            .decode_params_framed(&[1, 0])
+            .unwrap_err()
+            .to_string(),
+        "expect 8 framed bytes, but got 2"
+    );
+    // The frame of no params is the shortest one.
+    let empty_method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .build()
+        .unwrap();
+    assert_eq!(
+        empty_method
+            .decode_params_framed(&empty_method.encode_params_framed(&[]).unwrap())
+            .unwrap(),
+        []
+    );
+}
+
+#[test]
+fn bincode_round_trip() {
+    let mut abi = IRContractABIMeta::from_json(br#"{"abi_version": 1, "methods": []}"#);
+    abi.methods.push(IRContractMethodMeta {
+        name: "f".to_string(),
+        r#type: "function".to_string(),
+        ..Default::default()
+    });
+    let bincode_bytes = abi.to_bincode().unwrap();
+    assert_eq!(
+        hex::encode(&bincode_bytes),
+        "0100010000000000000001000000000000006608000000000000006675\
+         6e6374696f6e000000000000000000000000000000000000000000000000\
+         00000000000000000000000000000000000000000000000000000000"
+    );
+    let bincode_abi = IRContractABIMeta::from_bincode(&bincode_bytes).unwrap();
+    assert_eq!(bincode_abi.to_json(), abi.to_json());
+    assert!(IRContractABIMeta::from_bincode(&bincode_bytes[..10]).is_err());
+}
+
+#[test]
+fn decode_void_and_empty_array_outputs() {
+    let mut method = IRContractMethodMeta {
+        name: "f".to_string(),
+        r#type: "function".to_string(),
+        ..Default::default()
+    };
+    // void
+    assert_eq!(method.decode_outputs(&[]).unwrap(), vec![]);
+    assert_eq!(method.decode_outputs(&[0x00]).unwrap(), vec![]);
+    assert!(method.decode_outputs(&[0x00, 0x00]).is_err());
+    // zero-length array
+    method.outputs.push(IRContractMethodOutputMeta {
+        r#type: "[u8]".to_string(),
+    });
+    assert_eq!(
+        method.decode_outputs(&[0x00, 0x00]).unwrap(),
+        vec![ABIParam::U8Array(vec![])]
+    );
+    assert!(method.decode_outputs(&[]).is_err());
+}
+
+#[test]
+fn decode_params_with_oversized_length() {
+    let method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("values", "[u64]")
+        .input("name", "str")
+        .build()
+        .unwrap();
+    // The version byte, then a u64 array claiming 1_000_000_000 elements.
+    let err = method
+        .decode_params(&[0x00, 0x80, 0x94, 0xeb, 0xdc, 0x03, 0x01, 0x02])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("only 2 bytes remain"), "{err}");
+    // An empty array, then a string claiming 1_000_000_000 bytes.
+    let err = method
+        .decode_params(&[0x00, 0x00, 0x80, 0x94, 0xeb, 0xdc, 0x03])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("only 0 bytes remain"), "{err}");
+    // Truncated integers are rejected rather than panicking.
+    let method = MethodMetaBuilder::default()
+        .name("g")
+        .function()
+        .input("value", "u32")
+        .build()
+        .unwrap();
+    assert!(method.decode_params(&[0x00, 0x01, 0x02]).is_err());
+}
+
+#[test]
+fn build_method_meta() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .output("bool")
+        .build()
+        .unwrap();
+    assert_eq!(method.name, "transfer");
+    assert_eq!(method.r#type, "function");
+    assert_eq!(method.inputs[0].name, "to");
+    assert_eq!(method.inputs[1].r#type, "u64");
+    assert_eq!(method.outputs[0].r#type, "bool");
+    assert!(method.encode_params(&["alice", "10"]).is_ok());
+
+    assert_eq!(
+        MethodMetaBuilder::default()
+            .name("init")
+            .build()
+            .unwrap_err(),
+        AbiError::InvalidMethodType {
+            name: "init".to_string(),
+            method_type: "".to_string(),
+        }
+    );
+    assert_eq!(
+        MethodMetaBuilder::default()
+            .name("init")
+            .method_type("event")
+            .build()
+            .unwrap_err()
+            .to_string(),
+        "invalid method type 'event' of method init, expect 'constructor' or 'function'"
+    );
+    assert_eq!(
+        MethodMetaBuilder::default()
+            .constructor()
+            .build()
+            .unwrap_err(),
+        AbiError::MissingMethodName
+    );
+}
+
+#[test]
+fn yaml_round_trip() {
+    let abi = IRContractABIMeta::from_yaml(
+        r#"
+# The abi of the token contract
+abi_version: 1
+methods:
+  - name: transfer # transfer the token
+    type: function
+    inputs:
+      - name: to
+        type: str
+      - name: amount
+        type: u64
+    outputs:
+      - type: bool
+"#,
+    )
+    .unwrap();
+    assert_eq!(abi.methods[0].name, "transfer");
+    assert_eq!(abi.methods[0].inputs[1].r#type, "u64");
+    let yaml_abi = IRContractABIMeta::from_yaml(&abi.to_yaml().unwrap()).unwrap();
+    assert_eq!(yaml_abi.to_json(), abi.to_json());
+    assert!(IRContractABIMeta::from_yaml("methods: 1").is_err());
+}
+
+#[test]
+fn validate_duplicate_input_names() {
+    let mut abi = IRContractABIMeta::default();
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("transfer")
+            .function()
+            .input("", "str")
+            .input("", "str")
+            .input("amount", "u64")
+            .build()
+            .unwrap(),
+    );
+    assert!(abi.validate().is_ok());
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("transfer2")
+            .function()
+            .input("amount", "u64")
+            .input("amount", "u64")
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(
+        abi.validate().unwrap_err(),
+        "duplicate input name amount in method transfer2"
+    );
+}
+
+#[test]
+fn method_signature() {
+    let method = MethodMetaBuilder::default()
+        .name("init")
+        .constructor()
+        .build()
+        .unwrap();
+    assert_eq!(method.signature(), "init()");
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .build()
+        .unwrap();
+    assert_eq!(method.signature(), "transfer(string,u64)");
+    let method = MethodMetaBuilder::default()
+        .name("batch")
+        .function()
+        .input("to", "[str]")
+        .input("amounts", "{ string : u64 }")
+        .input("data", "[u8]")
+        .build()
+        .unwrap();
+    assert_eq!(method.signature(), "batch([string],{string:u64},[u8])");
+}
+
+#[test]
+fn str_alias_selector() {
+    let str_method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("memo", "{str:str}")
+        .build()
+        .unwrap();
+    let string_method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "string")
+        .input("memo", "{string: string}")
+        .build()
+        .unwrap();
+    assert_eq!(str_method.signature(), string_method.signature());
+    assert_eq!(str_method.selector(), string_method.selector());
+    assert_eq!(
+        str_method.encode_params(&["alice", "k:v"]).unwrap(),
+        string_method.encode_params(&["alice", "k:v"]).unwrap()
+    );
+}
+
+#[test]
+fn selector_hashers() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .build()
+        .unwrap();
+    assert_eq!(method.signature(), "transfer(string,u64)");
+    let keccak_selector = method.selector_with(&Keccak256Hasher);
+    assert_eq!(method.selector(), keccak_selector);
+    assert_eq!(
+        keccak_selector,
+        keccak("transfer(string,u64)".as_bytes()).0[..4]
+    );
+    let sha256_selector = method.selector_with(&Sha256Hasher);
+    assert_eq!(hex::encode(sha256_selector), "b61e647d");
+    let blake2b_selector = method.selector_with(&Blake2b256Hasher);
+    assert_eq!(hex::encode(blake2b_selector), "ff425fba");
+    assert_ne!(sha256_selector, keccak_selector);
+    assert_ne!(blake2b_selector, keccak_selector);
+    // The full 256-bit hashes of the standard test input.
+    assert_eq!(
+        hex::encode(Sha256Hasher.hash(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        hex::encode(Blake2b256Hasher.hash(b"abc")),
+        "bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319"
+    );
+}
+
+#[test]
+fn json_schema_validation() {
+    let schema = jsonschema::JSONSchema::compile(&IRContractABIMeta::json_schema()).unwrap();
+    let mut abi = IRContractABIMeta::default();
+    abi.abi_version = 1;
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("transfer")
+            .function()
+            .input("to", "string")
+            .input("amount", "u64")
+            .output("bool")
+            .build()
+            .unwrap(),
+    );
+    abi.constants
+        .push(IRConstantMeta::from_literal(&Literal::Int(
+            IntLiteral::U32(1),
+        )));
+    let valid = serde_json::to_value(&abi).unwrap();
+    assert!(schema.is_valid(&valid));
+    // Older abi files without constants and errors are still valid.
+    assert!(schema.is_valid(&serde_json::json!({"abi_version": 1, "methods": []})));
+
+    let mut wrong_method_type = valid.clone();
+    wrong_method_type["methods"][0]["type"] = "method".into();
+    assert!(!schema.is_valid(&wrong_method_type));
+    let mut missing_input_type = valid.clone();
+    missing_input_type["methods"][0]["inputs"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("type");
+    assert!(!schema.is_valid(&missing_input_type));
+    assert!(!schema.is_valid(&serde_json::json!({"abi_version": 1})));
+    assert!(!schema.is_valid(&serde_json::json!({"abi_version": "1", "methods": []})));
+}
+
+#[test]
+fn from_json_strict_unknown_fields() {
+    let mut abi = IRContractABIMeta::default();
+    abi.abi_version = 1;
+    abi.name = Some("Token".to_string());
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("transfer")
+            .function()
+            .input("to", "string")
+            .output("bool")
+            .build()
+            .unwrap(),
+    );
+    abi.constants
+        .push(IRConstantMeta::from_literal(&Literal::Int(
+            IntLiteral::U32(1),
+        )));
+    let strict = IRContractABIMeta::from_json_strict(&abi.to_json()).unwrap();
+    assert_eq!(strict.methods, abi.methods);
+
+    let misspelled = br#"{"abi_version": 1, "methods": [], "methdos": []}"#;
+    assert!(IRContractABIMeta::from_json(misspelled).methods.is_empty());
+    let err = IRContractABIMeta::from_json_strict(misspelled).unwrap_err();
+    assert_eq!(err.to_string(), "unknown field $.methdos");
+    let mut nested = serde_json::to_value(&abi).unwrap();
+    nested["methods"][0]["inputs"][0]["indexd"] = true.into();
+    let err = IRContractABIMeta::from_json_strict(nested.to_string().as_bytes()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown field $.methods[0].inputs[0].indexd"
+    );
+    // The type errors are still reported by the deserialization.
+    let err =
+        IRContractABIMeta::from_json_strict(br#"{"abi_version": 1, "methods": {}}"#).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("could not deserialize from json"));
+}
+
+#[test]
+fn encode_spaced_array_and_map_params() {
+    let method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("values", "[u8]")
+        .input("prices", "{string:u32}")
+        .input("names", "[string]")
+        .input("labels", "{string:string}")
+        .build()
+        .unwrap();
+    let bytes = method
+        .encode_params(&["1, 2 ,3", " a : 1 , b:2 ", "x, y", "k: v"])
+        .unwrap();
+    let params = method.decode_params(&bytes).unwrap();
+    assert_eq!(params[0], ABIParam::U8Array(vec![1, 2, 3]));
+    assert_eq!(
+        params[1],
+        ABIParam::StrU32Map(
+            [("a".to_string(), 1), ("b".to_string(), 2)]
+                .into_iter()
+                .collect()
+        )
+    );
+    // Whitespace inside string values is preserved.
+    assert_eq!(
+        params[2],
+        ABIParam::StrArray(vec!["x".to_string(), " y".to_string()])
+    );
+    assert_eq!(
+        params[3],
+        ABIParam::StrStrMap([("k".to_string(), " v".to_string())].into_iter().collect())
+    );
+}
+
+#[test]
+fn encode_radix_int_params() {
+    let method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("a", "u8")
+        .input("b", "[u8]")
+        .input("c", "{string:u8}")
+        .input("d", "i16")
+        .build()
+        .unwrap();
+    let bytes = method
+        .encode_params(&["0xFF", "255,0b11111111,0xff", "k:0b11111111", "-0x10"])
+        .unwrap();
+    assert_eq!(
+        method.decode_params(&bytes).unwrap(),
+        vec![
+            ABIParam::U8(255),
+            ABIParam::U8Array(vec![255, 255, 255]),
+            ABIParam::StrU8Map([("k".to_string(), 255)].into_iter().collect()),
+            ABIParam::I16(-16),
+        ]
+    );
+    let err = method
+        .encode_params(&["0x100", "0", "k:0", "0"])
+        .unwrap_err()
+        .to_string();
+    assert_eq!(err, "u8 value 0x100 is out of range [0, 255]");
+    let err = method
+        .encode_params(&["0", "1,256", "k:0", "0"])
+        .unwrap_err()
+        .to_string();
+    assert_eq!(err, "[u8] element #1 out of range: 256, expect [0, 255]");
+    assert!(method.encode_params(&["0xZZ", "0", "k:0", "0"]).is_err());
+    assert!(method.encode_params(&["0x-1", "0", "k:0", "0"]).is_err());
+}
+
+#[test]
+fn encode_out_of_range_int_params() {
+    let method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("a", "u8")
+        .input("b", "i8")
+        .build()
+        .unwrap();
+    assert_eq!(
+        method.encode_params(&["-1", "0"]).unwrap_err().to_string(),
+        "u8 cannot be negative: -1"
+    );
+    assert_eq!(
+        method.encode_params(&["0", "200"]).unwrap_err().to_string(),
+        "i8 value 200 is out of range [-128, 127]"
+    );
+    assert_eq!(
+        method
+            .encode_params(&["0", "-129"])
+            .unwrap_err()
+            .to_string(),
+        "i8 value -129 is out of range [-128, 127]"
+    );
+    let method = MethodMetaBuilder::default()
+        .name("g")
+        .function()
+        .input("values", "[u32]")
+        .build()
+        .unwrap();
+    assert_eq!(
+        method.encode_params(&["1,-2"]).unwrap_err().to_string(),
+        "[u32] element #1: u32 cannot be negative: -2"
+    );
+}
+
+#[test]
+fn abi_referenced_types() {
+    let mut abi = IRContractABIMeta::default();
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("f")
+            .function()
+            .input("data", "[u8]")
+            .input("balances", "{str: u64}")
+            .output("bool")
+            .build()
+            .unwrap(),
+    );
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("g")
+            .function()
+            .input("flag", "bool")
+            .build()
+            .unwrap(),
+    );
+    let type_names: Vec<String> = abi.referenced_types().into_iter().collect();
+    assert_eq!(
+        type_names,
+        vec!["[u8]", "bool", "string", "u64", "u8", "{string:u64}"]
+    );
+}
+
+#[test]
+fn from_contract_checked_unsupported_types() {
+    let src = r#"module_name = "Token"
+contract Token {
+    state {
+    }
+    pub fn Token.Token.init()  {
+        0:
+            ret()
+    }
+
+    pub fn Token.Token.balance(%0: str, ) -> u64 {
+        0:
+            ret(0: u64, )
+    }
+
+    pub fn Token.Token.peek(%0: u8*, ) -> {u8: u64} {
+        0:
+            ret()
+    }
+
+}
+"#;
+    let err = IRContractABIMeta::from_ir_source(src).unwrap_err();
+    assert_eq!(err, "method peek has unencodable types u8*, {u8:u64}");
+    let src = src.replace("%0: u8*, ) -> {u8: u64}", "%0: u8, ) -> [u64]");
+    let abi = IRContractABIMeta::from_ir_source(&src).unwrap();
+    assert_eq!(abi.methods.len(), 3);
+}
+
+#[test]
+fn method_is_encodable() {
+    let method = MethodMetaBuilder::default()
+        .name("peek")
+        .function()
+        .input("key", "str")
+        .input("ptr", "u8*")
+        .output("[u64]")
+        .build()
+        .unwrap();
+    assert_eq!(method.is_encodable(), Err(vec!["u8*".to_string()]));
+    let method = MethodMetaBuilder::default()
+        .name("peek")
+        .function()
+        .input("key", "str")
+        .output("{u8:u64}")
+        .build()
+        .unwrap();
+    assert_eq!(method.is_encodable(), Err(vec!["{u8:u64}".to_string()]));
+    let method = MethodMetaBuilder::default()
+        .name("peek")
+        .function()
+        .input("key", "str")
+        .output("[u64]")
+        .build()
+        .unwrap();
+    assert_eq!(method.is_encodable(), Ok(()));
+}
+
+#[test]
+fn from_contract_empty() {
+    let src = r#"module_name = "Token"
+contract Token {
+    state {
+    }
+    pub fn Token.Token.init()  {
+        0:
+            ret()
+    }
+
+}
+"#;
+    let abi = IRContractABIMeta::from_ir_source(src).unwrap();
+    assert_eq!(abi.methods.len(), 1);
+    assert!(abi.constructor().is_some());
+    assert!(abi.is_empty());
+
+    let src = src.replace("init", "balance");
+    assert!(!IRContractABIMeta::from_ir_source(&src).unwrap().is_empty());
+
+    let src = r#"module_name = "Token"
+contract Token {
+    state {
+    }
+}
+"#;
+    let abi = IRContractABIMeta::from_ir_source(src).unwrap();
+    assert!(abi.methods.is_empty());
+    assert_eq!(abi.abi_version, CURRENT_IR_ABI_VERSION);
+    assert!(abi.is_empty());
+}
+
+#[test]
+fn from_contract_with_imports() {
+    let base_src = r#"module_name = "Base"
+contract Base {
+    state {
+    }
+    pub fn Base.Base.init()  {
+        0:
+            ret()
+    }
+
+    pub fn Base.Base.owner() -> str {
+        0:
+            ret("": str, )
+    }
+
+}
+"#;
+    let derived_src = r#"module_name = "Token"
+contract Token {
+    state {
+    }
+    pub fn Token.Token.init()  {
+        0:
+            ret()
+    }
+
+    pub fn Token.Token.balance(%0: str, ) -> u64 {
+        0:
+            ret(0: u64, )
+    }
+
+}
+"#;
+    with_contract(base_src, |base| {
+        with_contract(derived_src, |derived| {
+            let abi = IRContractABIMeta::from_contract_with_imports(derived, &[base]).unwrap();
+            let methods: Vec<(String, Option<String>)> = abi
+                .methods
+                .iter()
+                .map(|method| (method.signature(), method.inherited_from.clone()))
+                .collect();
+            // The constructor of the base is not inherited.
+            assert_eq!(
+                methods,
+                [
+                    ("init()".to_string(), None),
+                    ("balance(string)".to_string(), None),
+                    ("owner()".to_string(), Some("Base".to_string())),
+                ]
+            );
+            assert_eq!(abi.name.as_deref(), Some("Token"));
+            assert_eq!(
+                IRContractABIMeta::from_contract_with_imports(derived, &[base, base]).unwrap_err(),
+                "method owner() of Base collides with Base"
+            );
+            assert_eq!(
+                IRContractABIMeta::from_contract_with_imports(derived, &[derived]).unwrap_err(),
+                "method balance(string) of Token collides with Token"
+            );
+        });
+    });
+}
+
+#[test]
+fn validate_constructor_count() {
+    let constructor = |input_type: &str| {
+        MethodMetaBuilder::default()
+            .name("init")
+            .constructor()
+            .input("v", input_type)
+            .build()
+            .unwrap()
+    };
+    let mut abi = IRContractABIMeta::default();
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("f")
+            .function()
+            .build()
+            .unwrap(),
+    );
+    // No constructor is allowed.
+    assert!(abi.validate().is_ok());
+    abi.methods.push(constructor("u8"));
+    assert!(abi.validate().is_ok());
+    abi.methods.push(constructor("string"));
+    assert_eq!(
+        abi.validate().unwrap_err(),
+        "expect at most one constructor, but got 2: init(u8), init(string)"
+    );
+}
+
+#[test]
+fn contract_name_and_version() {
+    let src = r#"module_name = "Token"
+contract Token {
+    state {
+    }
+    pub fn Token.transfer(%0: u8, ) {
+        0:
+            ret()
+    }
+
+}
+"#;
+    let abi = IRContractABIMeta::from_ir_source(src).unwrap();
+    assert_eq!(abi.name.as_deref(), Some("Token"));
+    assert_eq!(abi.version, None);
+    assert!(abi
+        .explain(false)
+        .starts_with("contract: Token\nabi version: 2\n"));
+    let abi = IRContractABIMeta::from_json(&abi.to_json());
+    assert_eq!(abi.name.as_deref(), Some("Token"));
+
+    // The name and version tell apart the abi metas with the same methods.
+    let mut renamed_abi = abi.clone();
+    renamed_abi.name = Some("Coin".to_string());
+    assert_ne!(abi.fingerprint(), renamed_abi.fingerprint());
+    let mut versioned_abi = abi.clone();
+    versioned_abi.version = Some("1.0.0".to_string());
+    assert_ne!(abi.fingerprint(), versioned_abi.fingerprint());
+    assert!(versioned_abi
+        .explain(false)
+        .starts_with("contract: Token\nversion: 1.0.0\n"));
+    let mut anonymous_abi = abi.clone();
+    anonymous_abi.name = None;
+    assert_ne!(abi.fingerprint(), anonymous_abi.fingerprint());
+
+    // Older abi files without the name and version are still decoded.
+    let abi = IRContractABIMeta::from_json(br#"{"abi_version": 1, "methods": []}"#);
+    assert_eq!((abi.name, abi.version), (None, None));
+}
+
+#[test]
+fn get_method_by_qualified_name() {
+    let src = r#"module_name = "Token"
+contract Token {
+    state {
+    }
+    pub fn Token.a.transfer(%0: u8, ) {
+        0:
+            ret()
+    }
+
+    pub fn Token.b.transfer(%0: str, ) {
+        0:
+            ret()
+    }
+
+}
+"#;
+    let abi = IRContractABIMeta::from_ir_source(src).unwrap();
+    // The short name still matches the first method.
+    let method = abi.get_method("transfer").unwrap();
+    assert_eq!(method.qualified_name, "Token.a.transfer");
+    let method = abi
+        .get_method_by_qualified_name("Token.b.transfer")
+        .unwrap();
+    assert_eq!(method.name, "transfer");
+    assert_eq!(method.inputs[0].r#type, "string");
+    assert!(abi.get_method_by_qualified_name("transfer").is_none());
+    // The qualified name is kept by the serialization.
+    let abi = IRContractABIMeta::from_json(&abi.to_json());
+    assert!(abi
+        .get_method_by_qualified_name("Token.a.transfer")
+        .is_some());
+    // Older abi files without the qualified name are still decoded.
+    let abi = IRContractABIMeta::from_json(
+        br#"{"abi_version": 1, "methods": [{"name": "f", "type": "function", "inputs": [], "outputs": []}]}"#,
+    );
+    assert_eq!(abi.get_method("f").unwrap().qualified_name, "");
+}
+
+#[test]
+fn from_contract_with_older_version() {
+    let src = r#"module_name = "Token"
+contract Token {
+    state {
+    }
+    pub fn Token.Token.balance(%0: str, ) -> u64 {
+        0:
+            ret(0: u64, )
+    }
+
+}
+"#;
+    with_contract(src, |contract| {
+        let abi = IRContractABIMeta::from_contract_with_version(contract, 0).unwrap();
+        assert_eq!(abi.abi_version, 0);
+        let method = abi.get_method("balance").unwrap();
+        assert_eq!(method.qualified_name, "");
+        assert_eq!(method.signature(), "balance(string)");
+        // The version 1 json has only the version 1 fields.
+        let abi = IRContractABIMeta::from_contract_with_version(contract, 1).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&abi.to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "abi_version": 1,
+                "methods": [{
+                    "name": "balance",
+                    "type": "function",
+                    "inputs": [{"name": "", "type": "string"}],
+                    "outputs": [{"type": "u64"}],
+                }],
+            })
+        );
+        let abi = IRContractABIMeta::from_contract_with_version(contract, 2).unwrap();
+        assert_eq!(abi.name.as_deref(), Some("Token"));
+        assert_eq!(
+            abi.get_method("balance").unwrap().qualified_name,
+            "Token.Token.balance"
+        );
+        assert_eq!(
+            IRContractABIMeta::from_contract_with_version(contract, 3).unwrap_err(),
+            "abi version 3 is newer than the current abi version 2"
+        );
+    });
+}
+
+#[test]
+fn migrate_v0_abi_to_current() {
+    let abi = IRContractABIMeta::from_json(
+        br#"{
+            "abi_version": 0,
+            "methods": [
+                {"name": "init", "type": "constructor", "inputs": [], "outputs": []},
+                {"name": "f", "type": "function", "inputs": [{"name": "", "type": "u8"}], "outputs": []}
+            ]
+        }"#,
+    );
+    let abi = abi.migrate_to_current();
+    assert_eq!(abi.abi_version, CURRENT_IR_ABI_VERSION);
+    assert_eq!(abi.methods.len(), 2);
+    assert!(abi.constants.is_empty());
+    assert!(abi.errors.is_empty());
+    assert_eq!(abi.get_method("f").unwrap().qualified_name, "");
+    // Migrating the current meta again changes nothing.
+    let json = abi.to_json();
+    assert_eq!(abi.migrate_to_current().to_json(), json);
+}
+
+#[test]
+fn encode_params_error_kinds() {
+    let method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("a", "u8")
+        .input("b", "{string:bool}")
+        .build()
+        .unwrap();
+    assert_eq!(
+        method.encode_params(&["1"]).unwrap_err(),
+        AbiError::ArityMismatch {
+            expected: 2,
+            got: 1
+        }
+    );
+    assert_eq!(
+        method.encode_params(&["1"]).unwrap_err().to_string(),
+        "too few params, expect 2 params, but got 1"
+    );
+    assert_eq!(
+        method.encode_params(&["1", "k:true", "2"]).unwrap_err(),
+        AbiError::ArityMismatch {
+            expected: 2,
+            got: 3
+        }
+    );
+    assert_eq!(
+        method
+            .encode_params(&["1", "k:true", "2"])
+            .unwrap_err()
+            .to_string(),
+        "too many params, expect 2 params, but got 3"
+    );
+    assert_eq!(
+        method.encode_params(&["x", "k:true"]).unwrap_err(),
+        AbiError::ParseInt {
+            ty: "u8".to_string(),
+            value: "x".to_string()
+        }
+    );
+    assert_eq!(
+        method.encode_params(&["1", "k"]).unwrap_err(),
+        AbiError::MalformedMapEntry("k".to_string())
+    );
+    assert_eq!(
+        method.encode_params(&["1", "k:yes"]).unwrap_err(),
+        AbiError::ParseBool {
+            value: "yes".to_string()
+        }
+    );
+    let method = MethodMetaBuilder::default()
+        .name("g")
+        .function()
+        .input("a", "u8*")
+        .build()
+        .unwrap();
+    let err = method.encode_params(&["1"]).unwrap_err();
+    assert_eq!(err, AbiError::UnsupportedType("u8*".to_string()));
+    assert_eq!(err.to_string(), "not supported abi param type u8*");
+    assert!(matches!(
+        method.decode_params(&[0x00, 0x01]).unwrap_err(),
+        AbiError::UnsupportedType(_)
+    ));
+}
+
+#[test]
+fn encode_bool_params() {
+    let method = MethodMetaBuilder::default()
+        .name("set")
+        .function()
+        .input("flag", "bool")
+        .input("flags", "[bool]")
+        .build()
+        .unwrap();
+    for (params, expected) in [
+        (["true", "true,false"], (true, vec![true, false])),
+        (["false", "1,0"], (false, vec![true, false])),
+        (["1", "TRUE,False"], (true, vec![true, false])),
+        (["0", "[]"], (false, vec![])),
+        (["True", "FALSE"], (true, vec![false])),
+    ] {
+        let encoded = method.encode_params(&params).unwrap();
+        assert_eq!(
+            method.decode_params(&encoded).unwrap(),
+            vec![ABIParam::Bool(expected.0), ABIParam::BoolArray(expected.1)]
+        );
+    }
+    for (params, invalid) in [
+        (["yes", "true"], "yes"),
+        (["ture", "true"], "ture"),
+        (["", "true"], ""),
+        (["2", "true"], "2"),
+    ] {
+        assert_eq!(
+            method.encode_params(&params).unwrap_err(),
+            AbiError::ParseBool {
+                value: invalid.to_string()
+            }
+        );
+    }
+    for (params, index, invalid) in [(["true", "true,no"], 1, "no"), (["true", "01"], 0, "01")] {
+        assert_eq!(
+            method.encode_params(&params).unwrap_err(),
+            AbiError::ArrayElement {
+                ty: "[bool]".to_string(),
+                index,
+                error: Box::new(AbiError::ParseBool {
+                    value: invalid.to_string()
+                })
+            }
+        );
+    }
+}
+
+#[test]
+fn encode_array_element_errors() {
+    let method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("a", "[i8]")
+        .build()
+        .unwrap();
+    let err = method.encode_params(&["1, -2, 200, 3"]).unwrap_err();
+    assert_eq!(
+        err,
+        AbiError::ArrayElement {
+            ty: "[i8]".to_string(),
+            index: 2,
+            error: Box::new(AbiError::IntOutOfRange {
+                ty: "i8".to_string(),
+                value: "200".to_string(),
+                min: "-128".to_string(),
+                max: "127".to_string(),
+            }),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "[i8] element #2 out of range: 200, expect [-128, 127]"
+    );
+    assert_eq!(
+        method.encode_params(&["-129"]).unwrap_err().to_string(),
+        "[i8] element #0 out of range: -129, expect [-128, 127]"
+    );
+    assert_eq!(
+        method.encode_params(&["1,x"]).unwrap_err().to_string(),
+        "[i8] element #1: invalid i8 value x"
+    );
+    let method = MethodMetaBuilder::default()
+        .name("g")
+        .function()
+        .input("a", "[u64]")
+        .input("b", "[f64]")
+        .build()
+        .unwrap();
+    assert_eq!(
+        method
+            .encode_params(&["1,-1", "1"])
+            .unwrap_err()
+            .to_string(),
+        "[u64] element #1: u64 cannot be negative: -1"
+    );
+    assert_eq!(
+        method
+            .encode_params(&["1", "1,inf"])
+            .unwrap_err()
+            .to_string(),
+        "[f64] element #1: f64 value must be finite, got inf"
+    );
+}
+
+#[test]
+fn call_data_round_trip() {
+    let mut abi = IRContractABIMeta::default();
+    for (name, input_type) in [
+        ("transfer", "u64"),
+        ("transfer", "string"),
+        ("approve", "bool"),
+    ] {
+        abi.methods.push(
+            MethodMetaBuilder::default()
+                .name(name)
+                .function()
+                .input("v", input_type)
+                .build()
+                .unwrap(),
+        );
+    }
+    let method = abi
+        .get_method_by_signature("transfer", &["string"])
+        .unwrap();
+    let call_data = method.encode_call(&["alice"]).unwrap();
+    assert_eq!(&call_data[..4], &method.selector());
+    let (decoded_method, params) = abi.decode_call(&call_data).unwrap();
+    assert_eq!(decoded_method.signature(), "transfer(string)");
+    assert_eq!(params, vec![ABIParam::Str("alice".to_string())]);
+
+    let call_data = abi.methods[2].encode_call(&["true"]).unwrap();
+    let (decoded_method, params) = abi.decode_call(&call_data).unwrap();
+    assert_eq!(decoded_method.name, "approve");
+    assert_eq!(params, vec![ABIParam::Bool(true)]);
+
+    assert!(matches!(
+        abi.decode_call(&[0, 0, 0, 0, 0]).unwrap_err(),
+        AbiError::UnknownSelector([0, 0, 0, 0])
+    ));
+    assert!(abi.decode_call(&call_data[..3]).is_err());
+}
+
+#[test]
+fn variant_params_round_trip() {
+    let method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("a", "enum<u8,str,bool>")
+        .input("b", "enum<{str:u8},[u64]>")
+        .build()
+        .unwrap();
+    for (params, expected) in [
+        (
+            ["0:7", "0:a:1"],
+            [
+                ABIParam::Variant {
+                    tag: 0,
+                    value: Box::new(ABIParam::U8(7)),
+                },
+                ABIParam::Variant {
+                    tag: 0,
+                    value: Box::new(ABIParam::StrU8Map([("a".to_string(), 1)].into())),
+                },
+            ],
+        ),
+        (
+            ["1:hello: world", "1:1,2"],
+            [
+                ABIParam::Variant {
+                    tag: 1,
+                    value: Box::new(ABIParam::Str("hello: world".to_string())),
+                },
+                ABIParam::Variant {
+                    tag: 1,
+                    value: Box::new(ABIParam::U64Array(vec![1, 2])),
+                },
+            ],
+        ),
+        (
+            ["2:true", "1:3"],
+            [
+                ABIParam::Variant {
+                    tag: 2,
+                    value: Box::new(ABIParam::Bool(true)),
+                },
+                ABIParam::Variant {
+                    tag: 1,
+                    value: Box::new(ABIParam::U64Array(vec![3])),
+                },
+            ],
+        ),
+    ] {
+        let encoded = method.encode_params(&params).unwrap();
+        assert_eq!(&encoded[1..5], &expected[0].as_bytes()[..4]);
+        assert_eq!(method.decode_params(&encoded).unwrap(), expected);
+    }
+    assert_eq!(
+        method.encode_params(&["3:true", "0:a:1"]).unwrap_err(),
+        AbiError::InvalidVariantTag { tag: 3, count: 3 }
+    );
+    assert_eq!(
+        method.encode_params(&["true", "0:a:1"]).unwrap_err(),
+        AbiError::MalformedVariant("true".to_string())
+    );
+    assert!(matches!(
+        method
+            .decode_params(&[0x00, 0x05, 0x00, 0x00, 0x00, 0x01])
+            .unwrap_err(),
+        AbiError::Decode { .. }
+    ));
+    let mut abi = IRContractABIMeta::default();
+    abi.methods.push(method);
+    assert!(abi.referenced_types().contains("{string:u8}"));
+    assert!(abi.referenced_types().contains("bool"));
+}
+
+#[test]
+fn abi_fingerprint() {
+    let transfer = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .output("bool")
+        .build()
+        .unwrap();
+    let balance = MethodMetaBuilder::default()
+        .name("balance")
+        .function()
+        .input("owner", "string")
+        .output("u64")
+        .build()
+        .unwrap();
+    let mut abi = IRContractABIMeta::default();
+    abi.methods = vec![transfer.clone(), balance.clone()];
+    let mut reordered_abi = IRContractABIMeta::default();
+    reordered_abi.methods = vec![balance.clone(), transfer.clone()];
+    assert_eq!(abi.fingerprint(), reordered_abi.fingerprint());
+
+    let mut changed_output = balance.clone();
+    changed_output.outputs[0].r#type = "u128".to_string();
+    let mut changed_abi = IRContractABIMeta::default();
+    changed_abi.methods = vec![transfer.clone(), changed_output];
+    assert_ne!(abi.fingerprint(), changed_abi.fingerprint());
+
+    let mut changed_input = transfer;
+    changed_input.inputs[1].r#type = "u32".to_string();
+    changed_abi.methods = vec![changed_input, balance];
+    assert_ne!(abi.fingerprint(), changed_abi.fingerprint());
+}
+
+#[test]
+fn abi_to_json_canonical() {
+    let transfer = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .output("bool")
+        .build()
+        .unwrap();
+    let balance = MethodMetaBuilder::default()
+        .name("balance")
+        .function()
+        .input("owner", "str")
+        .output("u64")
+        .build()
+        .unwrap();
+    let mut abi = IRContractABIMeta::default();
+    abi.methods = vec![transfer.clone(), balance.clone()];
+    let mut reordered_abi = IRContractABIMeta::default();
+    reordered_abi.methods = vec![balance, transfer];
+    let json = abi.to_json_canonical();
+    assert_eq!(json, abi.to_json_canonical());
+    assert_eq!(json, reordered_abi.to_json_canonical());
+    assert_ne!(abi.to_json(), reordered_abi.to_json());
+
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.ends_with("}\n"));
+    assert!(json.find("\"abi_version\"").unwrap() < json.find("\"methods\"").unwrap());
+    assert!(json.find("\"balance\"").unwrap() < json.find("\"transfer\"").unwrap());
+    // The inputs keep the positional order.
+    assert!(json.find("\"to\"").unwrap() < json.find("\"amount\"").unwrap());
+    let decoded = IRContractABIMeta::from_json(json.as_bytes());
+    assert_eq!(decoded.methods[0].name, "balance");
+    assert_eq!(decoded.to_json_canonical(), json.into_bytes());
+}
+
+#[test]
+fn abi_canonical_bytes() {
+    let mut abi = IRContractABIMeta::default();
+    abi.name = Some("Token".to_string());
+    for method in [
+        MethodMetaBuilder::default()
+            .name("transfer")
+            .function()
+            .input("to", "str")
+            .input("amount", "u64")
+            .output("bool")
+            .build(),
+        MethodMetaBuilder::default()
+            .name("balance")
+            .function()
+            .input("owner", "string")
+            .output("u64")
+            .build(),
+    ] {
+        abi.methods.push(method.unwrap());
+    }
+    let canonical_bytes = abi.canonical_bytes();
+    assert_eq!(canonical_bytes, abi.canonical_bytes());
+    assert_eq!(
+        String::from_utf8(canonical_bytes.clone()).unwrap(),
+        "name:Token\nbalance(string)->(u64)\ntransfer(string,u64)->(bool)"
+    );
+    assert_eq!(abi.fingerprint(), keccak(&canonical_bytes).0);
+}
+
+#[test]
+fn encode_empty_array_and_map_params() {
+    let method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("a", "[u8]")
+        .input("b", "{str:u64}")
+        .input("c", "[str]")
+        .build()
+        .unwrap();
+    let expected = vec![
+        ABIParam::U8Array(vec![]),
+        ABIParam::StrU64Map(Default::default()),
+        ABIParam::StrArray(vec![]),
+    ];
+    for params in [["", "", ""], ["[]", "{}", "[]"], [" [] ", " ", "[]"]] {
+        let encoded = method.encode_params(&params).unwrap();
+        assert_eq!(encoded, vec![0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(method.decode_params(&encoded).unwrap(), expected);
+    }
+    // The non-empty collections are not affected.
+    let encoded = method.encode_params(&["1", "a:2", " x"]).unwrap();
+    assert_eq!(
+        method.decode_params(&encoded).unwrap(),
+        vec![
+            ABIParam::U8Array(vec![1]),
+            ABIParam::StrU64Map([("a".to_string(), 2)].into()),
+            ABIParam::StrArray(vec![" x".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn encode_params_iter_with_owned_strings() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .build()
+        .unwrap();
+    let expected = method.encode_params(&["alice", "10"]).unwrap();
+    let owned: Vec<String> = vec!["alice".to_string(), "10".to_string()];
+    assert_eq!(method.encode_params_iter(&owned).unwrap(), expected);
+    assert_eq!(method.encode_params_iter(owned).unwrap(), expected);
+    assert_eq!(
+        method
+            .encode_params_iter(["alice", "10"].iter().map(|s| s.to_string()))
+            .unwrap(),
+        expected
+    );
+    assert_eq!(
+        method
+            .encode_params_iter(vec!["alice".to_string()])
+            .unwrap_err(),
+        AbiError::ArityMismatch {
+            expected: 2,
+            got: 1
+        }
+    );
+}
+
+#[test]
+fn encode_params_with_endianness() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("amount", "u32")
+        .input("flags", "[u16]")
+        .build()
+        .unwrap();
+    let params = ["16909060", "1,515"];
+    let little_endian = method.encode_params(&params).unwrap();
+    assert_eq!(
+        little_endian,
+        vec![0x00, 0x04, 0x03, 0x02, 0x01, 0x02, 0x01, 0x00, 0x03, 0x02]
+    );
+    assert_eq!(
+        method
+            .encode_params_with_endianness(params, Endianness::Little)
+            .unwrap(),
+        little_endian
+    );
+    let big_endian = method
+        .encode_params_with_endianness(params, Endianness::Big)
+        .unwrap();
+    // The array lengths are still ULEB128 encoded.
+    assert_eq!(
+        big_endian,
+        vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x02, 0x00, 0x01, 0x02, 0x03]
+    );
+    let expected = vec![ABIParam::U32(0x01020304), ABIParam::U16Array(vec![1, 515])];
+    assert_eq!(method.decode_params(&little_endian).unwrap(), expected);
+    assert_eq!(
+        method
+            .decode_params_with_endianness(&big_endian, Endianness::Big)
+            .unwrap(),
+        expected
+    );
+    assert_ne!(method.decode_params(&big_endian).unwrap(), expected);
+}
+
+#[test]
+fn encode_params_with_varint() {
+    let method = MethodMetaBuilder::default()
+        .name("set_limits")
+        .function()
+        .input("min", "u64")
+        .input("max", "u64")
+        .input("step", "u64")
+        .input("offset", "i64")
+        .build()
+        .unwrap();
+    let params = ["1", "300", "127", "-2"];
+    let fixed = method.encode_params(&params).unwrap();
+    let varint = method
+        .encode_params_with_options(params, EncodingOptions::varint())
+        .unwrap();
+    // The version byte and 4 u64s take 33 bytes, but only 6 as varints.
+    assert_eq!(fixed.len(), 33);
+    assert_eq!(varint, vec![0x00, 0x01, 0xac, 0x02, 0x7f, 0x7e]);
+    let expected = vec![
+        ABIParam::U64(1),
+        ABIParam::U64(300),
+        ABIParam::U64(127),
+        ABIParam::I64(-2),
+    ];
+    assert_eq!(method.decode_params(&fixed).unwrap(), expected);
+    assert_eq!(
+        method
+            .decode_params_with_options(&varint, EncodingOptions::varint())
+            .unwrap(),
+        expected
+    );
+    let boundaries = ["0", &u64::MAX.to_string(), "128", &i64::MIN.to_string()];
+    let varint = method
+        .encode_params_with_options(boundaries, EncodingOptions::varint())
+        .unwrap();
+    assert_eq!(varint.len(), 1 + 1 + 10 + 2 + 10);
+    assert_eq!(
+        method
+            .decode_params_with_options(&varint, EncodingOptions::varint())
+            .unwrap(),
+        vec![
+            ABIParam::U64(0),
+            ABIParam::U64(u64::MAX),
+            ABIParam::U64(128),
+            ABIParam::I64(i64::MIN),
+        ]
+    );
+}
+
+#[test]
+fn encode_params_json() {
+    let method = MethodMetaBuilder::default()
+        .name("set")
+        .function()
+        .input("owner", "str")
+        .input("amounts", "[u128]")
+        .input("flags", "{str:bool}")
+        .input("value", "enum<u8, [str]>")
+        .build()
+        .unwrap();
+    let params = serde_json::json!({
+        "flags": {"a": true, "b": "false"},
+        "amounts": [1, "340282366920938463463374607431768211455"],
+        "value": {"tag": 1, "value": ["x,y", ""]},
+        "owner": "alice",
+    });
+    let encoded = method.encode_params_json(&params).unwrap();
+    assert_eq!(
+        method.decode_params(&encoded).unwrap(),
+        [
+            ABIParam::Str("alice".to_string()),
+            ABIParam::U128Array(vec![1, u128::MAX]),
+            ABIParam::StrBoolMap([("a".to_string(), true), ("b".to_string(), false)].into()),
+            ABIParam::Variant {
+                tag: 1,
+                value: Box::new(ABIParam::StrArray(vec!["x,y".to_string(), "".to_string()])),
+            },
+        ]
+    );
+    let with = |key: &str, value: serde_json::Value| {
+        let mut params = params.clone();
+        params[key] = value;
+        params
+    };
+    for (params, error) in [
+        (
+            serde_json::json!([]),
+            "expect params object json value, but got []",
+        ),
+        (
+            {
+                let mut params = params.clone();
+                params.as_object_mut().unwrap().remove("flags");
+                params
+            },
+            "missing param flags",
+        ),
+        (with("to", serde_json::json!("bob")), "unknown param to"),
+        (
+            with("owner", serde_json::json!(1)),
+            "param 0 owner: expect string json value, but got 1",
+        ),
+        (
+            with("amounts", serde_json::json!([1, -1])),
+            "param 1 amounts: [u128] element #1: u128 cannot be negative: -1",
+        ),
+        (
+            with("amounts", serde_json::json!({"a": 1})),
+            r#"param 1 amounts: expect [u128] json value, but got {"a":1}"#,
+        ),
+        (
+            with("flags", serde_json::json!({"a": 1})),
+            "param 2 flags: expect bool json value, but got 1",
+        ),
+        (
+            with("value", serde_json::json!({"tag": 2, "value": 1})),
+            "param 3 value: variant tag 2 is out of range, expect less than 2",
+        ),
+        (
+            with("value", serde_json::json!(1)),
+            "param 3 value: invalid variant 1, expected tag:value",
+        ),
+    ] {
+        assert_eq!(
+            method.encode_params_json(&params).unwrap_err().to_string(),
+            error
+        );
+    }
+    let unnamed = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("", "u8")
+        .build()
+        .unwrap();
+    assert_eq!(
+        unnamed.encode_params_json(&serde_json::json!({})),
+        Err(AbiError::UnnamedParam(0))
+    );
+}
+
+#[test]
+fn encode_params_json_strict_numbers() {
+    let method = MethodMetaBuilder::default()
+        .name("mint")
+        .function()
+        .input("supply", "u128")
+        .input("nonce", "u64")
+        .input("ratio", "f64")
+        .build()
+        .unwrap();
+    let params = serde_json::json!({
+        "supply": "340282366920938463463374607431768211454",
+        "nonce": 9007199254740992u64,
+        "ratio": 1e30,
+    });
+    let encoded = method
+        .encode_params_json_with_numbers(&params, true)
+        .unwrap();
+    assert_eq!(
+        method.decode_params(&encoded).unwrap(),
+        [
+            ABIParam::U128(u128::MAX - 1),
+            ABIParam::U64(1 << 53),
+            ABIParam::F64(1e30)
+        ]
+    );
+    assert_eq!(method.encode_params_json(&params).unwrap(), encoded);
+    let with = |key: &str, value: serde_json::Value| {
+        let mut params = params.clone();
+        params[key] = value;
+        params
+    };
+    // The bare numbers out of the exact f64 range are rejected in the strict mode only.
+    let unsafe_nonce = with("nonce", serde_json::json!(9007199254740993u64));
+    assert!(method.encode_params_json(&unsafe_nonce).is_ok());
+    assert_eq!(
+        method
+            .encode_params_json_with_numbers(&unsafe_nonce, true)
+            .unwrap_err()
+            .to_string(),
+        "param 1 nonce: json number 9007199254740993 of u64 may have lost precision, \
+         give it as a string"
+    );
+    let oversized_supply = with("supply", serde_json::json!(3.402823669209385e38));
+    assert_eq!(
+        method.encode_params_json_with_numbers(&oversized_supply, true),
+        Err(AbiError::Param {
+            index: 0,
+            name: "supply".to_string(),
+            error: Box::new(AbiError::UnsafeJsonNumber {
+                ty: "u128".to_string(),
+                value: "3.402823669209385e+38".to_string(),
+            }),
+        })
+    );
+}
+
+#[test]
+fn decode_params_json() {
+    let method = MethodMetaBuilder::default()
+        .name("set")
+        .function()
+        .input("small", "u128")
+        .input("big", "u128")
+        .input("neg", "i64")
+        .input("data", "parampack")
+        .input("rate", "f32")
+        .input("balances", "{str:u64}")
+        .input("value", "enum<u8, [i128]>")
+        .build()
+        .unwrap();
+    let encoded = method
+        .encode_params(&[
+            "9007199254740992",
+            "9007199254740993",
+            "-9007199254740993",
+            "0xdeadbeef",
+            "1.1",
+            "a:1,b:18446744073709551615",
+            "1:-1,170141183460469231731687303715884105727",
+        ])
+        .unwrap();
+    let json = method.decode_params_json(&encoded).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "small": 9007199254740992u64,
+            "big": "9007199254740993",
+            "neg": "-9007199254740993",
+            "data": "0xdeadbeef",
+            "rate": 1.1,
+            "balances": {"a": 1, "b": "18446744073709551615"},
+            "value": {"tag": 1, "value": [-1, "170141183460469231731687303715884105727"]},
+        })
+    );
+    assert_eq!(method.encode_params_json(&json).unwrap(), encoded);
+}
+
+#[test]
+fn encode_large_array_params() {
+    let method = MethodMetaBuilder::default()
+        .name("set")
+        .function()
+        .input("values", "[u64]")
+        .build()
+        .unwrap();
+    let values: Vec<u64> = (0..100_000).map(|i| i * 1_000_003).collect();
+    let values_str: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    let encoded = method.encode_params(&[&values_str.join(",")]).unwrap();
+    // The version byte, the 3 bytes uleb128 length and the fixed-width elements.
+    assert_eq!(encoded.len(), 1 + 3 + 8 * values.len());
+    assert_eq!(
+        method.decode_params(&encoded).unwrap(),
+        [ABIParam::U64Array(values)]
+    );
+}
+
+#[test]
+fn decode_params_with_limits() {
+    let method = MethodMetaBuilder::default()
+        .name("set")
+        .function()
+        .input("values", "[u8]")
+        .input("value", "enum<u8, enum<bool, u8>>")
+        .build()
+        .unwrap();
+    let encoded = method.encode_params(&["1,2,3", "1:0:true"]).unwrap();
+    let limits = DecodeLimits {
+        max_bytes: encoded.len(),
+        max_elements: 3,
+        max_depth: 2,
+    };
+    let options = EncodingOptions::default();
+    assert_eq!(
+        method.decode_params_with_limits(&encoded, options, limits),
+        method.decode_params(&encoded)
+    );
+    for (limits, limit, actual, max) in [
+        (
+            DecodeLimits {
+                max_bytes: encoded.len() - 1,
+                ..limits
+            },
+            "max_bytes",
+            encoded.len(),
+            encoded.len() - 1,
+        ),
+        (
+            DecodeLimits {
+                max_elements: 2,
+                ..limits
+            },
+            "max_elements",
+            3,
+            2,
+        ),
+        (
+            DecodeLimits {
+                max_depth: 1,
+                ..limits
+            },
+            "max_depth",
+            2,
+            1,
+        ),
+    ] {
+        assert_eq!(
+            method.decode_params_with_limits(&encoded, options, limits),
+            Err(AbiError::LimitExceeded {
+                limit: limit.to_string(),
+                actual,
+                max,
+            })
+        );
+    }
+    // The element count is checked before the elements are allocated.
+    let huge_array = [DEFAULT_VERSION, 0xff, 0xff, 0xff, 0xff, 0x0f];
+    assert_eq!(
+        method
+            .decode_params_with_limits(&huge_array, options, limits)
+            .unwrap_err()
+            .to_string(),
+        "decode limit max_elements exceeded: 4294967295 > 3"
+    );
+}
+
+#[test]
+fn encoding_version() {
+    let method = MethodMetaBuilder::default()
+        .name("get")
+        .function()
+        .input("key", "u32")
+        .build()
+        .unwrap();
+    let mut encoded = method.encode_params(&["7"]).unwrap();
+    assert_eq!(EncodingVersion::CURRENT.as_u8(), 0);
+    assert_eq!(encoded[0], EncodingVersion::CURRENT.as_u8());
+    assert_eq!(decode_version(&encoded), Ok(EncodingVersion::CURRENT));
+    // The future version.
+    encoded[0] = 1;
+    assert_eq!(
+        decode_version(&encoded),
+        Err(AbiError::UnsupportedVersion(1))
+    );
+    assert!(decode_version(&[]).is_err());
+}
+
+#[test]
+fn decode_rejects_unknown_version() {
+    let method = MethodMetaBuilder::default()
+        .name("get")
+        .function()
+        .input("key", "u32")
+        .output("u32")
+        .build()
+        .unwrap();
+    let mut encoded = method.encode_params(&["7"]).unwrap();
+    assert_eq!(encoded[0], DEFAULT_VERSION);
+    encoded[0] = 1;
+    assert_eq!(
+        method.decode_params(&encoded),
+        Err(AbiError::UnsupportedVersion(1))
+    );
+    assert_eq!(
+        method.decode_outputs(&encoded),
+        Err(AbiError::UnsupportedVersion(1))
+    );
+    assert_eq!(
+        decode(&[ParamType::U32], &encoded).unwrap_err().to_string(),
+        "unsupported data stream version 1, expect 0"
+    );
+    // The void outputs still check the version byte.
+    let void_method = MethodMetaBuilder::default()
+        .name("reset")
+        .function()
+        .build()
+        .unwrap();
+    assert_eq!(void_method.decode_outputs(&[0x00]), Ok(vec![]));
+    assert_eq!(
+        void_method.decode_outputs(&[0xff]),
+        Err(AbiError::UnsupportedVersion(0xff))
+    );
+}
+
+#[test]
+fn encode_time_params() {
+    let method = MethodMetaBuilder::default()
+        .name("schedule")
+        .function()
+        .input("at", "timestamp")
+        .input("every", "duration")
+        .build()
+        .unwrap();
+    for (params, expected) in [
+        (
+            ["2024-01-01T00:00:00Z", "3600s"],
+            [ABIParam::Timestamp(1704067200), ABIParam::Duration(3600)],
+        ),
+        (
+            ["2024-01-01T08:00:00+08:00", "1h30m"],
+            [ABIParam::Timestamp(1704067200), ABIParam::Duration(5400)],
+        ),
+        (
+            ["1704067200", "3600"],
+            [ABIParam::Timestamp(1704067200), ABIParam::Duration(3600)],
+        ),
+        (
+            ["-1", "1d2h3m4s"],
+            [ABIParam::Timestamp(-1), ABIParam::Duration(93784)],
+        ),
+    ] {
+        let encoded = method.encode_params(&params).unwrap();
+        // Encoded as the underlying integers.
+        let mut expected_bytes = vec![0x00];
+        expected_bytes.append(&mut expected[0].as_bytes());
+        expected_bytes.append(&mut expected[1].as_bytes());
+        assert_eq!(encoded, expected_bytes);
+        assert_eq!(method.decode_params(&encoded).unwrap(), expected);
+    }
+    assert_eq!(
+        method
+            .encode_params(&["2024-13-01T00:00:00Z", "1s"])
+            .unwrap_err(),
+        AbiError::ParseTime {
+            ty: "timestamp".to_string(),
+            value: "2024-13-01T00:00:00Z".to_string()
+        }
+    );
+    assert_eq!(
+        method.encode_params(&["0", "1y"]).unwrap_err(),
+        AbiError::ParseTime {
+            ty: "duration".to_string(),
+            value: "1y".to_string()
+        }
+    );
+    for empty in ["", "  "] {
+        assert_eq!(
+            method.encode_params(&["0", empty]).unwrap_err(),
+            AbiError::ParseTime {
+                ty: "duration".to_string(),
+                value: "".to_string()
+            }
+        );
+    }
+    assert!(matches!(
+        method.encode_params(&["0", "-1"]).unwrap_err(),
+        AbiError::NegativeUnsigned { .. }
+    ));
+}
+
+#[test]
+fn validate_params_reports_all_errors() {
+    let method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("a", "u8")
+        .input("b", "string")
+        .input("c", "[u64]")
+        .input("d", "{str:bool}")
+        .build()
+        .unwrap();
+    assert_eq!(method.validate_params(&["1", "x", "1,2", "k:true"]), Ok(()));
+    let errors = method
+        .validate_params(&["256", "x", "1,a", "k"])
+        .unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            AbiError::Param {
+                index: 0,
+                name: "a".to_string(),
+                error: Box::new(AbiError::IntOutOfRange {
+                    ty: "u8".to_string(),
+                    value: "256".to_string(),
+                    min: "0".to_string(),
+                    max: "255".to_string()
+                }),
+            },
+            AbiError::Param {
+                index: 2,
+                name: "c".to_string(),
+                error: Box::new(AbiError::ArrayElement {
+                    ty: "[u64]".to_string(),
+                    index: 1,
+                    error: Box::new(AbiError::ParseInt {
+                        ty: "u64".to_string(),
+                        value: "a".to_string()
+                    }),
+                }),
+            },
+            AbiError::Param {
+                index: 3,
+                name: "d".to_string(),
+                error: Box::new(AbiError::MalformedMapEntry("k".to_string())),
+            },
+        ]
+    );
+    assert_eq!(
+        errors[0].to_string(),
+        "param 0 a: u8 value 256 is out of range [0, 255]"
+    );
+    assert_eq!(
+        method.validate_params(&["1"]).unwrap_err(),
+        vec![AbiError::ArityMismatch {
+            expected: 4,
+            got: 1
+        }]
+    );
+}
+
+#[test]
+fn method_param_types() {
+    let method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("a", "u8")
+        .input("b", "str")
+        .input("c", "[u64]")
+        .input("d", "{str: bool}")
+        .input("e", "enum<u8,[str]>")
+        .output("{string:string}")
+        .output("timestamp")
+        .build()
+        .unwrap();
+    assert_eq!(
+        method.input_param_types().unwrap(),
+        vec![
+            ABIParamType::U8,
+            ABIParamType::Str,
+            ABIParamType::U64Array,
+            ABIParamType::StrBoolMap,
+            ABIParamType::Variant(vec![ABIParamType::U8, ABIParamType::StrArray]),
+        ]
+    );
+    assert_eq!(
+        method.output_param_types().unwrap(),
+        vec![ABIParamType::StrStrMap, ABIParamType::Timestamp]
+    );
+    let method = MethodMetaBuilder::default()
+        .name("g")
+        .function()
+        .input("a", "u8")
+        .output("u8*")
+        .build()
+        .unwrap();
+    assert_eq!(method.input_param_types().unwrap(), vec![ABIParamType::U8]);
+    assert_eq!(
+        method.output_param_types().unwrap_err(),
+        AbiError::UnsupportedType("u8*".to_string())
+    );
+}
+
+#[test]
+fn method_param_types_after_modifying_inputs() {
+    let mut method = MethodMetaBuilder::default()
+        .name("f")
+        .function()
+        .input("a", "u8")
+        .build()
+        .unwrap();
+    assert_eq!(method.input_param_types().unwrap(), vec![ABIParamType::U8]);
+    method.inputs[0].r#type = "[u8]".to_string();
+    assert_eq!(
+        method.input_param_types().unwrap(),
+        vec![ABIParamType::U8Array]
+    );
+    assert_eq!(method.encode_params(&["1,2"]).unwrap(), vec![0, 2, 1, 2]);
+}
+
+#[test]
+fn abi_meta_shared_across_threads() {
+    let mut abi = IRContractABIMeta::default();
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("f")
+            .function()
+            .input("a", "u8")
+            .build()
+            .unwrap(),
+    );
+    std::thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                let method = abi.get_method("f").unwrap();
+                assert_eq!(method.input_param_types().unwrap(), vec![ABIParamType::U8]);
+                assert_eq!(method.encode_params(&["7"]).unwrap(), vec![0, 7]);
+            });
+        }
+    });
+}
+
+#[test]
+fn abi_diff() {
+    let transfer = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .input("to", "str")
+        .input("amount", "u64")
+        .output("bool")
+        .build()
+        .unwrap();
+    let balance = MethodMetaBuilder::default()
+        .name("balance")
+        .function()
+        .input("owner", "string")
+        .output("u64")
+        .build()
+        .unwrap();
+    let mut old = IRContractABIMeta::default();
+    old.methods = vec![transfer.clone(), balance.clone()];
+    let mut new = IRContractABIMeta::default();
+    new.methods = vec![balance.clone(), transfer.clone()];
+    assert!(old.diff(&new).is_empty());
+
+    let mut changed_balance = balance;
+    changed_balance.outputs[0].r#type = "u128".to_string();
+    let init = MethodMetaBuilder::default()
+        .name("init")
+        .constructor()
+        .build()
+        .unwrap();
+    new.methods = vec![changed_balance, init];
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff,
+        IRContractABIDiff {
+            added: vec!["constructor init()->()".to_string()],
+            removed: vec!["function transfer(string,u64)->(bool)".to_string()],
+            changed: vec![(
+                "function balance(string)->(u64)".to_string(),
+                "function balance(string)->(u128)".to_string()
+            )],
+        }
+    );
+    assert_eq!(
+        diff.to_string(),
+        "added: constructor init()->()\n\
+         removed: function transfer(string,u64)->(bool)\n\
+         changed: function balance(string)->(u64) => function balance(string)->(u128)\n"
+    );
+}
+
+#[test]
+fn indexed_inputs() {
+    let method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .indexed_input("to", "str")
+        .input("amount", "u64")
+        .build()
+        .unwrap();
+    let mut abi = IRContractABIMeta::default();
+    abi.methods.push(method);
+    let decoded_abi = IRContractABIMeta::from_json(&abi.to_json());
+    assert!(decoded_abi.methods[0].inputs[0].indexed);
+    assert!(!decoded_abi.methods[0].inputs[1].indexed);
+    let eth_json: serde_json::Value = serde_json::from_slice(&abi.to_eth_json().unwrap()).unwrap();
+    assert_eq!(eth_json[0]["inputs"][0]["indexed"], true);
+    assert!(eth_json[0]["inputs"][1].get("indexed").is_none());
+
+    // older abi json without the indexed field
+    let old_json = r#"{"abi_version":1,"methods":[{"name":"f","type":"function","inputs":[{"name":"a","type":"u8"}],"outputs":[]}]}"#;
+    let old_abi = IRContractABIMeta::from_json(old_json.as_bytes());
+    assert!(!old_abi.methods[0].inputs[0].indexed);
+
+    let src = r#"module_name = "Token"
+contract Token {
+    state {
+    }
+    pub fn Token.Token.init()  {
+        0:
+            ret()
+    }
+
+    pub fn Token.Token.transfer(%0: str, %1: u64, %2: str, ) !ir_abi_indexed_params !0 {
+        0:
+            ret()
+    }
+
+}
+meta !0 = !{0: u32, 2: u32, }
+"#;
+    let abi = IRContractABIMeta::from_ir_source(src).unwrap();
+    let transfer = abi.get_method("transfer").unwrap();
+    let indexed: Vec<bool> = transfer.inputs.iter().map(|input| input.indexed).collect();
+    assert_eq!(indexed, vec![true, false, true]);
+}
+
+#[test]
+fn abi_descriptions() {
+    let mut method = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .description("Transfer the tokens")
+        .input("to", "str")
+        .input("amount", "u64")
+        .build()
+        .unwrap();
+    method.inputs[1].description = Some("The amount".to_string());
+    let mut abi = IRContractABIMeta::default();
+    abi.abi_version = 1;
+    abi.methods.push(method);
+    let decoded_abi = IRContractABIMeta::from_json_strict(&abi.to_json()).unwrap();
+    let transfer = &decoded_abi.methods[0];
+    assert_eq!(transfer.description.as_deref(), Some("Transfer the tokens"));
+    assert_eq!(transfer.inputs[0].description, None);
+    assert_eq!(
+        transfer.inputs[1].description.as_deref(),
+        Some("The amount")
+    );
+    let explained = decoded_abi.explain(false);
+    assert!(explained.contains(
+        "    description: Transfer the tokens
+    mutability: nonpayable
+    inputs:
+      #  name    type    indexed  description
+      0  to      string
+      1  amount  u64              The amount
+"
+    ));
+
+    // older abi json without the description field
+    let old_json = r#"{"abi_version":1,"methods":[{"name":"f","type":"function","inputs":[{"name":"a","type":"u8"}],"outputs":[]}]}"#;
+    let old_abi = IRContractABIMeta::from_json(old_json.as_bytes());
+    assert_eq!(old_abi.methods[0].description, None);
+    assert_eq!(old_abi.methods[0].inputs[0].description, None);
+
+    let src = r#"module_name = "Token"
+contract Token {
+    state {
+    }
+    pub fn Token.Token.init()  {
+        0:
+            ret()
+    }
+
+    pub fn Token.Token.transfer(%0: str, %1: u64, ) !ir_abi_descriptions !0 {
+        0:
+            ret()
+    }
+
+}
+meta !0 = !{"Transfer the tokens": str, "": str, "The amount": str, }
+"#;
+    let abi = IRContractABIMeta::from_ir_source(src).unwrap();
+    let transfer = abi.get_method("transfer").unwrap();
+    assert_eq!(transfer.description.as_deref(), Some("Transfer the tokens"));
+    let descriptions: Vec<Option<&str>> = transfer
+        .inputs
+        .iter()
+        .map(|input| input.description.as_deref())
+        .collect();
+    assert_eq!(descriptions, vec![None, Some("The amount")]);
+    assert_eq!(abi.get_method("init").unwrap().description, None);
+}
+
+#[test]
+fn abi_functions_and_constructor() {
+    let mut abi = IRContractABIMeta::default();
+    assert!(abi.constructor().is_none());
+    assert_eq!(abi.functions().count(), 0);
+    for name in ["transfer", "balance"] {
+        abi.methods.push(
+            MethodMetaBuilder::default()
+                .name(name)
+                .function()
+                .build()
+                .unwrap(),
+        );
+    }
+    abi.methods.insert(
+        1,
+        MethodMetaBuilder::default()
+            .name("init")
+            .constructor()
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(abi.constructor().unwrap().name, "init");
+    let function_names: Vec<&str> = abi.functions().map(|m| m.name.as_str()).collect();
+    assert_eq!(function_names, ["transfer", "balance"]);
+}
+
+#[test]
+fn method_meta_reflection() {
+    let method = MethodMetaBuilder::default()
+        .name("swap")
+        .function()
+        .input("from", "str")
+        .input("", "u64")
+        .input("min_out", "u128")
+        .output("u128")
+        .output("bool")
+        .build()
+        .unwrap();
+    assert_eq!(method.input_names(), ["from", "", "min_out"]);
+    assert_eq!(method.input_count(), 3);
+    assert_eq!(method.output_count(), 2);
+    let empty = MethodMetaBuilder::default()
+        .name("ping")
+        .function()
+        .build()
+        .unwrap();
+    assert!(empty.input_names().is_empty());
+    assert_eq!((empty.input_count(), empty.output_count()), (0, 0));
+}
+
+#[test]
+fn decode_params_redacted() {
+    let method = MethodMetaBuilder::default()
+        .name("login")
+        .function()
+        .input("user", "str")
+        .input("secret", "str")
+        .input("", "u8")
+        .input("tags", "{str:str}")
+        .build()
+        .unwrap();
+    let encoded = method
+        .encode_params(&["alice", "hunter2", "7", "secret:x"])
+        .unwrap();
+    let redact = ["secret".to_string()].into();
+    assert_eq!(
+        method.decode_params_redacted(&encoded, &redact).unwrap(),
+        [
+            r#"user: "alice""#,
+            "secret: ***",
+            "2: 7",
+            // The nested map keys are not input names.
+            r#"tags: {"secret": "x"}"#,
+        ]
+    );
+    let params = method.decode_params(&encoded).unwrap();
+    assert_eq!(params[1].to_readable_redacted("secret", &redact), "***");
+    assert_eq!(
+        params[0].to_readable_redacted("user", &redact),
+        r#""alice""#
+    );
+}
+
+#[test]
+fn method_meta_to_function_signature() {
+    let method = MethodMetaBuilder::default()
+        .name("sum")
+        .function()
+        .input("values", "[u64]")
+        .input("key", "[u8; 4]")
+        .input("labels", "{str: bool}")
+        .output("u128")
+        .build()
+        .unwrap();
+    assert_eq!(
+        method.to_function_signature().unwrap(),
+        FunctionSignature {
+            name: "sum".to_string(),
+            params: vec![
+                Type::Array {
+                    elem: Rc::new(Type::u64()),
+                    len: None
+                },
+                Type::Array {
+                    elem: Rc::new(Type::u8()),
+                    len: Some(4)
+                },
+                Type::Map {
+                    key: Rc::new(Type::str()),
+                    value: Rc::new(Type::bool())
+                },
+            ],
+            ret: Type::u128(),
+        }
+    );
+    let no_output = MethodMetaBuilder::default()
+        .name("ping")
+        .function()
+        .build()
+        .unwrap();
+    assert_eq!(no_output.to_function_signature().unwrap().ret, Type::void());
+    for (input_ty, outputs, unsupported) in [
+        ("[f64]", vec!["u8"], "[f64]"),
+        ("timestamp", vec!["u8"], "timestamp"),
+        ("u8", vec!["u8", "bool"], "(u8,bool)"),
+    ] {
+        let mut builder = MethodMetaBuilder::default()
+            .name("f")
+            .function()
+            .input("a", input_ty);
+        for output in outputs {
+            builder = builder.output(output);
+        }
+        assert_eq!(
+            builder.build().unwrap().to_function_signature(),
+            Err(AbiError::UnsupportedType(unsupported.to_string()))
+        );
+    }
+    // The signatures of the ir functions are reconstructed from their abi metas.
+    let src = r#"module_name = "Token"
+contract Token {
+    state {
+    }
+    pub fn Token.Token.transfer(%0: str, %1: [u32], ) -> u64 {
+        0:
+            ret(0: u64, )
+    }
+
+}
+"#;
+    with_contract(src, |contract| {
+        let abi = IRContractABIMeta::from_contract(contract);
+        let func_def = &contract.functions["Token.Token.transfer"];
+        let signature = abi
+            .get_method("transfer")
+            .unwrap()
+            .to_function_signature()
+            .unwrap();
+        assert_eq!(signature.params, func_def.params);
+        assert_eq!(signature.ret, func_def.ret);
+    });
+}
+
+#[test]
+fn abi_registry_load_dir() {
+    let dir = std::env::temp_dir().join(format!("ir_cli_abi_registry_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut token = IRContractABIMeta::default();
+    token.name = Some("Token".to_string());
+    for method in [
+        MethodMetaBuilder::default()
+            .name("transfer")
+            .function()
+            .input("to", "str")
+            .input("amount", "u64")
+            .build(),
+        MethodMetaBuilder::default()
+            .name("transfer")
+            .function()
+            .input("to", "str")
+            .build(),
+    ] {
+        token.methods.push(method.unwrap());
+    }
+    // The older abi files without names are named by the file names.
+    let mut vault = IRContractABIMeta::default();
+    vault.methods.push(
+        MethodMetaBuilder::default()
+            .name("deposit")
+            .function()
+            .input("amount", "u64")
+            .build()
+            .unwrap(),
+    );
+    std::fs::write(dir.join("token.abi.json"), token.to_json()).unwrap();
+    std::fs::write(dir.join("Vault.abi.json"), vault.to_json()).unwrap();
+    std::fs::write(dir.join("notes.json"), "{}").unwrap();
+    let registry = AbiRegistry::load_dir(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+    let registry = registry.unwrap();
+
+    let mut contract_names: Vec<&String> = registry.contracts.keys().collect();
+    contract_names.sort();
+    assert_eq!(contract_names, ["Token", "Vault"]);
+    assert_eq!(
+        registry.get("Vault", "deposit").unwrap().signature(),
+        "deposit(u64)"
+    );
+    assert!(registry.get("Vault", "transfer").is_none());
+    assert!(registry.get("Bank", "deposit").is_none());
+
+    let index = registry.selector_index();
+    assert_eq!(index.len(), 3);
+    let call = token.methods[1].encode_call(&["alice"]).unwrap();
+    let selector: [u8; 4] = call[..4].try_into().unwrap();
+    let (contract, method) = &index[&selector];
+    assert_eq!(
+        (contract.as_str(), method.as_str()),
+        ("Token", "transfer(string)")
+    );
+    let method = registry.get(contract, method).unwrap();
+    assert_eq!(
+        method.decode_params(&call[4..]).unwrap(),
+        [ABIParam::Str("alice".to_string())]
+    );
+}
+
+#[test]
+fn abi_normalize_types() {
+    let variant_type = "enum<u8,[u8;32],string>";
+    let mut abi = IRContractABIMeta::default();
+    abi.abi_version = 1;
+    for (name, input_types, output_type) in [
+        ("set", vec![variant_type, "u64"], "[u8;4]"),
+        ("get", vec!["u64"], variant_type),
+        ("swap", vec![variant_type, "[u8;8]"], variant_type),
+        ("hash", vec!["[u8;4]"], "u64"),
+    ] {
+        let mut builder = MethodMetaBuilder::default()
+            .name(name)
+            .function()
+            .output(output_type);
+        for input_type in input_types {
+            builder = builder.input("", input_type);
+        }
+        abi.methods.push(builder.build().unwrap());
+    }
+    let denormalized_json = abi.to_json();
+    let payload = abi.methods[0].encode_params(&["0:7", "7"]).unwrap();
+
+    abi.normalize();
+    assert_eq!(abi.types, [variant_type, "[u8;4]"]);
+    let type_names = |abi: &IRContractABIMeta, index: usize| -> Vec<String> {
+        let method = &abi.methods[index];
+        let input_types = method.inputs.iter().map(|input| input.r#type.clone());
+        let output_types = method.outputs.iter().map(|output| output.r#type.clone());
+        input_types.chain(output_types).collect()
+    };
+    assert_eq!(type_names(&abi, 0), ["#0", "u64", "#1"]);
+    assert_eq!(type_names(&abi, 1), ["u64", "#0"]);
+    // The composite types used once and the scalars are kept in place.
+    assert_eq!(type_names(&abi, 2), ["#0", "[u8;8]", "#0"]);
+    assert_eq!(type_names(&abi, 3), ["#1", "u64"]);
+    assert!(abi.to_json().len() < denormalized_json.len());
+    let mut normalized_abi = IRContractABIMeta::from_json_strict(&abi.to_json()).unwrap();
+    // Normalizing twice does nothing.
+    normalized_abi.normalize();
+    assert_eq!(normalized_abi.types, abi.types);
+
+    normalized_abi.denormalize().unwrap();
+    assert!(normalized_abi.types.is_empty());
+    assert_eq!(normalized_abi.to_json(), denormalized_json);
+    assert_eq!(
+        normalized_abi.methods[0]
+            .encode_params(&["0:7", "7"])
+            .unwrap(),
+        payload
+    );
+
+    abi.methods[1].inputs[0].r#type = "#5".to_string();
+    assert_eq!(abi.denormalize(), Err(AbiError::UnresolvedTypeRef(5)));
+    // Nothing is replaced on the errors.
+    assert_eq!(abi.types.len(), 2);
+    assert_eq!(type_names(&abi, 0), ["#0", "u64", "#1"]);
+}
+
+#[test]
+fn abi_recursive_types() {
+    let mut abi = IRContractABIMeta::default();
+    abi.types = vec!["enum<u64, #0>".to_string()];
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("sum")
+            .function()
+            .input("values", "#0")
+            .output("u64")
+            .build()
+            .unwrap(),
+    );
+    let types = abi.type_table().unwrap();
+    assert_eq!(
+        types
+            .resolve(&ABIParamType::Ref(0))
+            .unwrap()
+            .canonical_string(),
+        "enum<u64,#0>"
+    );
+    assert_eq!(abi.denormalize(), Err(AbiError::UninlinableTypeRef(0)));
+    assert_eq!(abi.types.len(), 1);
+    abi.types = vec!["enum<#0>".to_string()];
+    assert_eq!(abi.type_table(), Err(AbiError::CyclicTypeRef(0)));
+}
+
+#[test]
+fn encode_params_with_type_table() {
+    let types = ABITypeTable::from_type_strs(&["enum<u64,#0>"]).unwrap();
+    let method = MethodMetaBuilder::default()
+        .name("sum")
+        .function()
+        .input("values", "#0")
+        .build()
+        .unwrap();
+    let list = |depth: usize| format!("{}0:5", "1:".repeat(depth));
+    let encoded = method
+        .encode_params_with_type_table(&[&list(2)], &types)
+        .unwrap();
+    let variant = |tag: u32, value: ABIParam| ABIParam::Variant {
+        tag,
+        value: Box::new(value),
+    };
+    assert_eq!(
+        decode_abi_param_with_types(
+            &ABIParamType::Ref(0),
+            &encoded,
+            &mut 1,
+            EncodingOptions::default(),
+            DecodeLimits::default(),
+            &types,
+        )
+        .unwrap(),
+        variant(1, variant(1, variant(0, ABIParam::U64(5))))
+    );
+    // The references are unresolved without the types table.
+    assert_eq!(
+        method.encode_params(&[&list(2)]).unwrap_err(),
+        AbiError::UnresolvedTypeRef(0)
+    );
+    assert!(method
+        .encode_params_with_type_table(&[&list(MAX_TYPE_REF_DEPTH)], &types)
+        .is_ok());
+    assert_eq!(
+        method
+            .encode_params_with_type_table(&[&list(MAX_TYPE_REF_DEPTH + 1)], &types)
+            .unwrap_err(),
+        AbiError::LimitExceeded {
+            limit: "max_depth".to_string(),
+            actual: MAX_TYPE_REF_DEPTH + 1,
+            max: MAX_TYPE_REF_DEPTH,
+        }
+    );
+    assert_eq!(
+        method
+            .encode_params_with_type_table(&["2:5"], &types)
+            .unwrap_err(),
+        AbiError::InvalidVariantTag { tag: 2, count: 2 }
+    );
+}
+
+#[test]
+fn fuzz_params_seeded() {
+    let method = MethodMetaBuilder::default()
+        .name("set")
+        .function()
+        .input("owner", "address20")
+        .input("amounts", "{str:u128}")
+        .input("value", "enum<[u8;4], [str], f64>")
+        .input("at", "timestamp")
+        .build()
+        .unwrap();
+    let blobs = method.fuzz_params(42, 32).unwrap();
+    assert_eq!(blobs.len(), 32);
+    assert_eq!(method.fuzz_params(42, 32).unwrap(), blobs);
+    assert_ne!(method.fuzz_params(43, 32).unwrap(), blobs);
+    // The first sets of a larger count are the same.
+    assert_eq!(method.fuzz_params(42, 8).unwrap(), blobs[..8]);
+    for blob in &blobs {
+        let params = method.decode_params(blob).unwrap();
+        assert_eq!(params.len(), 4);
+    }
+    let method = MethodMetaBuilder::default()
+        .name("get")
+        .function()
+        .input("key", "#0")
+        .build()
+        .unwrap();
+    assert_eq!(
+        method.fuzz_params(42, 1),
+        Err(AbiError::UnresolvedTypeRef(0))
+    );
+}
+
+#[test]
+fn abi_explain() {
+    let mut abi = IRContractABIMeta::default();
+    abi.abi_version = 1;
+    let transfer = MethodMetaBuilder::default()
+        .name("transfer")
+        .function()
+        .indexed_input("to", "str")
+        .input("amount", "u64")
+        .output("bool")
+        .build()
+        .unwrap();
+    let init = MethodMetaBuilder::default()
+        .name("init")
+        .constructor()
+        .build()
+        .unwrap();
+    let transfer_selector = hex::encode(transfer.selector());
+    let init_selector = hex::encode(init.selector());
+    abi.methods.push(transfer);
+    abi.methods.push(init);
+    abi.errors.push(IRContractErrorMeta {
+        name: "InsufficientBalance".to_string(),
+        code: 1,
+        fields: vec![IRContractMethodInputMeta {
+            name: "needed".to_string(),
+            r#type: "u64".to_string(),
+            ..Default::default()
+        }],
+    });
+    assert_eq!(
+        abi.explain(false),
+        format!(
+            "abi version: 1
+constructors:
+  init()  0x{init_selector}
+    mutability: nonpayable
+    inputs: none
+    outputs: none
+functions:
+  transfer(string,u64)  0x{transfer_selector}
+    mutability: nonpayable
+    inputs:
+      #  name    type    indexed
+      0  to      string  yes
+      1  amount  u64
+    outputs:
+      #  type
+      0  bool
+errors:
+  InsufficientBalance  code 1
+    #  name    type
+    0  needed  u64
+"
+        )
+    );
+    let colored = abi.explain(true);
+    assert!(colored.contains("\x1b[1mtransfer(string,u64)\x1b[0m"));
+    assert!(colored.contains(&format!("\x1b[36m0x{transfer_selector}\x1b[0m")));
+    // The columns are still aligned with the colors.
+    assert!(colored.contains("      0  to      string  yes\n"));
+}
@@ -2,7 +2,10 @@
 // Copyright (c) The Smart Intermediate Representation Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod abi;
 mod examples;
+#[cfg(feature = "wasm")]
+mod wasm;
 
 use smart_ir::abi::params::ABIParam;
 
@@ -0,0 +1,39 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::abi::builder::MethodMetaBuilder;
+use crate::abi::IRContractABIMeta;
+use crate::wasm::{decode_params, encode_params};
+
+// The JS values can only be created on the wasm targets, so only the successful calls
+// are tested natively.
+#[test]
+fn wasm_encode_decode_params() {
+    let mut abi = IRContractABIMeta::default();
+    abi.abi_version = 1;
+    abi.methods.push(
+        MethodMetaBuilder::default()
+            .name("transfer")
+            .function()
+            .input("to", "str")
+            .input("amount", "u64")
+            .build()
+            .unwrap(),
+    );
+    let abi_json = String::from_utf8(abi.to_json()).unwrap();
+    let bytes = encode_params(
+        &abi_json,
+        "transfer",
+        vec!["alice".to_string(), "7".to_string()],
+    )
+    .unwrap();
+    assert_eq!(
+        bytes,
+        abi.methods[0].encode_params(&["alice", "7"]).unwrap()
+    );
+    assert_eq!(
+        decode_params(&abi_json, "transfer", &bytes).unwrap(),
+        ["\"alice\"", "7"]
+    );
+}
@@ -0,0 +1,42 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `wasm-bindgen` facade of the abi encoder for the browsers, which takes the abi json
+//! text and the method name and forwards to `IRContractMethodMeta`. The errors, including
+//! the `AbiError`s, are thrown as the JS exceptions with their messages.
+
+use crate::abi::IRContractABIMeta;
+use wasm_bindgen::prelude::*;
+
+fn load_abi(abi_json: &str) -> Result<IRContractABIMeta, JsValue> {
+    serde_json::from_str(abi_json)
+        .map_err(|e| JsValue::from_str(&format!("could not deserialize from json: {e}")))
+}
+
+fn abi_error(err: impl ToString) -> JsValue {
+    JsValue::from_str(&err.to_string())
+}
+
+/// Encode the params strings of the method in the same way as
+/// `IRContractMethodMeta::encode_params`.
+#[wasm_bindgen(js_name = encodeParams)]
+pub fn encode_params(abi_json: &str, method: &str, args: Vec<String>) -> Result<Vec<u8>, JsValue> {
+    let abi = load_abi(abi_json)?;
+    let method_meta = abi
+        .get_method(method)
+        .ok_or_else(|| abi_error(format!("unknown method {method}")))?;
+    method_meta.encode_params_iter(&args).map_err(abi_error)
+}
+
+/// Decode the params bytes of the method in the same way as
+/// `IRContractMethodMeta::decode_params`, the params are rendered by `ABIParam::to_readable`.
+#[wasm_bindgen(js_name = decodeParams)]
+pub fn decode_params(abi_json: &str, method: &str, bytes: &[u8]) -> Result<Vec<String>, JsValue> {
+    let abi = load_abi(abi_json)?;
+    let method_meta = abi
+        .get_method(method)
+        .ok_or_else(|| abi_error(format!("unknown method {method}")))?;
+    let params = method_meta.decode_params(bytes).map_err(abi_error)?;
+    Ok(params.iter().map(|param| param.to_readable()).collect())
+}
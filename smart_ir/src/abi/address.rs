@@ -0,0 +1,136 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The checksum schemes of the hex address strings. The chains use addresses of different
+//! lengths, e.g., the 20-byte EVM addresses and the 32-byte ones, so the scheme is chosen
+//! by the caller rather than fixed by the `address` types.
+
+use crate::abi::error::AbiError;
+use crate::abi::params::{decode_hex_str, encode_hex_str};
+
+/// The checksum scheme validated when parsing the hex address strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AddressChecksum {
+    /// The letter case of the hex digits is not checked.
+    #[default]
+    None,
+    /// The EIP-55 mixed-case checksum of the 20-byte addresses. The all-lowercase and the
+    /// all-uppercase strings carry no checksum and are accepted, as EIP-55 specifies.
+    Eip55,
+}
+
+impl AddressChecksum {
+    /// The usual scheme of the address length, which is `Eip55` for the 20-byte addresses
+    /// and `None` for the others.
+    pub fn for_len(len: Option<usize>) -> AddressChecksum {
+        match len {
+            Some(EIP55_ADDRESS_LEN) => AddressChecksum::Eip55,
+            _ => AddressChecksum::None,
+        }
+    }
+
+    /// Decode the hex address string, accepting the same forms as `decode_hex_str`, and
+    /// validate its checksum.
+    pub fn decode(&self, hex_str: &str) -> Result<Vec<u8>, AbiError> {
+        let bytes = decode_hex_str(hex_str)?;
+        if *self == AddressChecksum::Eip55 {
+            if bytes.len() != EIP55_ADDRESS_LEN {
+                return Err(AbiError::UnsupportedType(format!(
+                    "eip-55 checksum of {}-byte addresses",
+                    bytes.len()
+                )));
+            }
+            let digits = &hex_str[hex_str.len() - bytes.len() * 2..];
+            let is_mixed_case = digits.chars().any(|c| c.is_ascii_lowercase())
+                && digits.chars().any(|c| c.is_ascii_uppercase());
+            let expected = eip55_checksum(&bytes);
+            if is_mixed_case && digits != &expected[2..] {
+                return Err(AbiError::AddressChecksum {
+                    address: hex_str.to_string(),
+                    expected,
+                });
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+/// The address length checksummed by EIP-55.
+pub const EIP55_ADDRESS_LEN: usize = 20;
+
+/// The `0x` prefixed EIP-55 checksummed hex string of the address bytes: the hex letter
+/// is uppercased when the matching nibble of the keccak-256 hash of the lowercase hex
+/// digits is at least 8.
+pub fn eip55_checksum(bytes: &[u8]) -> String {
+    let digits = encode_hex_str(bytes, false);
+    let hash = keccak_hash::keccak(digits.as_bytes());
+    let mut checksummed = String::with_capacity(digits.len() + 2);
+    checksummed.push_str("0x");
+    for (i, c) in digits.chars().enumerate() {
+        let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+        checksummed.push(if nibble >= 8 {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        });
+    }
+    checksummed
+}
+
+#[cfg(test)]
+mod address_test {
+    use super::{eip55_checksum, AddressChecksum};
+    use crate::abi::error::AbiError;
+    use crate::abi::params::{decode_hex_str, ABIParam};
+
+    /// The test vectors of EIP-55.
+    const CHECKSUMMED: [&str; 4] = [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn test_eip55_checksum() {
+        for address in CHECKSUMMED {
+            let bytes = decode_hex_str(address).unwrap();
+            assert_eq!(eip55_checksum(&bytes), address);
+            assert_eq!(
+                ABIParam::address_from_hex(address, Some(20), AddressChecksum::Eip55).unwrap(),
+                ABIParam::Address {
+                    bytes: bytes.clone(),
+                    len: Some(20)
+                }
+            );
+            // No checksum in the single-case strings.
+            for unchecked in [
+                address.to_lowercase(),
+                address.to_uppercase().replace("0X", "0x"),
+            ] {
+                assert!(AddressChecksum::Eip55.decode(&unchecked).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_eip55_invalid_checksum() {
+        // The first letter `a` of the valid checksum `0x5aAeb6...` is uppercased.
+        let address = "0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert_eq!(
+            ABIParam::address_from_hex(address, Some(20), AddressChecksum::Eip55),
+            Err(AbiError::AddressChecksum {
+                address: address.to_string(),
+                expected: CHECKSUMMED[0].to_string(),
+            })
+        );
+        // Not validated without the checksum scheme.
+        assert!(ABIParam::address_from_hex(address, Some(20), AddressChecksum::None).is_ok());
+        assert!(AddressChecksum::Eip55
+            .decode(&format!("0x{}", "aB".repeat(32)))
+            .is_err());
+        assert_eq!(AddressChecksum::for_len(Some(20)), AddressChecksum::Eip55);
+        assert_eq!(AddressChecksum::for_len(Some(32)), AddressChecksum::None);
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use core::fmt;
+
+use crate::encoding::datastream::DEFAULT_VERSION;
+
+/// The error of parsing, encoding and decoding the abi params, so that the callers
+/// can branch on the error kind instead of matching the message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiError {
+    /// The abi type name can't be encoded, e.g., `u8*` or `{u8:u64}`.
+    UnsupportedType(String),
+    /// The value is not a valid integer literal of the type.
+    ParseInt { ty: String, value: String },
+    /// The negative value of an unsigned integer type.
+    NegativeUnsigned { ty: String, value: String },
+    /// The integer value does not fit in the type.
+    IntOutOfRange {
+        ty: String,
+        value: String,
+        min: String,
+        max: String,
+    },
+    /// The value is not a valid float literal of the type.
+    ParseFloat { ty: String, value: String },
+    /// NaN and infinite float values.
+    NonFiniteFloat { ty: String, value: String },
+    /// The value is not a valid timestamp or duration such as `2024-01-01T00:00:00Z` or `1h30m`.
+    ParseTime { ty: String, value: String },
+    /// The value is not `true` or `false`.
+    ParseBool { value: String },
+    /// The invalid hex string of bytes params such as `parampack`.
+    BadHex { value: String, reason: String },
+    /// The number of the given params does not match the method inputs, `got` may be
+    /// more or less than `expected`.
+    ArityMismatch { expected: usize, got: usize },
+    /// The map entry is not in the form of `k:v`.
+    MalformedMapEntry(String),
+    /// The error of the method param at `index` named `name`.
+    Param {
+        index: usize,
+        name: String,
+        error: Box<AbiError>,
+    },
+    /// The element at `index` of the array type `ty` such as `[i8]` is invalid.
+    ArrayElement {
+        ty: String,
+        index: usize,
+        error: Box<AbiError>,
+    },
+    /// The element count of the fixed-size array such as `[u8;32]` is not the declared one.
+    FixedArrayLength { expected: usize, got: usize },
+    /// The byte length of the address such as `address20` is not the declared one.
+    AddressLength { expected: usize, got: usize },
+    /// The mixed-case hex address doesn't match the checksum such as EIP-55.
+    AddressChecksum { address: String, expected: String },
+    /// The bytes can't be decoded as the type.
+    Decode { ty: String, reason: String },
+    /// The param can't be converted from or to the ethabi token.
+    EthToken { ty: String, reason: String },
+    /// No method in the abi has the selector of the call data.
+    UnknownSelector([u8; 4]),
+    /// The variant value is not in the form of `tag:value`.
+    MalformedVariant(String),
+    /// The variant tag is not less than the declared variant count.
+    InvalidVariantTag { tag: u32, count: usize },
+    /// The json value does not match the param type `ty`, e.g., a json number of `string`.
+    JsonType { ty: String, value: String },
+    /// The bare json number of the integer type is out of the range `-2^53..=2^53` which
+    /// the `f64` json numbers represent exactly, rejected in the strict numeric mode.
+    UnsafeJsonNumber { ty: String, value: String },
+    /// The method input named by the string is not given.
+    MissingParam(String),
+    /// The given param named by the string is not a method input.
+    UnknownParam(String),
+    /// The method input at the index has no name, so the params can't be matched by names.
+    UnnamedParam(usize),
+    /// The named param string is not in the form of `name=value`.
+    MalformedNamedParam(String),
+    /// The param named by the string is given more than once.
+    DuplicateParam(String),
+    /// The decoded string bytes are not valid UTF-8, `offset` is where the invalid byte
+    /// sequence starts in the decoded bytes.
+    InvalidUtf8 { offset: usize },
+    /// The decoded bytes exceed the `limit` of `DecodeLimits`, such as `max_elements`.
+    LimitExceeded {
+        limit: String,
+        actual: usize,
+        max: usize,
+    },
+    /// The leading version byte of the data stream is not `EncodingVersion::CURRENT`,
+    /// i.e., `DEFAULT_VERSION`.
+    UnsupportedVersion(u8),
+    /// The framed params are not the declared length, `expected` includes the length
+    /// prefix and the checksum, i.e., the truncated or padded frames.
+    FrameLength { expected: usize, got: usize },
+    /// The CRC-32 checksum of the framed params does not match the trailer.
+    FrameChecksum { expected: u32, got: u32 },
+    /// The overriding type `overridden` is not a subtype of the `declared` input type.
+    IncompatibleTypeOverride {
+        declared: String,
+        overridden: String,
+    },
+    /// The `remaining` bytes are left after the last decoded param.
+    TrailingBytes { remaining: usize },
+    /// The type reference such as `#0` is not in the types table, or refers to a later
+    /// type of the table.
+    UnresolvedTypeRef(usize),
+    /// The type of the id in the types table refers to itself without a finite value,
+    /// e.g., `enum<#0>` or `#0` itself at `#0`.
+    CyclicTypeRef(usize),
+    /// The type of the id in the types table has type references itself, so the references
+    /// to it can't be replaced with its type name.
+    UninlinableTypeRef(usize),
+    /// The key is in both of the merged map params.
+    DuplicateMapKey(String),
+    /// The merged params are not maps of the same type such as `{str:u64}`.
+    MapTypeMismatch { left: String, right: String },
+    /// The built method meta has no name.
+    MissingMethodName,
+    /// The method type of the built method meta is not `constructor` or `function`.
+    InvalidMethodType { name: String, method_type: String },
+}
+
+impl fmt::Display for AbiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiError::UnsupportedType(ty) => write!(f, "not supported abi param type {ty}"),
+            AbiError::ParseInt { ty, value } => write!(f, "invalid {ty} value {value}"),
+            AbiError::NegativeUnsigned { ty, value } => {
+                write!(f, "{ty} cannot be negative: {value}")
+            }
+            AbiError::IntOutOfRange {
+                ty,
+                value,
+                min,
+                max,
+            } => write!(f, "{ty} value {value} is out of range [{min}, {max}]"),
+            AbiError::ParseFloat { ty, value } => write!(f, "invalid {ty} value {value}"),
+            AbiError::NonFiniteFloat { ty, value } => {
+                write!(f, "{ty} value must be finite, got {value}")
+            }
+            AbiError::ParseTime { ty, value } => write!(f, "invalid {ty} value {value}"),
+            AbiError::ParseBool { value } => write!(f, "invalid bool value {value}"),
+            AbiError::BadHex { value, reason } => write!(f, "invalid hex string {value}: {reason}"),
+            AbiError::ArityMismatch { expected, got } => write!(
+                f,
+                "too {} params, expect {expected} params, but got {got}",
+                if got < expected { "few" } else { "many" }
+            ),
+            AbiError::MalformedMapEntry(entry) => {
+                write!(f, "invalid map entry {entry}, expected k1:v1,k2:v2,...")
+            }
+            AbiError::Param { index, name, error } => write!(f, "param {index} {name}: {error}"),
+            AbiError::ArrayElement { ty, index, error } => match error.as_ref() {
+                AbiError::IntOutOfRange {
+                    value, min, max, ..
+                } => write!(
+                    f,
+                    "{ty} element #{index} out of range: {value}, expect [{min}, {max}]"
+                ),
+                error => write!(f, "{ty} element #{index}: {error}"),
+            },
+            AbiError::FixedArrayLength { expected, got } => write!(
+                f,
+                "expect {expected} fixed-size array elements, but got {got}"
+            ),
+            AbiError::AddressLength { expected, got } => {
+                write!(f, "expect {expected} address bytes, but got {got}")
+            }
+            AbiError::AddressChecksum { address, expected } => {
+                write!(
+                    f,
+                    "invalid checksum of address {address}, expect {expected}"
+                )
+            }
+            AbiError::Decode { ty, reason } => write!(f, "decode {ty} error: {reason}"),
+            AbiError::EthToken { ty, reason } => {
+                write!(f, "convert {ty} eth token error: {reason}")
+            }
+            AbiError::UnknownSelector(selector) => {
+                write!(f, "unknown method selector 0x{}", hex::encode(selector))
+            }
+            AbiError::MalformedVariant(value) => {
+                write!(f, "invalid variant {value}, expected tag:value")
+            }
+            AbiError::InvalidVariantTag { tag, count } => write!(
+                f,
+                "variant tag {tag} is out of range, expect less than {count}"
+            ),
+            AbiError::JsonType { ty, value } => {
+                write!(f, "expect {ty} json value, but got {value}")
+            }
+            AbiError::UnsafeJsonNumber { ty, value } => write!(
+                f,
+                "json number {value} of {ty} may have lost precision, give it as a string"
+            ),
+            AbiError::MissingParam(name) => write!(f, "missing param {name}"),
+            AbiError::UnknownParam(name) => write!(f, "unknown param {name}"),
+            AbiError::UnnamedParam(index) => {
+                write!(f, "param {index} has no name to match the named params")
+            }
+            AbiError::MalformedNamedParam(param) => {
+                write!(f, "invalid named param {param}, expected name=value")
+            }
+            AbiError::DuplicateParam(name) => write!(f, "param {name} is given more than once"),
+            AbiError::InvalidUtf8 { offset } => {
+                write!(f, "invalid utf-8 string bytes at offset {offset}")
+            }
+            AbiError::LimitExceeded { limit, actual, max } => {
+                write!(f, "decode limit {limit} exceeded: {actual} > {max}")
+            }
+            AbiError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported data stream version {version}, expect {DEFAULT_VERSION}"
+            ),
+            AbiError::FrameLength { expected, got } => {
+                write!(f, "expect {expected} framed bytes, but got {got}")
+            }
+            AbiError::FrameChecksum { expected, got } => write!(
+                f,
+                "frame checksum mismatch, expect {expected:#010x}, but got {got:#010x}"
+            ),
+            AbiError::IncompatibleTypeOverride {
+                declared,
+                overridden,
+            } => write!(
+                f,
+                "type {overridden} can't override the declared type {declared}"
+            ),
+            AbiError::TrailingBytes { remaining } => {
+                write!(f, "unexpected {remaining} trailing bytes after the params")
+            }
+            AbiError::UnresolvedTypeRef(id) => write!(f, "unresolved type reference #{id}"),
+            AbiError::CyclicTypeRef(id) => {
+                write!(f, "type #{id} refers to itself without a finite value")
+            }
+            AbiError::UninlinableTypeRef(id) => {
+                write!(f, "type #{id} has type references and can't be inlined")
+            }
+            AbiError::DuplicateMapKey(key) => write!(f, "duplicate map key {key}"),
+            AbiError::MapTypeMismatch { left, right } => {
+                write!(
+                    f,
+                    "cannot merge {left} with {right}, expect the same map type"
+                )
+            }
+            AbiError::MissingMethodName => write!(f, "method name is not set"),
+            AbiError::InvalidMethodType { name, method_type } => write!(
+                f,
+                "invalid method type '{method_type}' of method {name}, expect 'constructor' or 'function'"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AbiError {}
+
+impl From<AbiError> for String {
+    fn from(err: AbiError) -> Self {
+        err.to_string()
+    }
+}
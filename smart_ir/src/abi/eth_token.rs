@@ -0,0 +1,418 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversions between the abi params and the `ethabi` tokens, so that the params can be
+//! bridged into the EVM tooling. Integers are widened to 256 bits, `bytes` and `[u8]` are
+//! mapped to the dynamic bytes and the other arrays to the dynamic token arrays, and the
+//! fixed-size arrays to the fixed bytes and the fixed token arrays. The `address20`
+//! addresses are mapped to the address tokens. Floats, maps, parampacks, variants and the
+//! other addresses have no EVM analog and fail to convert.
+
+use core::fmt::Display;
+
+use ethabi::{Int, Token, Uint};
+use num_traits::Bounded;
+
+use crate::abi::address::EIP55_ADDRESS_LEN;
+use crate::abi::error::AbiError;
+use crate::abi::params::ABIParam;
+use crate::abi::types::ABIParamType;
+
+macro_rules! uint_param {
+    ($ty:literal, $token:expr) => {
+        narrow($ty, token_to_u128($ty, $token)?)?
+    };
+}
+
+macro_rules! int_param {
+    ($ty:literal, $token:expr) => {
+        narrow($ty, token_to_i128($ty, $token)?)?
+    };
+}
+
+macro_rules! array_param {
+    ($token:expr, $array_ty:literal, $item:ident => $elem:expr) => {
+        token_items($array_ty, $token)?
+            .iter()
+            .map(|$item| Ok($elem))
+            .collect::<Result<_, AbiError>>()?
+    };
+}
+
+impl TryFrom<ABIParam> for Token {
+    type Error = AbiError;
+
+    fn try_from(param: ABIParam) -> Result<Self, Self::Error> {
+        let token = match param {
+            ABIParam::U8(v) => uint_token(v),
+            ABIParam::U16(v) => uint_token(v),
+            ABIParam::U32(v) => uint_token(v),
+            ABIParam::U64(v) => uint_token(v),
+            ABIParam::U128(v) => uint_token(v),
+            ABIParam::I8(v) => int_token(v),
+            ABIParam::I16(v) => int_token(v),
+            ABIParam::I32(v) => int_token(v),
+            ABIParam::I64(v) => int_token(v),
+            ABIParam::I128(v) => int_token(v),
+            ABIParam::Bool(v) => Token::Bool(v),
+            ABIParam::Str(v) => Token::String(v),
+            ABIParam::Timestamp(v) => int_token(v),
+            ABIParam::Duration(v) => uint_token(v),
+            ABIParam::U8Array(v) | ABIParam::Bytes(v) => Token::Bytes(v),
+            ABIParam::I8Array(v) => Token::Array(v.into_iter().map(int_token).collect()),
+            ABIParam::U16Array(v) => Token::Array(v.into_iter().map(uint_token).collect()),
+            ABIParam::I16Array(v) => Token::Array(v.into_iter().map(int_token).collect()),
+            ABIParam::U32Array(v) => Token::Array(v.into_iter().map(uint_token).collect()),
+            ABIParam::I32Array(v) => Token::Array(v.into_iter().map(int_token).collect()),
+            ABIParam::U64Array(v) => Token::Array(v.into_iter().map(uint_token).collect()),
+            ABIParam::I64Array(v) => Token::Array(v.into_iter().map(int_token).collect()),
+            ABIParam::U128Array(v) => Token::Array(v.into_iter().map(uint_token).collect()),
+            ABIParam::I128Array(v) => Token::Array(v.into_iter().map(int_token).collect()),
+            ABIParam::BoolArray(v) => Token::Array(v.into_iter().map(Token::Bool).collect()),
+            ABIParam::StrArray(v) => Token::Array(v.into_iter().map(Token::String).collect()),
+            ABIParam::FixedArray(array) => match Token::try_from(*array)? {
+                Token::Bytes(v) => Token::FixedBytes(v),
+                Token::Array(items) => Token::FixedArray(items),
+                token => token,
+            },
+            ABIParam::Address {
+                bytes,
+                len: Some(EIP55_ADDRESS_LEN),
+            } => Token::Address(ethabi::Address::from_slice(&bytes)),
+            param => {
+                let ty = match param.to_param_type() {
+                    Some(param_ty) => format!("{param_ty:?}"),
+                    None if matches!(param, ABIParam::Address { .. }) => "Address".to_string(),
+                    None => "Variant".to_string(),
+                };
+                return Err(AbiError::EthToken {
+                    ty,
+                    reason: "no eth token analog".to_string(),
+                });
+            }
+        };
+        Ok(token)
+    }
+}
+
+/// Convert the token to the widest param of its kind, e.g., uints to `U128` and bytes to
+/// `U8Array`, because the token carries no integer width. Use `ABIParam::from_eth_token`
+/// to convert to the exact param type.
+impl TryFrom<Token> for ABIParam {
+    type Error = AbiError;
+
+    fn try_from(token: Token) -> Result<Self, Self::Error> {
+        let param_ty = match &token {
+            Token::Uint(_) => ABIParamType::U128,
+            Token::Int(_) => ABIParamType::I128,
+            Token::Bool(_) => ABIParamType::Bool,
+            Token::String(_) => ABIParamType::Str,
+            Token::Bytes(_) | Token::FixedBytes(_) => ABIParamType::U8Array,
+            Token::Address(_) => ABIParamType::Address(Some(EIP55_ADDRESS_LEN)),
+            Token::Array(items) | Token::FixedArray(items) => match items.first() {
+                Some(Token::Uint(_)) => ABIParamType::U128Array,
+                Some(Token::Int(_)) => ABIParamType::I128Array,
+                Some(Token::Bool(_)) => ABIParamType::BoolArray,
+                Some(Token::String(_)) => ABIParamType::StrArray,
+                Some(item) => return Err(mismatch("array", item)),
+                None => {
+                    return Err(AbiError::EthToken {
+                        ty: "array".to_string(),
+                        reason: "can't infer the element type of the empty array".to_string(),
+                    })
+                }
+            },
+            Token::Tuple(_) => return Err(mismatch("param", &token)),
+        };
+        ABIParam::from_eth_token(&token, &param_ty)
+    }
+}
+
+impl ABIParam {
+    /// Convert the token to the param of the abi param type, the integers out of the
+    /// range of the type are rejected.
+    pub fn from_eth_token(token: &Token, ty: &ABIParamType) -> Result<ABIParam, AbiError> {
+        let param = match ty {
+            ABIParamType::U8 => ABIParam::U8(uint_param!("u8", token)),
+            ABIParamType::U16 => ABIParam::U16(uint_param!("u16", token)),
+            ABIParamType::U32 => ABIParam::U32(uint_param!("u32", token)),
+            ABIParamType::U64 => ABIParam::U64(uint_param!("u64", token)),
+            ABIParamType::U128 => ABIParam::U128(uint_param!("u128", token)),
+            ABIParamType::I8 => ABIParam::I8(int_param!("i8", token)),
+            ABIParamType::I16 => ABIParam::I16(int_param!("i16", token)),
+            ABIParamType::I32 => ABIParam::I32(int_param!("i32", token)),
+            ABIParamType::I64 => ABIParam::I64(int_param!("i64", token)),
+            ABIParamType::I128 => ABIParam::I128(int_param!("i128", token)),
+            ABIParamType::Timestamp => ABIParam::Timestamp(int_param!("timestamp", token)),
+            ABIParamType::Duration => ABIParam::Duration(uint_param!("duration", token)),
+            ABIParamType::Bool => match token {
+                Token::Bool(v) => ABIParam::Bool(*v),
+                _ => return Err(mismatch("bool", token)),
+            },
+            ABIParamType::Str => match token {
+                Token::String(v) => ABIParam::Str(v.clone()),
+                _ => return Err(mismatch("string", token)),
+            },
+            ABIParamType::Bytes => match token {
+                Token::Bytes(v) | Token::FixedBytes(v) => ABIParam::Bytes(v.clone()),
+                _ => return Err(mismatch("bytes", token)),
+            },
+            ABIParamType::U8Array => match token {
+                Token::Bytes(v) | Token::FixedBytes(v) => ABIParam::U8Array(v.clone()),
+                _ => {
+                    ABIParam::U8Array(array_param!(token, "[u8]", item => uint_param!("u8", item)))
+                }
+            },
+            ABIParamType::U16Array => {
+                ABIParam::U16Array(array_param!(token, "[u16]", item => uint_param!("u16", item)))
+            }
+            ABIParamType::U32Array => {
+                ABIParam::U32Array(array_param!(token, "[u32]", item => uint_param!("u32", item)))
+            }
+            ABIParamType::U64Array => {
+                ABIParam::U64Array(array_param!(token, "[u64]", item => uint_param!("u64", item)))
+            }
+            ABIParamType::U128Array => ABIParam::U128Array(
+                array_param!(token, "[u128]", item => uint_param!("u128", item)),
+            ),
+            ABIParamType::I8Array => {
+                ABIParam::I8Array(array_param!(token, "[i8]", item => int_param!("i8", item)))
+            }
+            ABIParamType::I16Array => {
+                ABIParam::I16Array(array_param!(token, "[i16]", item => int_param!("i16", item)))
+            }
+            ABIParamType::I32Array => {
+                ABIParam::I32Array(array_param!(token, "[i32]", item => int_param!("i32", item)))
+            }
+            ABIParamType::I64Array => {
+                ABIParam::I64Array(array_param!(token, "[i64]", item => int_param!("i64", item)))
+            }
+            ABIParamType::I128Array => {
+                ABIParam::I128Array(array_param!(token, "[i128]", item => int_param!("i128", item)))
+            }
+            ABIParamType::BoolArray => ABIParam::BoolArray(array_param!(token, "[bool]", item => {
+                match item {
+                    Token::Bool(v) => *v,
+                    _ => return Err(mismatch("bool", item)),
+                }
+            })),
+            ABIParamType::StrArray => ABIParam::StrArray(array_param!(token, "[string]", item => {
+                match item {
+                    Token::String(v) => v.clone(),
+                    _ => return Err(mismatch("string", item)),
+                }
+            })),
+            ABIParamType::FixedArray(array_ty, len) => {
+                ABIParam::fixed_array(ABIParam::from_eth_token(token, array_ty)?, *len)?
+            }
+            ABIParamType::Address(Some(EIP55_ADDRESS_LEN)) => match token {
+                Token::Address(v) => ABIParam::Address {
+                    bytes: v.as_bytes().to_vec(),
+                    len: Some(EIP55_ADDRESS_LEN),
+                },
+                _ => return Err(mismatch("address20", token)),
+            },
+            _ => {
+                return Err(AbiError::EthToken {
+                    ty: format!("{ty:?}"),
+                    reason: "no eth token analog".to_string(),
+                })
+            }
+        };
+        Ok(param)
+    }
+}
+
+fn uint_token(v: impl Into<u128>) -> Token {
+    Token::Uint(Uint::from(v.into()))
+}
+
+/// The two's complement 256-bit int token.
+fn int_token(v: impl Into<i128>) -> Token {
+    let v: i128 = v.into();
+    if v >= 0 {
+        Token::Int(Int::from(v as u128))
+    } else {
+        Token::Int(!Int::from(!v as u128))
+    }
+}
+
+fn mismatch(ty: &str, token: &Token) -> AbiError {
+    AbiError::EthToken {
+        ty: ty.to_string(),
+        reason: format!("unexpected token {token:?}"),
+    }
+}
+
+fn token_items<'a>(ty: &str, token: &'a Token) -> Result<&'a Vec<Token>, AbiError> {
+    match token {
+        Token::Array(items) | Token::FixedArray(items) => Ok(items),
+        _ => Err(mismatch(ty, token)),
+    }
+}
+
+fn token_to_u128(ty: &str, token: &Token) -> Result<u128, AbiError> {
+    match token {
+        Token::Uint(v) if v.bits() <= 128 => Ok(v.low_u128()),
+        Token::Uint(v) => Err(out_of_range::<u128>(ty, v)),
+        _ => Err(mismatch(ty, token)),
+    }
+}
+
+fn token_to_i128(ty: &str, token: &Token) -> Result<i128, AbiError> {
+    let v = match token {
+        Token::Int(v) => v,
+        _ => return Err(mismatch(ty, token)),
+    };
+    // The magnitude of the negative value `-(m + 1)` is `m = !v`.
+    let negative = v.bit(255);
+    let magnitude = if negative { !*v } else { *v };
+    if magnitude.bits() > 127 {
+        let value = if negative {
+            format!("-{}", magnitude + 1)
+        } else {
+            magnitude.to_string()
+        };
+        return Err(out_of_range::<i128>(ty, value));
+    }
+    let low = magnitude.low_u128() as i128;
+    Ok(if negative { !low } else { low })
+}
+
+fn out_of_range<T: Bounded + Display>(ty: &str, value: impl Display) -> AbiError {
+    AbiError::IntOutOfRange {
+        ty: ty.to_string(),
+        value: value.to_string(),
+        min: T::min_value().to_string(),
+        max: T::max_value().to_string(),
+    }
+}
+
+/// Narrow the 128-bit integer to the integer type of the param.
+fn narrow<T, W>(ty: &str, value: W) -> Result<T, AbiError>
+where
+    T: TryFrom<W> + Bounded + Display,
+    W: Display + Copy,
+{
+    T::try_from(value).map_err(|_| out_of_range::<T>(ty, value))
+}
+
+#[cfg(test)]
+mod eth_token_test {
+    use super::*;
+
+    fn assert_round_trip(param: ABIParam, ty: ABIParamType) {
+        let token = Token::try_from(param.clone()).unwrap();
+        assert_eq!(ABIParam::from_eth_token(&token, &ty).unwrap(), param);
+    }
+
+    #[test]
+    fn test_eth_token_round_trip() {
+        assert_round_trip(ABIParam::U8(u8::MAX), ABIParamType::U8);
+        assert_round_trip(ABIParam::U16(u16::MAX), ABIParamType::U16);
+        assert_round_trip(ABIParam::U32(u32::MAX), ABIParamType::U32);
+        assert_round_trip(ABIParam::U64(u64::MAX), ABIParamType::U64);
+        assert_round_trip(ABIParam::U128(u128::MAX), ABIParamType::U128);
+        assert_round_trip(ABIParam::I8(i8::MIN), ABIParamType::I8);
+        assert_round_trip(ABIParam::I16(-1), ABIParamType::I16);
+        assert_round_trip(ABIParam::I32(i32::MAX), ABIParamType::I32);
+        assert_round_trip(ABIParam::I64(i64::MIN), ABIParamType::I64);
+        assert_round_trip(ABIParam::I128(i128::MIN), ABIParamType::I128);
+        assert_round_trip(ABIParam::I128(i128::MAX), ABIParamType::I128);
+        assert_round_trip(ABIParam::Bool(true), ABIParamType::Bool);
+        assert_round_trip(ABIParam::Str("alice".to_string()), ABIParamType::Str);
+        assert_round_trip(ABIParam::Timestamp(-1), ABIParamType::Timestamp);
+        assert_round_trip(ABIParam::Duration(3600), ABIParamType::Duration);
+        assert_round_trip(ABIParam::U8Array(vec![1, 2]), ABIParamType::U8Array);
+        assert_round_trip(ABIParam::I8Array(vec![-1, 2]), ABIParamType::I8Array);
+        assert_round_trip(ABIParam::U16Array(vec![]), ABIParamType::U16Array);
+        assert_round_trip(ABIParam::I16Array(vec![-3]), ABIParamType::I16Array);
+        assert_round_trip(ABIParam::U32Array(vec![4]), ABIParamType::U32Array);
+        assert_round_trip(ABIParam::I32Array(vec![-4]), ABIParamType::I32Array);
+        assert_round_trip(ABIParam::U64Array(vec![5]), ABIParamType::U64Array);
+        assert_round_trip(ABIParam::I64Array(vec![-5]), ABIParamType::I64Array);
+        assert_round_trip(ABIParam::U128Array(vec![6]), ABIParamType::U128Array);
+        assert_round_trip(ABIParam::I128Array(vec![-6]), ABIParamType::I128Array);
+        assert_round_trip(ABIParam::BoolArray(vec![true]), ABIParamType::BoolArray);
+        assert_round_trip(
+            ABIParam::StrArray(vec!["a".to_string()]),
+            ABIParamType::StrArray,
+        );
+        for (array, array_ty) in [
+            (ABIParam::U8Array(vec![1, 2]), ABIParamType::U8Array),
+            (ABIParam::U32Array(vec![3, 4]), ABIParamType::U32Array),
+        ] {
+            assert_round_trip(
+                ABIParam::fixed_array(array, 2).unwrap(),
+                ABIParamType::FixedArray(Box::new(array_ty), 2),
+            );
+        }
+        assert_round_trip(
+            ABIParam::address(vec![0xab; 20], Some(20)).unwrap(),
+            ABIParamType::Address(Some(20)),
+        );
+    }
+
+    #[test]
+    fn test_eth_token_conversion() {
+        assert_eq!(
+            Token::try_from(ABIParam::I8(-1)).unwrap(),
+            Token::Int(Int::MAX)
+        );
+        assert_eq!(
+            Token::try_from(ABIParam::U8Array(vec![1])).unwrap(),
+            Token::Bytes(vec![1])
+        );
+        assert_eq!(
+            ABIParam::try_from(Token::Uint(Uint::from(7))).unwrap(),
+            ABIParam::U128(7)
+        );
+        assert_eq!(
+            ABIParam::try_from(Token::Int(Int::MAX)).unwrap(),
+            ABIParam::I128(-1)
+        );
+        assert_eq!(
+            ABIParam::try_from(Token::FixedBytes(vec![1, 2])).unwrap(),
+            ABIParam::U8Array(vec![1, 2])
+        );
+        assert_eq!(
+            ABIParam::try_from(Token::Array(vec![Token::Bool(true)])).unwrap(),
+            ABIParam::BoolArray(vec![true])
+        );
+        assert_eq!(
+            ABIParam::from_eth_token(&Token::Uint(Uint::from(256)), &ABIParamType::U8).unwrap_err(),
+            AbiError::IntOutOfRange {
+                ty: "u8".to_string(),
+                value: "256".to_string(),
+                min: "0".to_string(),
+                max: "255".to_string(),
+            }
+        );
+        assert!(matches!(
+            ABIParam::from_eth_token(&Token::Int(Int::MAX << 200), &ABIParamType::I128)
+                .unwrap_err(),
+            AbiError::IntOutOfRange { .. }
+        ));
+        assert!(matches!(
+            ABIParam::from_eth_token(&Token::Bool(true), &ABIParamType::U8).unwrap_err(),
+            AbiError::EthToken { .. }
+        ));
+        for param in [
+            ABIParam::F64(1.0),
+            ABIParam::Parampack(vec![]),
+            ABIParam::StrU8Map(Default::default()),
+            ABIParam::Variant {
+                tag: 0,
+                value: Box::new(ABIParam::U8(1)),
+            },
+        ] {
+            assert!(matches!(
+                Token::try_from(param).unwrap_err(),
+                AbiError::EthToken { .. }
+            ));
+        }
+        assert!(ABIParam::try_from(Token::Array(vec![])).is_err());
+        assert!(ABIParam::try_from(Token::Tuple(vec![])).is_err());
+    }
+}
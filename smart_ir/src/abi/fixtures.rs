@@ -0,0 +1,198 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The golden vectors of the data stream encoding for the tests, so that the expected byte
+//! layouts are written down once instead of hand-computed in every test, and a layout
+//! change is caught here. The scalar bytes are literals, and the arrays, maps and composite
+//! params are laid out from the scalar ones by hand rather than by `as_bytes`.
+//!
+//! Enabled in the tests of this crate and by the `fixtures` feature for the dependents.
+
+use std::collections::BTreeMap;
+
+use crate::abi::params::ABIParam;
+use crate::abi::types::ABIParamType;
+
+/// The element count of the dynamic array samples.
+const SAMPLE_ARRAY_LEN: usize = 2;
+/// The key of the single entry of the map samples.
+const SAMPLE_MAP_KEY: &str = "k";
+
+macro_rules! sample_array {
+    ($elem_id:ident, $array_id:ident, $len:expr) => {{
+        let (elem, elem_bytes) = sample_param(&ABIParamType::$elem_id);
+        match elem {
+            ABIParam::$elem_id(elem) => (
+                ABIParam::$array_id(vec![elem; $len]),
+                elem_bytes.repeat($len),
+            ),
+            _ => unreachable!(
+                "the sample of {} is not {}",
+                stringify!($elem_id),
+                stringify!($elem_id)
+            ),
+        }
+    }};
+}
+
+macro_rules! sample_map {
+    ($value_id:ident, $map_id:ident) => {{
+        let (value, value_bytes) = sample_param(&ABIParamType::$value_id);
+        match value {
+            ABIParam::$value_id(value) => {
+                // The entry count, the key string and the value.
+                let mut bytes = vec![1, SAMPLE_MAP_KEY.len() as u8];
+                bytes.extend_from_slice(SAMPLE_MAP_KEY.as_bytes());
+                bytes.extend(value_bytes);
+                (
+                    ABIParam::$map_id(BTreeMap::from([(SAMPLE_MAP_KEY.to_string(), value)])),
+                    bytes,
+                )
+            }
+            _ => unreachable!(
+                "the sample of {} is not {}",
+                stringify!($value_id),
+                stringify!($value_id)
+            ),
+        }
+    }};
+}
+
+/// The elements of the array sample with `len` elements and their bytes without the
+/// length prefix, or `None` for the non-array types.
+fn sample_array_elements(array_ty: &ABIParamType, len: usize) -> Option<(ABIParam, Vec<u8>)> {
+    Some(match array_ty {
+        ABIParamType::U8Array => sample_array!(U8, U8Array, len),
+        ABIParamType::I8Array => sample_array!(I8, I8Array, len),
+        ABIParamType::U16Array => sample_array!(U16, U16Array, len),
+        ABIParamType::I16Array => sample_array!(I16, I16Array, len),
+        ABIParamType::U32Array => sample_array!(U32, U32Array, len),
+        ABIParamType::I32Array => sample_array!(I32, I32Array, len),
+        ABIParamType::U64Array => sample_array!(U64, U64Array, len),
+        ABIParamType::I64Array => sample_array!(I64, I64Array, len),
+        ABIParamType::U128Array => sample_array!(U128, U128Array, len),
+        ABIParamType::I128Array => sample_array!(I128, I128Array, len),
+        ABIParamType::BoolArray => sample_array!(Bool, BoolArray, len),
+        ABIParamType::StrArray => sample_array!(Str, StrArray, len),
+        ABIParamType::F64Array => sample_array!(F64, F64Array, len),
+        _ => return None,
+    })
+}
+
+/// A representative param of the type and its expected `as_bytes` encoding with the
+/// default options. The dynamic arrays have two elements, the maps have one entry, the
+/// variants select the last variant type, and the addresses are `0xab` bytes.
+///
+/// # Panics
+///
+/// Panics for the types which can't be encoded, i.e., the empty variant types and the
+/// fixed-size arrays of non-array types, and the type references without a types table.
+pub fn sample_param(ty: &ABIParamType) -> (ABIParam, Vec<u8>) {
+    match ty {
+        ABIParamType::U8 => (ABIParam::U8(0x12), vec![0x12]),
+        ABIParamType::I8 => (ABIParam::I8(-2), vec![0xfe]),
+        ABIParamType::U16 => (ABIParam::U16(0x1234), vec![0x34, 0x12]),
+        ABIParamType::I16 => (ABIParam::I16(-2), vec![0xfe, 0xff]),
+        ABIParamType::U32 => (ABIParam::U32(0x12345678), vec![0x78, 0x56, 0x34, 0x12]),
+        ABIParamType::I32 => (ABIParam::I32(-2), vec![0xfe, 0xff, 0xff, 0xff]),
+        ABIParamType::U64 => (
+            ABIParam::U64(0x0102030405060708),
+            vec![0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01],
+        ),
+        ABIParamType::I64 => (
+            ABIParam::I64(-2),
+            vec![0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        ),
+        ABIParamType::U128 => (
+            ABIParam::U128((1 << 64) | 2),
+            vec![2, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0],
+        ),
+        ABIParamType::I128 => {
+            let mut bytes = vec![0xff; 16];
+            bytes[0] = 0xfe;
+            (ABIParam::I128(-2), bytes)
+        }
+        ABIParamType::Bool => (ABIParam::Bool(true), vec![1]),
+        ABIParamType::Str => (ABIParam::Str("hi".to_string()), vec![2, b'h', b'i']),
+        ABIParamType::Parampack => (ABIParam::Parampack(vec![0xde, 0xad]), vec![2, 0xde, 0xad]),
+        ABIParamType::F32 => (ABIParam::F32(1.5), vec![0x00, 0x00, 0xc0, 0x3f]),
+        ABIParamType::F64 => (
+            ABIParam::F64(1.5),
+            vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf8, 0x3f],
+        ),
+        ABIParamType::U8Array
+        | ABIParamType::I8Array
+        | ABIParamType::U16Array
+        | ABIParamType::I16Array
+        | ABIParamType::U32Array
+        | ABIParamType::I32Array
+        | ABIParamType::U64Array
+        | ABIParamType::I64Array
+        | ABIParamType::U128Array
+        | ABIParamType::I128Array
+        | ABIParamType::BoolArray
+        | ABIParamType::StrArray
+        | ABIParamType::F64Array => {
+            let (param, elem_bytes) = sample_array_elements(ty, SAMPLE_ARRAY_LEN).unwrap();
+            let mut bytes = vec![SAMPLE_ARRAY_LEN as u8];
+            bytes.extend(elem_bytes);
+            (param, bytes)
+        }
+        ABIParamType::StrU8Map => sample_map!(U8, StrU8Map),
+        ABIParamType::StrI8Map => sample_map!(I8, StrI8Map),
+        ABIParamType::StrU16Map => sample_map!(U16, StrU16Map),
+        ABIParamType::StrI16Map => sample_map!(I16, StrI16Map),
+        ABIParamType::StrU32Map => sample_map!(U32, StrU32Map),
+        ABIParamType::StrI32Map => sample_map!(I32, StrI32Map),
+        ABIParamType::StrU64Map => sample_map!(U64, StrU64Map),
+        ABIParamType::StrI64Map => sample_map!(I64, StrI64Map),
+        ABIParamType::StrU128Map => sample_map!(U128, StrU128Map),
+        ABIParamType::StrI128Map => sample_map!(I128, StrI128Map),
+        ABIParamType::StrBoolMap => sample_map!(Bool, StrBoolMap),
+        ABIParamType::StrStrMap => sample_map!(Str, StrStrMap),
+        // 2023-11-14T22:13:20Z.
+        ABIParamType::Timestamp => (
+            ABIParam::Timestamp(1_700_000_000),
+            vec![0x00, 0xf1, 0x53, 0x65, 0x00, 0x00, 0x00, 0x00],
+        ),
+        ABIParamType::Duration => (ABIParam::Duration(60), vec![60, 0, 0, 0, 0, 0, 0, 0]),
+        ABIParamType::Bytes => (ABIParam::Bytes(vec![0xbe, 0xef]), vec![2, 0xbe, 0xef]),
+        ABIParamType::Variant(variant_tys) => {
+            let tag = variant_tys
+                .len()
+                .checked_sub(1)
+                .expect("no sample of the empty variant type") as u32;
+            let (value, value_bytes) = sample_param(&variant_tys[tag as usize]);
+            let mut bytes = tag.to_le_bytes().to_vec();
+            bytes.extend(value_bytes);
+            (
+                ABIParam::Variant {
+                    tag,
+                    value: Box::new(value),
+                },
+                bytes,
+            )
+        }
+        ABIParamType::FixedArray(array_ty, len) => {
+            let (array, bytes) = sample_array_elements(array_ty, *len)
+                .expect("no sample of the fixed-size array of non-array types");
+            (ABIParam::FixedArray(Box::new(array)), bytes)
+        }
+        ABIParamType::Address(Some(len)) => (
+            ABIParam::Address {
+                bytes: vec![0xab; *len],
+                len: Some(*len),
+            },
+            vec![0xab; *len],
+        ),
+        ABIParamType::Ref(id) => panic!("no sample of the type reference #{id}"),
+        ABIParamType::Address(None) => (
+            ABIParam::Address {
+                bytes: vec![0xab; 2],
+                len: None,
+            },
+            vec![2, 0xab, 0xab],
+        ),
+    }
+}
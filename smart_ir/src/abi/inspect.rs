@@ -0,0 +1,144 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The inspection of the self-describing bytes produced by
+//! `ABIParam::as_self_describing_bytes` without the method meta, which helps to
+//! reverse-engineer the call data in the debuggers.
+
+use num_traits::FromPrimitive;
+
+use crate::abi::params::{
+    decode_self_describing, read_uleb128_len_and_offset, SELF_DESCRIBING_VARIANT_TAG,
+};
+use crate::encoding::datastream::ParamType;
+
+/// A field identified in the self-describing bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InspectedSpan {
+    /// The type tag, which is the `ParamType` code or `SELF_DESCRIBING_VARIANT_TAG`.
+    pub type_tag: u8,
+    /// The offset of the field, which starts with the type tag.
+    pub offset: usize,
+    /// The length of the whole field, including the type tag and the length prefix.
+    pub len: usize,
+}
+
+impl InspectedSpan {
+    /// The param type of the type tag, or `None` for the variants.
+    pub fn param_type(&self) -> Option<ParamType> {
+        ParamType::from_u8(self.type_tag)
+    }
+
+    pub fn is_variant(&self) -> bool {
+        self.type_tag == SELF_DESCRIBING_VARIANT_TAG
+    }
+}
+
+/// Identify the fields of the concatenated self-describing bytes in order. The field must
+/// decode to be reported, and the inspection stops at the first region which can't be
+/// decoded, so the spans before it are still returned for the truncated or corrupt bytes,
+/// or the variants nested deeper than `MAX_TYPE_REF_DEPTH`.
+pub fn inspect(bytes: &[u8]) -> Vec<InspectedSpan> {
+    let mut spans = vec![];
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let len = match field_len(&bytes[offset..]) {
+            Some(len) => len,
+            None => break,
+        };
+        spans.push(InspectedSpan {
+            type_tag: bytes[offset],
+            offset,
+            len,
+        });
+        offset += len;
+    }
+    spans
+}
+
+/// The length of the first field if it decodes.
+fn field_len(bytes: &[u8]) -> Option<usize> {
+    // Skip the type tag and read the payload length.
+    let (payload_len, payload_offset) = read_uleb128_len_and_offset(bytes, &1).ok()?;
+    let len = payload_offset.checked_add(payload_len)?;
+    if len > bytes.len() {
+        return None;
+    }
+    decode_self_describing(&bytes[..len]).ok()?;
+    Some(len)
+}
+
+#[cfg(test)]
+mod inspect_test {
+    use super::{inspect, InspectedSpan};
+    use crate::abi::params::params_test::nested_variant_bytes;
+    use crate::abi::params::{ABIParam, MAX_TYPE_REF_DEPTH, SELF_DESCRIBING_VARIANT_TAG};
+    use crate::encoding::datastream::ParamType;
+
+    #[test]
+    fn test_inspect() {
+        let mut bytes = vec![];
+        for param in [
+            ABIParam::U32(7),
+            ABIParam::Str("hello".to_string()),
+            ABIParam::Variant {
+                tag: 1,
+                value: Box::new(ABIParam::U8Array(vec![1, 2])),
+            },
+            ABIParam::StrU8Map([("a".to_string(), 1)].into()),
+        ] {
+            bytes.append(&mut param.as_self_describing_bytes());
+        }
+        let valid_len = bytes.len();
+        // The i64 field declaring 5 bytes with only 1 byte remaining.
+        bytes.extend([ParamType::I64 as u8, 5, 1]);
+        let spans = inspect(&bytes);
+        let span = |type_tag: u8, offset: usize, len: usize| InspectedSpan {
+            type_tag,
+            offset,
+            len,
+        };
+        assert_eq!(
+            spans,
+            [
+                span(ParamType::U32 as u8, 0, 6),
+                span(ParamType::Str as u8, 6, 8),
+                span(SELF_DESCRIBING_VARIANT_TAG, 14, 11),
+                span(ParamType::StrU8Map as u8, 25, 6),
+            ]
+        );
+        assert_eq!(spans[3].offset + spans[3].len, valid_len);
+        assert_eq!(spans[0].param_type(), Some(ParamType::U32));
+        assert!(spans[2].is_variant() && spans[2].param_type().is_none());
+        // Stop at the unknown type tag and the undecodable payload.
+        assert_eq!(inspect(&[0xff, 0]), []);
+        assert_eq!(inspect(&[ParamType::Bool as u8, 2, 1, 1]), []);
+        assert_eq!(inspect(&[]), []);
+    }
+
+    #[test]
+    fn test_inspect_too_deep_variant() {
+        let mut bytes = ABIParam::U32(7).as_self_describing_bytes();
+        let deepest = nested_variant_bytes(MAX_TYPE_REF_DEPTH);
+        bytes.extend(&deepest);
+        bytes.extend(nested_variant_bytes(MAX_TYPE_REF_DEPTH + 1));
+        bytes.extend(ABIParam::U8(1).as_self_describing_bytes());
+        assert_eq!(
+            inspect(&bytes),
+            [
+                InspectedSpan {
+                    type_tag: ParamType::U32 as u8,
+                    offset: 0,
+                    len: 6,
+                },
+                InspectedSpan {
+                    type_tag: SELF_DESCRIBING_VARIANT_TAG,
+                    offset: 6,
+                    len: deepest.len(),
+                },
+            ]
+        );
+        assert_eq!(inspect(&nested_variant_bytes(10_000)), []);
+    }
+}
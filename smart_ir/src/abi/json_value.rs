@@ -0,0 +1,452 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversions between the abi params and the `serde_json` values for the generic tooling,
+//! which round-trip losslessly with the abi param type. The integers up to 64 bits are
+//! json numbers and the 128-bit ones are decimal strings so that no precision is lost,
+//! the non-finite floats are strings such as `NaN`, `parampack`, `bytes` and the addresses
+//! are `0x` prefixed hex strings, arrays including the fixed-size ones are json arrays,
+//! maps are json objects and variants are `{"tag": 1, "value": ...}` objects.
+
+use core::str::FromStr;
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Number, Value};
+
+use crate::abi::error::AbiError;
+use crate::abi::params::{decode_hex_str, encode_hex_str, ABIParam};
+use crate::abi::types::ABIParamType;
+
+/// The scalars of the array elements and map values.
+trait JsonScalar: Sized {
+    const TYPE_NAME: &'static str;
+
+    fn to_json(&self) -> Value;
+
+    fn from_json(value: &Value) -> Result<Self, AbiError>;
+}
+
+fn json_type_error(ty: &str, value: &Value) -> AbiError {
+    AbiError::JsonType {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    }
+}
+
+macro_rules! impl_json_number {
+    ($($ty:ty => $as_json:ident),*) => {
+        $(impl JsonScalar for $ty {
+            const TYPE_NAME: &'static str = stringify!($ty);
+
+            fn to_json(&self) -> Value {
+                Value::from(*self)
+            }
+
+            fn from_json(value: &Value) -> Result<Self, AbiError> {
+                let v = value
+                    .$as_json()
+                    .ok_or_else(|| json_type_error(Self::TYPE_NAME, value))?;
+                <$ty>::try_from(v).map_err(|_| AbiError::IntOutOfRange {
+                    ty: Self::TYPE_NAME.to_string(),
+                    value: v.to_string(),
+                    min: <$ty>::MIN.to_string(),
+                    max: <$ty>::MAX.to_string(),
+                })
+            }
+        })*
+    };
+}
+
+impl_json_number!(
+    u8 => as_u64, u16 => as_u64, u32 => as_u64, u64 => as_u64,
+    i8 => as_i64, i16 => as_i64, i32 => as_i64, i64 => as_i64
+);
+
+/// The 128-bit integers are decimal strings, the json numbers are accepted too.
+macro_rules! impl_json_wide_int {
+    ($($ty:ty),*) => {
+        $(impl JsonScalar for $ty {
+            const TYPE_NAME: &'static str = stringify!($ty);
+
+            fn to_json(&self) -> Value {
+                Value::String(self.to_string())
+            }
+
+            fn from_json(value: &Value) -> Result<Self, AbiError> {
+                let parse_int_err = || AbiError::ParseInt {
+                    ty: Self::TYPE_NAME.to_string(),
+                    value: value.to_string(),
+                };
+                match value {
+                    Value::String(s) => <$ty>::from_str(s).map_err(|_| parse_int_err()),
+                    Value::Number(n) => {
+                        <$ty>::from_str(&n.to_string()).map_err(|_| parse_int_err())
+                    }
+                    _ => Err(json_type_error(Self::TYPE_NAME, value)),
+                }
+            }
+        })*
+    };
+}
+
+impl_json_wide_int!(u128, i128);
+
+/// The finite floats are json numbers and the non-finite ones are strings such as `NaN`
+/// and `-inf`, which have no json number.
+macro_rules! impl_json_float {
+    ($($ty:ty),*) => {
+        $(impl JsonScalar for $ty {
+            const TYPE_NAME: &'static str = stringify!($ty);
+
+            fn to_json(&self) -> Value {
+                Number::from_f64(*self as f64)
+                    .map(Value::Number)
+                    .unwrap_or_else(|| Value::String(self.to_string()))
+            }
+
+            fn from_json(value: &Value) -> Result<Self, AbiError> {
+                match value {
+                    // The f32 values are exact in f64, so the narrowing is lossless.
+                    Value::Number(n) => n
+                        .as_f64()
+                        .map(|v| v as $ty)
+                        .ok_or_else(|| json_type_error(Self::TYPE_NAME, value)),
+                    Value::String(s) => <$ty>::from_str(s).map_err(|_| AbiError::ParseFloat {
+                        ty: Self::TYPE_NAME.to_string(),
+                        value: s.clone(),
+                    }),
+                    _ => Err(json_type_error(Self::TYPE_NAME, value)),
+                }
+            }
+        })*
+    };
+}
+
+impl_json_float!(f32, f64);
+
+impl JsonScalar for bool {
+    const TYPE_NAME: &'static str = "bool";
+
+    fn to_json(&self) -> Value {
+        Value::Bool(*self)
+    }
+
+    fn from_json(value: &Value) -> Result<Self, AbiError> {
+        value
+            .as_bool()
+            .ok_or_else(|| json_type_error(Self::TYPE_NAME, value))
+    }
+}
+
+impl JsonScalar for String {
+    const TYPE_NAME: &'static str = "string";
+
+    fn to_json(&self) -> Value {
+        Value::String(self.clone())
+    }
+
+    fn from_json(value: &Value) -> Result<Self, AbiError> {
+        value
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| json_type_error(Self::TYPE_NAME, value))
+    }
+}
+
+fn array_to_json<T: JsonScalar>(v: &[T]) -> Value {
+    Value::Array(v.iter().map(T::to_json).collect())
+}
+
+fn map_to_json<T: JsonScalar>(v: &BTreeMap<String, T>) -> Value {
+    Value::Object(
+        v.iter()
+            .map(|(key, value)| (key.clone(), value.to_json()))
+            .collect::<Map<String, Value>>(),
+    )
+}
+
+fn json_to_array<T: JsonScalar>(ty: &ABIParamType, value: &Value) -> Result<Vec<T>, AbiError> {
+    let items = value
+        .as_array()
+        .ok_or_else(|| json_type_error(&ty.canonical_string(), value))?;
+    let mut result = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let elem = T::from_json(item).map_err(|error| AbiError::ArrayElement {
+            ty: ty.canonical_string(),
+            index,
+            error: Box::new(error),
+        })?;
+        result.push(elem);
+    }
+    Ok(result)
+}
+
+fn json_to_map<T: JsonScalar>(
+    ty: &ABIParamType,
+    value: &Value,
+) -> Result<BTreeMap<String, T>, AbiError> {
+    value
+        .as_object()
+        .ok_or_else(|| json_type_error(&ty.canonical_string(), value))?
+        .iter()
+        .map(|(key, value)| Ok((key.clone(), T::from_json(value)?)))
+        .collect()
+}
+
+fn json_to_hex_bytes(value: &Value) -> Result<Vec<u8>, AbiError> {
+    match value {
+        Value::String(s) => decode_hex_str(s),
+        _ => Err(json_type_error("hex string", value)),
+    }
+}
+
+impl From<&ABIParam> for Value {
+    fn from(param: &ABIParam) -> Self {
+        match param {
+            ABIParam::U8(v) => v.to_json(),
+            ABIParam::I8(v) => v.to_json(),
+            ABIParam::U16(v) => v.to_json(),
+            ABIParam::I16(v) => v.to_json(),
+            ABIParam::U32(v) => v.to_json(),
+            ABIParam::I32(v) => v.to_json(),
+            ABIParam::U64(v) => v.to_json(),
+            ABIParam::I64(v) => v.to_json(),
+            ABIParam::U128(v) => v.to_json(),
+            ABIParam::I128(v) => v.to_json(),
+            ABIParam::Bool(v) => v.to_json(),
+            ABIParam::Str(v) => v.to_json(),
+            ABIParam::F32(v) => v.to_json(),
+            ABIParam::F64(v) => v.to_json(),
+            ABIParam::Parampack(v) | ABIParam::Bytes(v) => Value::String(encode_hex_str(v, true)),
+            ABIParam::U8Array(v) => array_to_json(v),
+            ABIParam::I8Array(v) => array_to_json(v),
+            ABIParam::U16Array(v) => array_to_json(v),
+            ABIParam::I16Array(v) => array_to_json(v),
+            ABIParam::U32Array(v) => array_to_json(v),
+            ABIParam::I32Array(v) => array_to_json(v),
+            ABIParam::U64Array(v) => array_to_json(v),
+            ABIParam::I64Array(v) => array_to_json(v),
+            ABIParam::U128Array(v) => array_to_json(v),
+            ABIParam::I128Array(v) => array_to_json(v),
+            ABIParam::BoolArray(v) => array_to_json(v),
+            ABIParam::StrArray(v) => array_to_json(v),
+            ABIParam::F64Array(v) => array_to_json(v),
+            ABIParam::StrU8Map(v) => map_to_json(v),
+            ABIParam::StrI8Map(v) => map_to_json(v),
+            ABIParam::StrU16Map(v) => map_to_json(v),
+            ABIParam::StrI16Map(v) => map_to_json(v),
+            ABIParam::StrU32Map(v) => map_to_json(v),
+            ABIParam::StrI32Map(v) => map_to_json(v),
+            ABIParam::StrU64Map(v) => map_to_json(v),
+            ABIParam::StrI64Map(v) => map_to_json(v),
+            ABIParam::StrU128Map(v) => map_to_json(v),
+            ABIParam::StrI128Map(v) => map_to_json(v),
+            ABIParam::StrBoolMap(v) => map_to_json(v),
+            ABIParam::StrStrMap(v) => map_to_json(v),
+            ABIParam::Timestamp(v) => v.to_json(),
+            ABIParam::Duration(v) => v.to_json(),
+            ABIParam::Variant { tag, value } => {
+                let mut obj = Map::new();
+                obj.insert("tag".to_string(), Value::from(*tag));
+                obj.insert("value".to_string(), Value::from(value.as_ref()));
+                Value::Object(obj)
+            }
+            ABIParam::FixedArray(array) => Value::from(array.as_ref()),
+            ABIParam::Address { bytes, .. } => Value::String(encode_hex_str(bytes, true)),
+        }
+    }
+}
+
+impl ABIParam {
+    /// Convert the json value produced by `Value::from(&ABIParam)` back to the param of
+    /// the abi param type, the integers out of the range of the type are rejected.
+    pub fn from_json_value(ty: ABIParamType, v: &Value) -> Result<ABIParam, AbiError> {
+        json_value_to_param(&ty, v)
+    }
+}
+
+fn json_value_to_param(ty: &ABIParamType, v: &Value) -> Result<ABIParam, AbiError> {
+    let param = match ty {
+        ABIParamType::U8 => ABIParam::U8(JsonScalar::from_json(v)?),
+        ABIParamType::I8 => ABIParam::I8(JsonScalar::from_json(v)?),
+        ABIParamType::U16 => ABIParam::U16(JsonScalar::from_json(v)?),
+        ABIParamType::I16 => ABIParam::I16(JsonScalar::from_json(v)?),
+        ABIParamType::U32 => ABIParam::U32(JsonScalar::from_json(v)?),
+        ABIParamType::I32 => ABIParam::I32(JsonScalar::from_json(v)?),
+        ABIParamType::U64 => ABIParam::U64(JsonScalar::from_json(v)?),
+        ABIParamType::I64 => ABIParam::I64(JsonScalar::from_json(v)?),
+        ABIParamType::U128 => ABIParam::U128(JsonScalar::from_json(v)?),
+        ABIParamType::I128 => ABIParam::I128(JsonScalar::from_json(v)?),
+        ABIParamType::Bool => ABIParam::Bool(JsonScalar::from_json(v)?),
+        ABIParamType::Str => ABIParam::Str(JsonScalar::from_json(v)?),
+        ABIParamType::F32 => ABIParam::F32(JsonScalar::from_json(v)?),
+        ABIParamType::F64 => ABIParam::F64(JsonScalar::from_json(v)?),
+        ABIParamType::Parampack => ABIParam::Parampack(json_to_hex_bytes(v)?),
+        ABIParamType::Bytes => ABIParam::Bytes(json_to_hex_bytes(v)?),
+        ABIParamType::U8Array => ABIParam::U8Array(json_to_array(ty, v)?),
+        ABIParamType::I8Array => ABIParam::I8Array(json_to_array(ty, v)?),
+        ABIParamType::U16Array => ABIParam::U16Array(json_to_array(ty, v)?),
+        ABIParamType::I16Array => ABIParam::I16Array(json_to_array(ty, v)?),
+        ABIParamType::U32Array => ABIParam::U32Array(json_to_array(ty, v)?),
+        ABIParamType::I32Array => ABIParam::I32Array(json_to_array(ty, v)?),
+        ABIParamType::U64Array => ABIParam::U64Array(json_to_array(ty, v)?),
+        ABIParamType::I64Array => ABIParam::I64Array(json_to_array(ty, v)?),
+        ABIParamType::U128Array => ABIParam::U128Array(json_to_array(ty, v)?),
+        ABIParamType::I128Array => ABIParam::I128Array(json_to_array(ty, v)?),
+        ABIParamType::BoolArray => ABIParam::BoolArray(json_to_array(ty, v)?),
+        ABIParamType::StrArray => ABIParam::StrArray(json_to_array(ty, v)?),
+        ABIParamType::F64Array => ABIParam::F64Array(json_to_array(ty, v)?),
+        ABIParamType::StrU8Map => ABIParam::StrU8Map(json_to_map(ty, v)?),
+        ABIParamType::StrI8Map => ABIParam::StrI8Map(json_to_map(ty, v)?),
+        ABIParamType::StrU16Map => ABIParam::StrU16Map(json_to_map(ty, v)?),
+        ABIParamType::StrI16Map => ABIParam::StrI16Map(json_to_map(ty, v)?),
+        ABIParamType::StrU32Map => ABIParam::StrU32Map(json_to_map(ty, v)?),
+        ABIParamType::StrI32Map => ABIParam::StrI32Map(json_to_map(ty, v)?),
+        ABIParamType::StrU64Map => ABIParam::StrU64Map(json_to_map(ty, v)?),
+        ABIParamType::StrI64Map => ABIParam::StrI64Map(json_to_map(ty, v)?),
+        ABIParamType::StrU128Map => ABIParam::StrU128Map(json_to_map(ty, v)?),
+        ABIParamType::StrI128Map => ABIParam::StrI128Map(json_to_map(ty, v)?),
+        ABIParamType::StrBoolMap => ABIParam::StrBoolMap(json_to_map(ty, v)?),
+        ABIParamType::StrStrMap => ABIParam::StrStrMap(json_to_map(ty, v)?),
+        ABIParamType::Timestamp => ABIParam::Timestamp(JsonScalar::from_json(v)?),
+        ABIParamType::Duration => ABIParam::Duration(JsonScalar::from_json(v)?),
+        ABIParamType::Variant(variant_tys) => {
+            let malformed = || AbiError::MalformedVariant(v.to_string());
+            let tag = v
+                .get("tag")
+                .and_then(Value::as_u64)
+                .and_then(|tag| u32::try_from(tag).ok())
+                .ok_or_else(malformed)?;
+            let value = v.get("value").ok_or_else(malformed)?;
+            let variant_ty = variant_tys
+                .get(tag as usize)
+                .ok_or(AbiError::InvalidVariantTag {
+                    tag,
+                    count: variant_tys.len(),
+                })?;
+            ABIParam::Variant {
+                tag,
+                value: Box::new(json_value_to_param(variant_ty, value)?),
+            }
+        }
+        ABIParamType::FixedArray(array_ty, len) => {
+            ABIParam::fixed_array(json_value_to_param(array_ty, v)?, *len)?
+        }
+        ABIParamType::Address(len) => ABIParam::address(json_to_hex_bytes(v)?, *len)?,
+        ABIParamType::Ref(id) => return Err(AbiError::UnresolvedTypeRef(*id)),
+    };
+    Ok(param)
+}
+
+#[cfg(test)]
+mod json_value_test {
+    use super::*;
+    use crate::abi::params::params_test::arb_abi_param;
+    use proptest::prelude::*;
+    use serde_json::json;
+
+    fn assert_round_trip(param: &ABIParam, ty: ABIParamType) {
+        let value = Value::from(param);
+        assert_eq!(&ABIParam::from_json_value(ty, &value).unwrap(), param);
+    }
+
+    proptest! {
+        #[test]
+        fn test_json_value_round_trip(param in arb_abi_param()) {
+            let ty = ABIParamType::from(param.to_param_type().unwrap());
+            let value = Value::from(&param);
+            // Through the json text too, which is where the precision would be lost.
+            let value: Value = serde_json::from_str(&value.to_string()).unwrap();
+            prop_assert_eq!(ABIParam::from_json_value(ty, &value).unwrap(), param);
+        }
+    }
+
+    #[test]
+    fn test_json_value_conversion() {
+        assert_eq!(Value::from(&ABIParam::U64(u64::MAX)), json!(u64::MAX));
+        assert_eq!(Value::from(&ABIParam::I64(i64::MIN)), json!(i64::MIN));
+        assert_eq!(
+            Value::from(&ABIParam::U128(u128::MAX)),
+            json!(u128::MAX.to_string())
+        );
+        assert_eq!(
+            Value::from(&ABIParam::I128Array(vec![-1, 2])),
+            json!(["-1", "2"])
+        );
+        assert_eq!(
+            Value::from(&ABIParam::Bytes(vec![0xde, 0xad])),
+            json!("0xdead")
+        );
+        assert_eq!(Value::from(&ABIParam::U8Array(vec![1, 2])), json!([1, 2]));
+        assert_eq!(
+            Value::from(&ABIParam::StrBoolMap([("on".to_string(), true)].into())),
+            json!({"on": true})
+        );
+        assert_eq!(Value::from(&ABIParam::F64(f64::NAN)), json!("NaN"));
+        assert!(matches!(
+            ABIParam::from_json_value(ABIParamType::F64, &json!("NaN")),
+            Ok(ABIParam::F64(v)) if v.is_nan()
+        ));
+        assert_round_trip(&ABIParam::F32(f32::NEG_INFINITY), ABIParamType::F32);
+        assert_round_trip(&ABIParam::Timestamp(-1), ABIParamType::Timestamp);
+        assert_round_trip(&ABIParam::Duration(3600), ABIParamType::Duration);
+        assert_round_trip(&ABIParam::Bytes(vec![]), ABIParamType::Bytes);
+        let variant_ty = ABIParamType::from_type_str("enum<u8,u128>").unwrap();
+        let variant = ABIParam::Variant {
+            tag: 1,
+            value: Box::new(ABIParam::U128(u128::MAX)),
+        };
+        assert_eq!(
+            Value::from(&variant),
+            json!({"tag": 1, "value": u128::MAX.to_string()})
+        );
+        assert_round_trip(&variant, variant_ty);
+        let fixed = ABIParam::fixed_array(ABIParam::U16Array(vec![1, 2]), 2).unwrap();
+        assert_eq!(Value::from(&fixed), json!([1, 2]));
+        assert_round_trip(&fixed, ABIParamType::from_type_str("[u16;2]").unwrap());
+        // The json numbers are accepted for the 128-bit integers too.
+        assert_eq!(
+            ABIParam::from_json_value(ABIParamType::U128, &json!(7)).unwrap(),
+            ABIParam::U128(7)
+        );
+    }
+
+    #[test]
+    fn test_json_value_errors() {
+        assert_eq!(
+            ABIParam::from_json_value(ABIParamType::U8, &json!(256)).unwrap_err(),
+            AbiError::IntOutOfRange {
+                ty: "u8".to_string(),
+                value: "256".to_string(),
+                min: "0".to_string(),
+                max: "255".to_string(),
+            }
+        );
+        assert_eq!(
+            ABIParam::from_json_value(ABIParamType::U8, &json!(-1)).unwrap_err(),
+            AbiError::JsonType {
+                ty: "u8".to_string(),
+                value: "-1".to_string(),
+            }
+        );
+        assert!(matches!(
+            ABIParam::from_json_value(ABIParamType::I128, &json!("1.5")).unwrap_err(),
+            AbiError::ParseInt { .. }
+        ));
+        assert!(matches!(
+            ABIParam::from_json_value(ABIParamType::U32Array, &json!([1, "a"])).unwrap_err(),
+            AbiError::ArrayElement { index: 1, .. }
+        ));
+        assert!(matches!(
+            ABIParam::from_json_value(ABIParamType::Bytes, &json!("0xabc")).unwrap_err(),
+            AbiError::BadHex { .. }
+        ));
+        assert!(ABIParam::from_json_value(ABIParamType::StrU8Map, &json!([])).is_err());
+        assert!(ABIParam::from_json_value(
+            ABIParamType::from_type_str("[u8;2]").unwrap(),
+            &json!([1])
+        )
+        .is_err());
+    }
+}
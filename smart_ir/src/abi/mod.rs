@@ -2,4 +2,16 @@
 // Copyright (c) The Smart Intermediate Representation Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod address;
+pub mod error;
+#[cfg(feature = "ethabi")]
+pub mod eth_token;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+pub mod inspect;
+#[cfg(feature = "serde_json")]
+pub mod json_value;
 pub mod params;
+pub mod path;
+pub mod types;
+pub mod visitor;
@@ -7,18 +7,32 @@ use anyhow::{anyhow, Result};
 use nano_leb128::ULEB128;
 #[allow(unused_imports)]
 use num_traits::FromPrimitive;
-use std::collections::HashMap;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
 
-use crate::encoding::datastream::ParamType;
+use crate::abi::address::AddressChecksum;
+use crate::abi::error::AbiError;
+use crate::abi::types::{ABIParamType, ABITypeTable};
+use crate::encoding::datastream::{ParamType, DEFAULT_VERSION};
 
 type Bytes = Vec<u8>;
 #[allow(unused)]
 const VERSION: u8 = 0;
 
+/// The readable form of the redacted params.
+pub const REDACTED_READABLE: &str = "***";
+
 /// Smart Intermediate Representation ABI params.
 ///
+/// The enum is `#[non_exhaustive]` because new param types are added in the minor
+/// releases, so the matches outside this crate need a wildcard arm. Use the accessors
+/// such as `as_integer`, `as_str` and `as_bytes_ref` to handle the common cases without
+/// the exhaustive matches, they keep working when the variants are added.
+///
 /// TODO: use macros and se/der traits and macros to impl.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
 pub enum ABIParam {
     U8(u8),
     I8(i8),
@@ -33,6 +47,8 @@ pub enum ABIParam {
     Bool(bool),
     Str(String),
     Parampack(Vec<u8>),
+    F32(f32),
+    F64(f64),
 
     // Array params
     U8Array(Vec<u8>),
@@ -47,59 +63,331 @@ pub enum ABIParam {
     I128Array(Vec<i128>),
     BoolArray(Vec<bool>),
     StrArray(Vec<String>),
+    F64Array(Vec<f64>),
+
+    // Map params, which are ordered by keys so that the same map is always
+    // encoded to the same bytes.
+    StrU8Map(BTreeMap<String, u8>),
+    StrI8Map(BTreeMap<String, i8>),
+    StrU16Map(BTreeMap<String, u16>),
+    StrI16Map(BTreeMap<String, i16>),
+    StrU32Map(BTreeMap<String, u32>),
+    StrI32Map(BTreeMap<String, i32>),
+    StrU64Map(BTreeMap<String, u64>),
+    StrI64Map(BTreeMap<String, i64>),
+    StrU128Map(BTreeMap<String, u128>),
+    StrI128Map(BTreeMap<String, i128>),
+    StrBoolMap(BTreeMap<String, bool>),
+    StrStrMap(BTreeMap<String, String>),
+
+    // Time params
+    /// The unix timestamp in seconds.
+    Timestamp(i64),
+    /// The duration in seconds.
+    Duration(u64),
+
+    // Bytes params
+    /// The arbitrary binary, which has the same layout as `Parampack` but not its meaning
+    /// of a packed parameter bundle.
+    Bytes(Vec<u8>),
+
+    // Composite params
+    /// The selected variant `tag` of an enum type such as `enum<u8,string,bool>`.
+    Variant {
+        tag: u32,
+        value: Box<ABIParam>,
+    },
+    /// The fixed-size array of the array param such as `U8Array`, the elements are encoded
+    /// without the length prefix because the element count is declared by the type.
+    FixedArray(Box<ABIParam>),
+    /// The address `bytes` of the `addressN` type with the expected length `len`, which
+    /// are encoded without the length prefix, or of the variable-length `address` type
+    /// with `None`, which are encoded as `Bytes`.
+    Address {
+        bytes: Vec<u8>,
+        len: Option<usize>,
+    },
+}
 
-    // Map params
-    StrU8Map(HashMap<String, u8>),
-    StrI8Map(HashMap<String, i8>),
-    StrU16Map(HashMap<String, u16>),
-    StrI16Map(HashMap<String, i16>),
-    StrU32Map(HashMap<String, u32>),
-    StrI32Map(HashMap<String, i32>),
-    StrU64Map(HashMap<String, u64>),
-    StrI64Map(HashMap<String, i64>),
-    StrU128Map(HashMap<String, u128>),
-    StrI128Map(HashMap<String, i128>),
-    StrBoolMap(HashMap<String, bool>),
-    StrStrMap(HashMap<String, String>),
+/// The byte order of the fixed-width integers, floats and variant tags in the data
+/// stream bytes. The default is `Little`, which is what `as_bytes` and `decode_param`
+/// use; the ULEB128 lengths are not affected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+/// The options of the data stream encoding, which must be the same for the encoder and
+/// the decoder. The default options produce the same bytes as `as_bytes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EncodingOptions {
+    /// The byte order of the fixed-width numbers.
+    pub endianness: Endianness,
+    /// Encode the integers, including the integer array elements, map values, timestamps
+    /// and durations, as LEB128 varints, unsigned integers with ULEB128 and signed
+    /// integers with SLEB128, so that the small values take fewer bytes. Floats and
+    /// variant tags are still fixed-width, and `encoded_len` is the fixed-width length.
+    pub varint: bool,
+    /// Pack the bool arrays, including the fixed-size ones, 8 bools per byte after the
+    /// length prefix, the first bool in the lowest bit and the unused high bits zero, so
+    /// that the large flag arrays take an eighth of the bytes. The default is one byte per
+    /// bool, and `encoded_len` is the unpacked length.
+    pub packed_bools: bool,
+}
+
+impl EncodingOptions {
+    /// The fixed-width options with the byte order.
+    pub fn with_endianness(endianness: Endianness) -> Self {
+        EncodingOptions {
+            endianness,
+            ..Default::default()
+        }
+    }
+
+    /// The varint options, the byte order only applies to the floats and variant tags.
+    pub fn varint() -> Self {
+        EncodingOptions {
+            varint: true,
+            ..Default::default()
+        }
+    }
+
+    /// The options packing the bool arrays.
+    pub fn packed_bools() -> Self {
+        EncodingOptions {
+            packed_bools: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// The limits of decoding the untrusted data stream bytes, so that the adversarial call
+/// data can't make the decoders allocate or recurse without bound. Exceeding any limit is
+/// reported as `AbiError::LimitExceeded`. The default limits are unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// The max length of the whole input bytes.
+    pub max_bytes: usize,
+    /// The max element count of every array and map.
+    pub max_elements: usize,
+    /// The max nesting depth of the arrays, maps and variants, e.g., `u8` has the depth 0,
+    /// `[u8]` has the depth 1 and `enum<[u8]>` has the depth 2.
+    pub max_depth: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            max_bytes: usize::MAX,
+            max_elements: usize::MAX,
+            max_depth: usize::MAX,
+        }
+    }
+}
+
+impl DecodeLimits {
+    /// Check the length of the whole input bytes.
+    pub fn check_bytes(&self, len: usize) -> Result<(), AbiError> {
+        check_limit("max_bytes", len, self.max_bytes)
+    }
+}
+
+fn check_limit(limit: &str, actual: usize, max: usize) -> Result<(), AbiError> {
+    if actual > max {
+        return Err(AbiError::LimitExceeded {
+            limit: limit.to_string(),
+            actual,
+            max,
+        });
+    }
+    Ok(())
+}
+
+/// The max nesting depth of resolving the type references such as `#0` in decoding, which
+/// bounds the recursive types such as `enum<u64,#0>` even with the default unlimited
+/// `DecodeLimits`, so that the crafted bytes can't overflow the stack.
+pub const MAX_TYPE_REF_DEPTH: usize = 128;
+
+/// The state threaded through the recursive decoders.
+#[derive(Debug, Clone, Copy)]
+struct DecodeContext<'a> {
+    options: EncodingOptions,
+    limits: DecodeLimits,
+    depth: usize,
+    /// The types table resolving the type references.
+    types: Option<&'a ABITypeTable>,
+}
+
+impl<'a> DecodeContext<'a> {
+    fn new(options: EncodingOptions, limits: DecodeLimits) -> Self {
+        DecodeContext {
+            options,
+            limits,
+            depth: 0,
+            types: None,
+        }
+    }
+
+    /// The context of the params nested in an array, map or variant.
+    fn nested(self) -> Result<Self, AbiError> {
+        let depth = self.depth + 1;
+        check_limit("max_depth", depth, self.limits.max_depth)?;
+        Ok(DecodeContext { depth, ..self })
+    }
+
+    /// The context of the elements of an array or map with `len` elements.
+    fn elements(self, len: usize) -> Result<Self, AbiError> {
+        check_limit("max_elements", len, self.limits.max_elements)?;
+        self.nested()
+    }
+}
+
+macro_rules! num_bytes {
+    ($v:expr, $endianness:expr) => {
+        match $endianness {
+            Endianness::Little => $v.to_le_bytes().to_vec(),
+            Endianness::Big => $v.to_be_bytes().to_vec(),
+        }
+    };
+}
+
+macro_rules! uint_bytes {
+    ($v:expr, $options:expr) => {
+        if $options.varint {
+            uleb128_bytes($v as u128)
+        } else {
+            num_bytes!($v, $options.endianness)
+        }
+    };
+}
+
+macro_rules! int_bytes {
+    ($v:expr, $options:expr) => {
+        if $options.varint {
+            sleb128_bytes($v as i128)
+        } else {
+            num_bytes!($v, $options.endianness)
+        }
+    };
 }
 
 macro_rules! encode_vec {
-    ($v:expr, $id:ident) => {{
+    ($v:expr, $id:ident, $options:expr) => {{
         let mut buf = buffer_starts_with_uleb128_len($v.len());
         for elem_v in $v {
-            buf.append(&mut ABIParam::$id(elem_v.clone()).as_bytes());
+            buf.append(&mut ABIParam::$id(elem_v.clone()).as_bytes_with_options($options));
         }
         buf
     }};
 }
 
 macro_rules! encode_map {
-    ($v:expr, $key_id:ident, $val_id:ident) => {{
+    ($v:expr, $key_id:ident, $val_id:ident, $options:expr) => {{
         let mut buf = buffer_starts_with_uleb128_len($v.len());
         for (str_k, elem_v) in $v {
-            buf.append(&mut ABIParam::$key_id(str_k.clone()).as_bytes());
-            buf.append(&mut ABIParam::$val_id(elem_v.clone()).as_bytes());
+            buf.append(&mut ABIParam::$key_id(str_k.clone()).as_bytes_with_options($options));
+            buf.append(&mut ABIParam::$val_id(elem_v.clone()).as_bytes_with_options($options));
         }
         buf
     }};
 }
 
-macro_rules! decode_int {
-    ($data:expr, $offset:expr, $id:ident, $ty_id:ident, $size:expr) => {{
+macro_rules! readable_vec {
+    ($v:expr, $id:ident) => {{
+        let elems: Vec<String> = $v
+            .iter()
+            .map(|elem_v| ABIParam::$id(elem_v.clone()).to_readable())
+            .collect();
+        format!("[{}]", elems.join(", "))
+    }};
+}
+
+macro_rules! merge_map {
+    ($left:expr, $right:expr, $($id:ident),+) => {
+        match ($left, $right) {
+            $((ABIParam::$id(mut left), ABIParam::$id(right)) => {
+                for (str_k, elem_v) in right {
+                    match left.entry(str_k) {
+                        Entry::Occupied(entry) => {
+                            return Err(AbiError::DuplicateMapKey(entry.key().clone()))
+                        }
+                        Entry::Vacant(entry) => {
+                            entry.insert(elem_v);
+                        }
+                    }
+                }
+                Ok(ABIParam::$id(left))
+            })+
+            (left, right) => Err(AbiError::MapTypeMismatch {
+                left: left.type_name(),
+                right: right.type_name(),
+            }),
+        }
+    };
+}
+
+macro_rules! readable_map {
+    ($v:expr, $val_id:ident) => {{
+        let entries: Vec<String> = $v
+            .iter()
+            .map(|(str_k, elem_v)| {
+                format!(
+                    "{str_k:?}: {}",
+                    ABIParam::$val_id(elem_v.clone()).to_readable()
+                )
+            })
+            .collect();
+        format!("{{{}}}", entries.join(", "))
+    }};
+}
+
+macro_rules! decode_num {
+    ($data:expr, $offset:expr, $id:ident, $ty_id:ident, $size:expr, $endianness:expr) => {{
         const SIZE: usize = $size;
+        ensure_remaining($data, $offset, SIZE, stringify!($ty_id))?;
         let bytes = get_bytes::<SIZE>($data, $offset);
-        let param = ABIParam::$id($ty_id::from_le_bytes(bytes));
+        let param = ABIParam::$id(match $endianness {
+            Endianness::Little => $ty_id::from_le_bytes(bytes),
+            Endianness::Big => $ty_id::from_be_bytes(bytes),
+        });
         *$offset += SIZE;
         Ok(param)
     }};
 }
 
+macro_rules! decode_int {
+    ($data:expr, $offset:expr, $id:ident, $ty_id:ident, $size:expr, $options:expr, $read_varint:ident) => {{
+        if $options.varint {
+            let v = $read_varint($data, $offset, stringify!($ty_id))?;
+            match $ty_id::try_from(v) {
+                Ok(v) => Ok(ABIParam::$id(v)),
+                Err(_) => Err(anyhow!(
+                    "decode {} error: varint {} is out of range",
+                    stringify!($ty_id),
+                    v
+                )),
+            }
+        } else {
+            decode_num!($data, $offset, $id, $ty_id, $size, $options.endianness)
+        }
+    }};
+}
+
 macro_rules! decode_vec {
-    ($data:expr, $offset:expr, $id:ident, $id_arr:ident) => {{
-        let mut result = vec![];
-        let (len, mut elem_offset) = read_uleb128_len_and_offset($data, $offset)?;
+    ($data:expr, $offset:expr, $id:ident, $id_arr:ident, $ctx:expr, $fixed_len:expr) => {{
+        let (len, mut elem_offset) = match $fixed_len {
+            Some(len) => (len, *$offset),
+            None => read_uleb128_len_and_offset($data, $offset)?,
+        };
+        let ctx = $ctx.elements(len)?;
+        // Every element takes at least one byte, reject the length which can't fit, so
+        // that the untrusted length is bounded by the input bytes before reserving.
+        ensure_remaining($data, &elem_offset, len, concat!(stringify!($id), " array"))?;
+        let mut result = Vec::with_capacity(len);
         for _ in 0..len {
-            let param = decode_param(&ParamType::$id, $data, &mut elem_offset)?;
+            let param = decode_param_in(&ParamType::$id, $data, &mut elem_offset, ctx)?;
             if let ABIParam::$id(v) = param {
                 result.push(v);
             } else {
@@ -112,11 +400,14 @@ macro_rules! decode_vec {
 }
 
 macro_rules! decode_map {
-    ($data:expr, $offset:expr, $key_id:ident, $val_id:ident, $id_arr:ident) => {{
-        let mut result = HashMap::new();
+    ($data:expr, $offset:expr, $key_id:ident, $val_id:ident, $id_arr:ident, $ctx:expr) => {{
+        let mut result = BTreeMap::new();
         let (len, mut elem_offset) = read_uleb128_len_and_offset($data, $offset)?;
+        let ctx = $ctx.elements(len)?;
+        // Every entry takes at least one byte, reject the length which can't fit.
+        ensure_remaining($data, &elem_offset, len, "map")?;
         for _ in 0..len {
-            let param_key = decode_param(&ParamType::$key_id, $data, &mut elem_offset)?;
+            let param_key = decode_param_in(&ParamType::$key_id, $data, &mut elem_offset, ctx)?;
             let key = if let ABIParam::$key_id(v) = param_key {
                 v
             } else {
@@ -126,7 +417,7 @@ macro_rules! decode_map {
                     stringify!($val_id)
                 ));
             };
-            let param_val = decode_param(&ParamType::$val_id, $data, &mut elem_offset)?;
+            let param_val = decode_param_in(&ParamType::$val_id, $data, &mut elem_offset, ctx)?;
             let val = if let ABIParam::$val_id(v) = param_val {
                 v
             } else {
@@ -146,57 +437,452 @@ macro_rules! decode_map {
 impl ABIParam {
     /// Cast contract ABI parameter to data stream bytes.
     pub fn as_bytes(&self) -> Vec<u8> {
+        self.as_bytes_with_endianness(Endianness::Little)
+    }
+
+    /// Cast contract ABI parameter to data stream bytes with the byte order of the
+    /// fixed-width numbers, which must match the decoder.
+    pub fn as_bytes_with_endianness(&self, endianness: Endianness) -> Vec<u8> {
+        self.as_bytes_with_options(EncodingOptions::with_endianness(endianness))
+    }
+
+    /// Cast contract ABI parameter to data stream bytes with the encoding options, which
+    /// must match the decoder.
+    pub fn as_bytes_with_options(&self, options: EncodingOptions) -> Vec<u8> {
         match self {
-            ABIParam::U8(v) => (*v).to_le_bytes().to_vec(),
-            ABIParam::I8(v) => (*v).to_le_bytes().to_vec(),
-            ABIParam::U16(v) => (*v).to_le_bytes().to_vec(),
-            ABIParam::I16(v) => (*v).to_le_bytes().to_vec(),
-            ABIParam::U32(v) => (*v).to_le_bytes().to_vec(),
-            ABIParam::I32(v) => (*v).to_le_bytes().to_vec(),
-            ABIParam::U64(v) => (*v).to_le_bytes().to_vec(),
-            ABIParam::I64(v) => (*v).to_le_bytes().to_vec(),
-            ABIParam::U128(v) => (*v).to_le_bytes().to_vec(),
-            ABIParam::I128(v) => (*v).to_le_bytes().to_vec(),
+            ABIParam::U8(v) => uint_bytes!(*v, options),
+            ABIParam::I8(v) => int_bytes!(*v, options),
+            ABIParam::U16(v) => uint_bytes!(*v, options),
+            ABIParam::I16(v) => int_bytes!(*v, options),
+            ABIParam::U32(v) => uint_bytes!(*v, options),
+            ABIParam::I32(v) => int_bytes!(*v, options),
+            ABIParam::U64(v) => uint_bytes!(*v, options),
+            ABIParam::I64(v) => int_bytes!(*v, options),
+            ABIParam::U128(v) => uint_bytes!(*v, options),
+            ABIParam::I128(v) => int_bytes!(*v, options),
+            ABIParam::F32(v) => num_bytes!(*v, options.endianness),
+            ABIParam::F64(v) => num_bytes!(*v, options.endianness),
             ABIParam::Bool(v) => vec![(*v) as u8],
             ABIParam::Str(v) => {
                 let mut buf = buffer_starts_with_uleb128_len(v.len());
                 buf.append(&mut v.clone().as_bytes().to_vec());
                 buf
             }
-            ABIParam::U8Array(v) => encode_vec!(v, U8),
-            ABIParam::I8Array(v) => encode_vec!(v, I8),
-            ABIParam::U16Array(v) => encode_vec!(v, U16),
-            ABIParam::I16Array(v) => encode_vec!(v, I16),
-            ABIParam::U32Array(v) => encode_vec!(v, U32),
-            ABIParam::I32Array(v) => encode_vec!(v, I32),
-            ABIParam::U64Array(v) => encode_vec!(v, U64),
-            ABIParam::I64Array(v) => encode_vec!(v, I64),
-            ABIParam::U128Array(v) => encode_vec!(v, U128),
-            ABIParam::I128Array(v) => encode_vec!(v, I128),
-            ABIParam::BoolArray(v) => encode_vec!(v, Bool),
-            ABIParam::StrArray(v) => encode_vec!(v, Str),
-            ABIParam::StrU8Map(v) => encode_map!(v, Str, U8),
-            ABIParam::StrI8Map(v) => encode_map!(v, Str, I8),
-            ABIParam::StrU16Map(v) => encode_map!(v, Str, U16),
-            ABIParam::StrI16Map(v) => encode_map!(v, Str, I16),
-            ABIParam::StrU32Map(v) => encode_map!(v, Str, U32),
-            ABIParam::StrI32Map(v) => encode_map!(v, Str, I32),
-            ABIParam::StrU64Map(v) => encode_map!(v, Str, U64),
-            ABIParam::StrI64Map(v) => encode_map!(v, Str, I64),
-            ABIParam::StrU128Map(v) => encode_map!(v, Str, U128),
-            ABIParam::StrI128Map(v) => encode_map!(v, Str, I128),
-            ABIParam::StrBoolMap(v) => encode_map!(v, Str, Bool),
-            ABIParam::StrStrMap(v) => encode_map!(v, Str, Str),
-            ABIParam::Parampack(v) => {
+            ABIParam::U8Array(v) => encode_vec!(v, U8, options),
+            ABIParam::I8Array(v) => encode_vec!(v, I8, options),
+            ABIParam::U16Array(v) => encode_vec!(v, U16, options),
+            ABIParam::I16Array(v) => encode_vec!(v, I16, options),
+            ABIParam::U32Array(v) => encode_vec!(v, U32, options),
+            ABIParam::I32Array(v) => encode_vec!(v, I32, options),
+            ABIParam::U64Array(v) => encode_vec!(v, U64, options),
+            ABIParam::I64Array(v) => encode_vec!(v, I64, options),
+            ABIParam::U128Array(v) => encode_vec!(v, U128, options),
+            ABIParam::I128Array(v) => encode_vec!(v, I128, options),
+            ABIParam::BoolArray(v) if options.packed_bools => {
+                let mut buf = buffer_starts_with_uleb128_len(v.len());
+                buf.append(&mut pack_bools(v));
+                buf
+            }
+            ABIParam::BoolArray(v) => encode_vec!(v, Bool, options),
+            ABIParam::StrArray(v) => encode_vec!(v, Str, options),
+            ABIParam::F64Array(v) => encode_vec!(v, F64, options),
+            ABIParam::StrU8Map(v) => encode_map!(v, Str, U8, options),
+            ABIParam::StrI8Map(v) => encode_map!(v, Str, I8, options),
+            ABIParam::StrU16Map(v) => encode_map!(v, Str, U16, options),
+            ABIParam::StrI16Map(v) => encode_map!(v, Str, I16, options),
+            ABIParam::StrU32Map(v) => encode_map!(v, Str, U32, options),
+            ABIParam::StrI32Map(v) => encode_map!(v, Str, I32, options),
+            ABIParam::StrU64Map(v) => encode_map!(v, Str, U64, options),
+            ABIParam::StrI64Map(v) => encode_map!(v, Str, I64, options),
+            ABIParam::StrU128Map(v) => encode_map!(v, Str, U128, options),
+            ABIParam::StrI128Map(v) => encode_map!(v, Str, I128, options),
+            ABIParam::StrBoolMap(v) => encode_map!(v, Str, Bool, options),
+            ABIParam::StrStrMap(v) => encode_map!(v, Str, Str, options),
+            ABIParam::Parampack(v) | ABIParam::Bytes(v) => {
                 let mut buf = buffer_starts_with_uleb128_len(v.len());
                 buf.append(&mut v.clone());
                 buf
             }
+            ABIParam::Timestamp(v) => int_bytes!(*v, options),
+            ABIParam::Duration(v) => uint_bytes!(*v, options),
+            ABIParam::Variant { tag, value } => {
+                let mut buf = num_bytes!(*tag, options.endianness);
+                buf.append(&mut value.as_bytes_with_options(options));
+                buf
+            }
+            ABIParam::FixedArray(array) => {
+                let buf = array.as_bytes_with_options(options);
+                buf[array.array_len().map_or(0, uleb128_encoded_len)..].to_vec()
+            }
+            ABIParam::Address {
+                bytes,
+                len: Some(_),
+            } => bytes.clone(),
+            ABIParam::Address { bytes, len: None } => {
+                let mut buf = buffer_starts_with_uleb128_len(bytes.len());
+                buf.append(&mut bytes.clone());
+                buf
+            }
+        }
+    }
+
+    /// Build the address param of the `addressN` type with the expected length `len`, or
+    /// of the variable-length `address` type with `None`.
+    pub fn address(bytes: Vec<u8>, len: Option<usize>) -> Result<ABIParam, AbiError> {
+        match len {
+            Some(len) if len != bytes.len() => Err(AbiError::AddressLength {
+                expected: len,
+                got: bytes.len(),
+            }),
+            _ => Ok(ABIParam::Address { bytes, len }),
+        }
+    }
+
+    /// Build the address param from the hex string like `address`, the hex string is
+    /// validated by the `checksum` scheme, e.g., `AddressChecksum::Eip55` of the 20-byte
+    /// addresses.
+    pub fn address_from_hex(
+        hex_str: &str,
+        len: Option<usize>,
+        checksum: AddressChecksum,
+    ) -> Result<ABIParam, AbiError> {
+        ABIParam::address(checksum.decode(hex_str)?, len)
+    }
+
+    /// Build the fixed-size array param of the dynamic array param such as `U8Array`, the
+    /// element count must be `len`.
+    pub fn fixed_array(array: ABIParam, len: usize) -> Result<ABIParam, AbiError> {
+        if matches!(array, ABIParam::FixedArray(_)) {
+            return Err(AbiError::UnsupportedType(
+                "fixed-size array of fixed-size arrays".to_string(),
+            ));
         }
+        match array.array_len() {
+            Some(got) if got == len => Ok(ABIParam::FixedArray(Box::new(array))),
+            Some(got) => Err(AbiError::FixedArrayLength { expected: len, got }),
+            None => Err(AbiError::UnsupportedType(
+                "fixed-size array of non-array params".to_string(),
+            )),
+        }
+    }
+
+    /// Merge the entries of the `other` map param into this map param of the same type such
+    /// as `StrU64Map`, e.g., to build a `{str:u64}` argument from several sources instead of
+    /// joining the `k:v` strings. The keys in both maps are rejected rather than overwritten.
+    pub fn merge_map(self, other: ABIParam) -> Result<ABIParam, AbiError> {
+        merge_map!(
+            self, other, StrU8Map, StrI8Map, StrU16Map, StrI16Map, StrU32Map, StrI32Map, StrU64Map,
+            StrI64Map, StrU128Map, StrI128Map, StrBoolMap, StrStrMap
+        )
     }
 
-    pub fn to_param_type(&self) -> ParamType {
+    /// The abi type name of the params with a `ParamType` code for the errors.
+    fn type_name(&self) -> String {
+        match self.to_param_type() {
+            Some(ty) => ABIParamType::from(ty).canonical_string(),
+            None => "composite param".to_string(),
+        }
+    }
+
+    /// The element count of the array params, including the fixed-size arrays, or `None`
+    /// for the other params.
+    pub fn array_len(&self) -> Option<usize> {
+        Some(match self {
+            ABIParam::U8Array(v) => v.len(),
+            ABIParam::I8Array(v) => v.len(),
+            ABIParam::U16Array(v) => v.len(),
+            ABIParam::I16Array(v) => v.len(),
+            ABIParam::U32Array(v) => v.len(),
+            ABIParam::I32Array(v) => v.len(),
+            ABIParam::U64Array(v) => v.len(),
+            ABIParam::I64Array(v) => v.len(),
+            ABIParam::U128Array(v) => v.len(),
+            ABIParam::I128Array(v) => v.len(),
+            ABIParam::BoolArray(v) => v.len(),
+            ABIParam::StrArray(v) => v.len(),
+            ABIParam::F64Array(v) => v.len(),
+            ABIParam::FixedArray(array) => return array.array_len(),
+            _ => return None,
+        })
+    }
+
+    /// The value of the integer params widened to `i128`, or `None` for the other params
+    /// and the `u128` values above `i128::MAX`. The timestamps and durations are not
+    /// integer params here.
+    pub fn as_integer(&self) -> Option<i128> {
+        Some(match self {
+            ABIParam::U8(v) => *v as i128,
+            ABIParam::I8(v) => *v as i128,
+            ABIParam::U16(v) => *v as i128,
+            ABIParam::I16(v) => *v as i128,
+            ABIParam::U32(v) => *v as i128,
+            ABIParam::I32(v) => *v as i128,
+            ABIParam::U64(v) => *v as i128,
+            ABIParam::I64(v) => *v as i128,
+            ABIParam::U128(v) => return i128::try_from(*v).ok(),
+            ABIParam::I128(v) => *v,
+            _ => return None,
+        })
+    }
+
+    /// The value of the float params widened to `f64`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ABIParam::F32(v) => Some(*v as f64),
+            ABIParam::F64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ABIParam::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ABIParam::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The raw bytes of the `parampack`, `bytes` and `[u8]` params including the fixed-size
+    /// `[u8;N]` ones, which are borrowed rather than encoded as `as_bytes` does.
+    pub fn as_bytes_ref(&self) -> Option<&[u8]> {
+        match self {
+            ABIParam::Parampack(v) | ABIParam::Bytes(v) | ABIParam::U8Array(v) => Some(v),
+            ABIParam::FixedArray(array) => array.as_bytes_ref(),
+            ABIParam::Address { bytes, .. } => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// The selected tag and value of the variant params.
+    pub fn as_variant(&self) -> Option<(u32, &ABIParam)> {
+        match self {
+            ABIParam::Variant { tag, value } => Some((*tag, value)),
+            _ => None,
+        }
+    }
+
+    /// Build the `parampack` param from the hex string, accepting the same forms as
+    /// `decode_hex_str`, e.g. `0xdeadbeef` and `DEADBEEF`.
+    pub fn parampack_from_hex(hex_str: &str) -> Result<ABIParam, AbiError> {
+        Ok(ABIParam::Parampack(decode_hex_str(hex_str)?))
+    }
+
+    /// Build the `bytes` param from the hex string, accepting the same forms as
+    /// `parampack_from_hex`.
+    pub fn bytes_from_hex(hex_str: &str) -> Result<ABIParam, AbiError> {
+        Ok(ABIParam::Bytes(decode_hex_str(hex_str)?))
+    }
+
+    /// The canonical hex string of the `as_bytes` encoding, lowercase without the `0x`
+    /// prefix, use `encode_hex_str` to get the prefixed form.
+    pub fn to_hex(&self) -> String {
+        encode_hex_str(&self.as_bytes(), false)
+    }
+
+    /// Render the param in one line for the cli output and logs, which is the typed
+    /// analog of the `readable` field of the constant meta, e.g., `42`, `"alice"`,
+    /// `0xdead`, `[1, 2]` and `{"a": true}`. Variants are rendered as `tag:value` and
+    /// durations with the `s` suffix.
+    pub fn to_readable(&self) -> String {
+        match self {
+            ABIParam::U8(v) => v.to_string(),
+            ABIParam::I8(v) => v.to_string(),
+            ABIParam::U16(v) => v.to_string(),
+            ABIParam::I16(v) => v.to_string(),
+            ABIParam::U32(v) => v.to_string(),
+            ABIParam::I32(v) => v.to_string(),
+            ABIParam::U64(v) => v.to_string(),
+            ABIParam::I64(v) => v.to_string(),
+            ABIParam::U128(v) => v.to_string(),
+            ABIParam::I128(v) => v.to_string(),
+            ABIParam::F32(v) => v.to_string(),
+            ABIParam::F64(v) => v.to_string(),
+            ABIParam::Bool(v) => v.to_string(),
+            ABIParam::Str(v) => format!("{v:?}"),
+            ABIParam::U8Array(v) => readable_vec!(v, U8),
+            ABIParam::I8Array(v) => readable_vec!(v, I8),
+            ABIParam::U16Array(v) => readable_vec!(v, U16),
+            ABIParam::I16Array(v) => readable_vec!(v, I16),
+            ABIParam::U32Array(v) => readable_vec!(v, U32),
+            ABIParam::I32Array(v) => readable_vec!(v, I32),
+            ABIParam::U64Array(v) => readable_vec!(v, U64),
+            ABIParam::I64Array(v) => readable_vec!(v, I64),
+            ABIParam::U128Array(v) => readable_vec!(v, U128),
+            ABIParam::I128Array(v) => readable_vec!(v, I128),
+            ABIParam::BoolArray(v) => readable_vec!(v, Bool),
+            ABIParam::StrArray(v) => readable_vec!(v, Str),
+            ABIParam::F64Array(v) => readable_vec!(v, F64),
+            ABIParam::StrU8Map(v) => readable_map!(v, U8),
+            ABIParam::StrI8Map(v) => readable_map!(v, I8),
+            ABIParam::StrU16Map(v) => readable_map!(v, U16),
+            ABIParam::StrI16Map(v) => readable_map!(v, I16),
+            ABIParam::StrU32Map(v) => readable_map!(v, U32),
+            ABIParam::StrI32Map(v) => readable_map!(v, I32),
+            ABIParam::StrU64Map(v) => readable_map!(v, U64),
+            ABIParam::StrI64Map(v) => readable_map!(v, I64),
+            ABIParam::StrU128Map(v) => readable_map!(v, U128),
+            ABIParam::StrI128Map(v) => readable_map!(v, I128),
+            ABIParam::StrBoolMap(v) => readable_map!(v, Bool),
+            ABIParam::StrStrMap(v) => readable_map!(v, Str),
+            ABIParam::Parampack(v) | ABIParam::Bytes(v) => encode_hex_str(v, true),
+            ABIParam::Timestamp(v) => v.to_string(),
+            ABIParam::Duration(v) => format!("{v}s"),
+            ABIParam::Variant { tag, value } => format!("{tag}:{}", value.to_readable()),
+            ABIParam::FixedArray(array) => array.to_readable(),
+            ABIParam::Address { bytes, .. } => encode_hex_str(bytes, true),
+        }
+    }
+
+    /// Render the param like `to_readable`, or as `REDACTED_READABLE` when its input `name`
+    /// is in `redact`, so that the sensitive params can be masked in the audit logs. Only
+    /// the named top-level params are redacted, the nested elements and variant values have
+    /// no names and are rendered along with their param.
+    pub fn to_readable_redacted(&self, name: &str, redact: &HashSet<String>) -> String {
+        if redact.contains(name) {
+            REDACTED_READABLE.to_string()
+        } else {
+            self.to_readable()
+        }
+    }
+
+    /// The exact length of the `as_bytes` encoding, computed without encoding the param,
+    /// so that the encode buffer can be allocated once up front.
+    pub fn encoded_len(&self) -> usize {
         match self {
+            ABIParam::U8(_) => 1,
+            ABIParam::I8(_) => 1,
+            ABIParam::U16(_) => 2,
+            ABIParam::I16(_) => 2,
+            ABIParam::U32(_) => 4,
+            ABIParam::I32(_) => 4,
+            ABIParam::U64(_) => 8,
+            ABIParam::I64(_) => 8,
+            ABIParam::U128(_) => 16,
+            ABIParam::I128(_) => 16,
+            ABIParam::F32(_) => 4,
+            ABIParam::F64(_) => 8,
+            ABIParam::Bool(_) => 1,
+            ABIParam::Str(v) => str_encoded_len(v),
+            ABIParam::U8Array(v) => uleb128_encoded_len(v.len()) + v.len(),
+            ABIParam::I8Array(v) => uleb128_encoded_len(v.len()) + v.len(),
+            ABIParam::U16Array(v) => uleb128_encoded_len(v.len()) + v.len() * 2,
+            ABIParam::I16Array(v) => uleb128_encoded_len(v.len()) + v.len() * 2,
+            ABIParam::U32Array(v) => uleb128_encoded_len(v.len()) + v.len() * 4,
+            ABIParam::I32Array(v) => uleb128_encoded_len(v.len()) + v.len() * 4,
+            ABIParam::U64Array(v) => uleb128_encoded_len(v.len()) + v.len() * 8,
+            ABIParam::I64Array(v) => uleb128_encoded_len(v.len()) + v.len() * 8,
+            ABIParam::U128Array(v) => uleb128_encoded_len(v.len()) + v.len() * 16,
+            ABIParam::I128Array(v) => uleb128_encoded_len(v.len()) + v.len() * 16,
+            ABIParam::BoolArray(v) => uleb128_encoded_len(v.len()) + v.len(),
+            ABIParam::StrArray(v) => {
+                uleb128_encoded_len(v.len()) + v.iter().map(|s| str_encoded_len(s)).sum::<usize>()
+            }
+            ABIParam::F64Array(v) => uleb128_encoded_len(v.len()) + v.len() * 8,
+            ABIParam::StrU8Map(v) => fixed_map_encoded_len(v, 1),
+            ABIParam::StrI8Map(v) => fixed_map_encoded_len(v, 1),
+            ABIParam::StrU16Map(v) => fixed_map_encoded_len(v, 2),
+            ABIParam::StrI16Map(v) => fixed_map_encoded_len(v, 2),
+            ABIParam::StrU32Map(v) => fixed_map_encoded_len(v, 4),
+            ABIParam::StrI32Map(v) => fixed_map_encoded_len(v, 4),
+            ABIParam::StrU64Map(v) => fixed_map_encoded_len(v, 8),
+            ABIParam::StrI64Map(v) => fixed_map_encoded_len(v, 8),
+            ABIParam::StrU128Map(v) => fixed_map_encoded_len(v, 16),
+            ABIParam::StrI128Map(v) => fixed_map_encoded_len(v, 16),
+            ABIParam::StrBoolMap(v) => fixed_map_encoded_len(v, 1),
+            ABIParam::StrStrMap(v) => {
+                uleb128_encoded_len(v.len())
+                    + v.iter()
+                        .map(|(k, v)| str_encoded_len(k) + str_encoded_len(v))
+                        .sum::<usize>()
+            }
+            ABIParam::Parampack(v) | ABIParam::Bytes(v) => uleb128_encoded_len(v.len()) + v.len(),
+            ABIParam::Timestamp(_) => 8,
+            ABIParam::Duration(_) => 8,
+            ABIParam::Variant { value, .. } => 4 + value.encoded_len(),
+            ABIParam::FixedArray(array) => {
+                array.encoded_len() - array.array_len().map_or(0, uleb128_encoded_len)
+            }
+            ABIParam::Address {
+                bytes,
+                len: Some(_),
+            } => bytes.len(),
+            ABIParam::Address { bytes, len: None } => {
+                uleb128_encoded_len(bytes.len()) + bytes.len()
+            }
+        }
+    }
+
+    /// Decode a single param of the type `ty` at the start of `bytes`, returns the param
+    /// and the number of bytes consumed, so that incremental parsers can continue with
+    /// the remaining bytes.
+    pub fn decode(ty: ABIParamType, bytes: &[u8]) -> Result<(ABIParam, usize), String> {
+        ABIParam::decode_with_endianness(ty, bytes, Endianness::Little)
+    }
+
+    /// The same as `decode`, but with the byte order used by the encoder.
+    pub fn decode_with_endianness(
+        ty: ABIParamType,
+        bytes: &[u8],
+        endianness: Endianness,
+    ) -> Result<(ABIParam, usize), String> {
+        ABIParam::decode_with_options(ty, bytes, EncodingOptions::with_endianness(endianness))
+    }
+
+    /// The same as `decode`, but with the encoding options used by the encoder.
+    pub fn decode_with_options(
+        ty: ABIParamType,
+        bytes: &[u8],
+        options: EncodingOptions,
+    ) -> Result<(ABIParam, usize), String> {
+        let mut offset = 0;
+        let param = decode_abi_param_with_options(&ty, bytes, &mut offset, options)
+            .map_err(|err| err.to_string())?;
+        Ok((param, offset))
+    }
+
+    /// Cast the param to the self-describing bytes `[type tag][uleb128 length][payload]`,
+    /// where the type tag is the `ParamType` code and the payload is `as_bytes`, so that
+    /// a standalone param blob can be decoded without the method meta. It is intended for
+    /// logging and debugging tools, the compact `as_bytes` layout is still the default.
+    /// Variants use the `SELF_DESCRIBING_VARIANT_TAG` type tag and the payload is the
+    /// variant tag followed by the self-describing bytes of the value. Fixed-size arrays
+    /// are framed as their dynamic arrays because the element count is not in the bytes,
+    /// and the addresses are framed as bytes for the same reason.
+    pub fn as_self_describing_bytes(&self) -> Vec<u8> {
+        let (type_tag, mut payload) = match self {
+            ABIParam::FixedArray(array) => return array.as_self_describing_bytes(),
+            ABIParam::Address { bytes, .. } => {
+                return ABIParam::Bytes(bytes.clone()).as_self_describing_bytes()
+            }
+            ABIParam::Variant { tag, value } => {
+                let mut payload = tag.to_le_bytes().to_vec();
+                payload.append(&mut value.as_self_describing_bytes());
+                (SELF_DESCRIBING_VARIANT_TAG, payload)
+            }
+            _ => match self.to_param_type() {
+                Some(param_ty) => (u8::from(param_ty), self.as_bytes()),
+                None => unreachable!("param {self:?} has no param type code"),
+            },
+        };
+        let mut buf = vec![type_tag];
+        buf.append(&mut buffer_starts_with_uleb128_len(payload.len()));
+        buf.append(&mut payload);
+        buf
+    }
+
+    /// The `ParamType` code of the param, time params use the codes of the underlying
+    /// integers and bytes use the code of parampack with the same layout, so they are
+    /// decoded as integers and parampacks from the self-describing bytes.
+    /// Composite params such as variants and fixed-size arrays and the addresses have no
+    /// code and return `None`.
+    pub fn to_param_type(&self) -> Option<ParamType> {
+        Some(match self {
             ABIParam::U8(_) => ParamType::U8,
             ABIParam::I8(_) => ParamType::I8,
             ABIParam::U16(_) => ParamType::U16,
@@ -210,6 +896,8 @@ impl ABIParam {
             ABIParam::Bool(_) => ParamType::Bool,
             ABIParam::Str(_) => ParamType::Str,
             ABIParam::Parampack(_) => ParamType::Parampack,
+            ABIParam::F32(_) => ParamType::F32,
+            ABIParam::F64(_) => ParamType::F64,
             ABIParam::U8Array(_) => ParamType::U8Array,
             ABIParam::I8Array(_) => ParamType::I8Array,
             ABIParam::U16Array(_) => ParamType::U16Array,
@@ -222,6 +910,7 @@ impl ABIParam {
             ABIParam::I128Array(_) => ParamType::I128Array,
             ABIParam::BoolArray(_) => ParamType::BoolArray,
             ABIParam::StrArray(_) => ParamType::StrArray,
+            ABIParam::F64Array(_) => ParamType::F64Array,
             ABIParam::StrU8Map(_) => ParamType::StrU8Map,
             ABIParam::StrI8Map(_) => ParamType::StrI8Map,
             ABIParam::StrU16Map(_) => ParamType::StrU16Map,
@@ -234,7 +923,13 @@ impl ABIParam {
             ABIParam::StrI128Map(_) => ParamType::StrI128Map,
             ABIParam::StrBoolMap(_) => ParamType::StrBoolMap,
             ABIParam::StrStrMap(_) => ParamType::StrStrMap,
-        }
+            ABIParam::Timestamp(_) => ParamType::I64,
+            ABIParam::Duration(_) => ParamType::U64,
+            ABIParam::Bytes(_) => ParamType::Parampack,
+            ABIParam::Variant { .. } | ABIParam::FixedArray(_) | ABIParam::Address { .. } => {
+                return None
+            }
+        })
     }
 }
 
@@ -252,6 +947,7 @@ pub fn decode(types: &[ParamType], data: &[u8]) -> anyhow::Result<Vec<ABIParam>>
     if data.len() <= 1 && !types.is_empty() {
         return Err(anyhow!("please ensure the contract and method you're calling exist! failed to decode empty bytes."));
     }
+    check_data_stream_version(data)?;
     let mut params = vec![];
     // Skip the first byte version.
     let mut offset = 1;
@@ -264,59 +960,488 @@ pub fn decode(types: &[ParamType], data: &[u8]) -> anyhow::Result<Vec<ABIParam>>
     Ok(params)
 }
 
+/// The leading version byte of the encoded params, which is the version of the data
+/// stream wire format. It is separate from the `abi_version` of the abi json meta and the
+/// contract, and only changes when the byte layout of the params changes, so that the
+/// bytes encoded by any abi meta version are decoded in the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EncodingVersion(pub u8);
+
+impl EncodingVersion {
+    /// The version written by the encoders and accepted by the decoders, which is the
+    /// `DEFAULT_VERSION` byte also written by the codegen.
+    pub const CURRENT: EncodingVersion = EncodingVersion(DEFAULT_VERSION);
+
+    pub fn as_u8(self) -> u8 {
+        self.0
+    }
+}
+
+/// Peek the leading version byte of the encoded params without decoding them. The
+/// versions other than `EncodingVersion::CURRENT` are rejected.
+pub fn decode_version(bytes: &[u8]) -> Result<EncodingVersion, AbiError> {
+    match bytes.first() {
+        Some(version) => {
+            check_data_stream_version(bytes)?;
+            Ok(EncodingVersion(*version))
+        }
+        None => Err(AbiError::Decode {
+            ty: "version".to_string(),
+            reason: "failed to decode empty bytes".to_string(),
+        }),
+    }
+}
+
+/// Check the leading version byte of the data stream if any, which must be
+/// `EncodingVersion::CURRENT` written by `encode_params`, the missing byte is left to the
+/// callers.
+pub fn check_data_stream_version(data: &[u8]) -> Result<(), AbiError> {
+    match data.first() {
+        Some(version) if *version != EncodingVersion::CURRENT.as_u8() => {
+            Err(AbiError::UnsupportedVersion(*version))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The self-describing type tag of the variant params, which have no `ParamType` code.
+pub const SELF_DESCRIBING_VARIANT_TAG: u8 = 0x80;
+
+/// Decodes the concatenated self-describing bytes produced by `ABIParam::as_self_describing_bytes`.
+/// The nested variants are bounded by `MAX_TYPE_REF_DEPTH`, so that the crafted bytes can't
+/// overflow the stack.
+pub fn decode_self_describing(data: &[u8]) -> anyhow::Result<Vec<ABIParam>> {
+    let mut params = vec![];
+    let mut offset = 0;
+    while offset < data.len() {
+        params.push(decode_self_describing_param(data, &mut offset, 0)?);
+    }
+    Ok(params)
+}
+
+fn decode_self_describing_param(
+    data: &[u8],
+    offset: &mut usize,
+    depth: usize,
+) -> anyhow::Result<ABIParam> {
+    ensure_remaining(data, offset, 1, "type tag")?;
+    let type_tag = data[*offset];
+    let (len, payload_offset) = read_uleb128_len_and_offset(data, &(*offset + 1))?;
+    if len > data.len() - payload_offset {
+        return Err(anyhow!(
+            "param with type tag {} at offset {} declares {} bytes, but only {} bytes remain",
+            type_tag,
+            offset,
+            len,
+            data.len() - payload_offset
+        ));
+    }
+    let payload = &data[payload_offset..payload_offset + len];
+    let mut consumed = 0;
+    let param = if type_tag == SELF_DESCRIBING_VARIANT_TAG {
+        ensure_remaining(payload, &consumed, 4, "variant tag")?;
+        let tag = u32::from_le_bytes(get_bytes::<4>(payload, &consumed));
+        consumed += 4;
+        check_limit("max_depth", depth + 1, MAX_TYPE_REF_DEPTH)?;
+        let value = decode_self_describing_param(payload, &mut consumed, depth + 1)?;
+        ABIParam::Variant {
+            tag,
+            value: Box::new(value),
+        }
+    } else {
+        let param_ty = match ParamType::from_u8(type_tag) {
+            Some(param_ty) => param_ty,
+            None => {
+                return Err(anyhow!(
+                    "unknown param type tag {} at offset {}",
+                    type_tag,
+                    offset
+                ))
+            }
+        };
+        decode_param(&param_ty, payload, &mut consumed)?
+    };
+    if consumed != len {
+        return Err(anyhow!(
+            "param with type tag {} at offset {} has {} unused bytes",
+            type_tag,
+            offset,
+            len - consumed
+        ));
+    }
+    *offset = payload_offset + len;
+    Ok(param)
+}
+
+/// Decode the param of the abi param type, which supports the time types and the
+/// composite types without `ParamType` codes such as variants.
+pub fn decode_abi_param(
+    param_ty: &ABIParamType,
+    data: &[u8],
+    offset: &mut usize,
+) -> anyhow::Result<ABIParam> {
+    decode_abi_param_with_endianness(param_ty, data, offset, Endianness::Little)
+}
+
+/// The same as `decode_abi_param`, but with the byte order used by the encoder.
+pub fn decode_abi_param_with_endianness(
+    param_ty: &ABIParamType,
+    data: &[u8],
+    offset: &mut usize,
+    endianness: Endianness,
+) -> anyhow::Result<ABIParam> {
+    decode_abi_param_with_options(
+        param_ty,
+        data,
+        offset,
+        EncodingOptions::with_endianness(endianness),
+    )
+}
+
+/// The same as `decode_abi_param`, but with the encoding options used by the encoder.
+pub fn decode_abi_param_with_options(
+    param_ty: &ABIParamType,
+    data: &[u8],
+    offset: &mut usize,
+    options: EncodingOptions,
+) -> anyhow::Result<ABIParam> {
+    decode_abi_param_with_limits(param_ty, data, offset, options, DecodeLimits::default())
+}
+
+/// The same as `decode_abi_param_with_options`, but the untrusted bytes are decoded within
+/// the limits, the `AbiError::LimitExceeded` error can be downcast from the result error.
+pub fn decode_abi_param_with_limits(
+    param_ty: &ABIParamType,
+    data: &[u8],
+    offset: &mut usize,
+    options: EncodingOptions,
+    limits: DecodeLimits,
+) -> anyhow::Result<ABIParam> {
+    limits.check_bytes(data.len())?;
+    decode_abi_param_in(param_ty, data, offset, DecodeContext::new(options, limits))
+}
+
+/// The same as `decode_abi_param_with_limits`, but the type references such as `#0` are
+/// resolved by the types table, e.g., the recursive types. The references are resolved up
+/// to the nesting depth `MAX_TYPE_REF_DEPTH` or the `max_depth` limit if it is smaller,
+/// deeper values are rejected with `AbiError::LimitExceeded`.
+pub fn decode_abi_param_with_types(
+    param_ty: &ABIParamType,
+    data: &[u8],
+    offset: &mut usize,
+    options: EncodingOptions,
+    limits: DecodeLimits,
+    types: &ABITypeTable,
+) -> anyhow::Result<ABIParam> {
+    limits.check_bytes(data.len())?;
+    let ctx = DecodeContext {
+        types: Some(types),
+        ..DecodeContext::new(options, limits)
+    };
+    decode_abi_param_in(param_ty, data, offset, ctx)
+}
+
+fn decode_abi_param_in(
+    param_ty: &ABIParamType,
+    data: &[u8],
+    offset: &mut usize,
+    ctx: DecodeContext<'_>,
+) -> anyhow::Result<ABIParam> {
+    let options = ctx.options;
+    match param_ty {
+        ABIParamType::Variant(variant_tys) => {
+            ensure_remaining(data, offset, 4, "variant tag")?;
+            let tag_bytes = get_bytes::<4>(data, offset);
+            let tag = match options.endianness {
+                Endianness::Little => u32::from_le_bytes(tag_bytes),
+                Endianness::Big => u32::from_be_bytes(tag_bytes),
+            };
+            let variant_ty = match variant_tys.get(tag as usize) {
+                Some(variant_ty) => variant_ty,
+                None => {
+                    return Err(anyhow!(
+                        "variant tag {} is out of range, expect less than {}",
+                        tag,
+                        variant_tys.len()
+                    ))
+                }
+            };
+            *offset += 4;
+            let value = decode_abi_param_in(variant_ty, data, offset, ctx.nested()?)?;
+            Ok(ABIParam::Variant {
+                tag,
+                value: Box::new(value),
+            })
+        }
+        ABIParamType::FixedArray(array_ty, len) => {
+            let array = match array_ty.to_param_type() {
+                Some(array_param_ty) => {
+                    decode_array_in(&array_param_ty, data, offset, ctx, Some(*len))?
+                }
+                None => return Err(anyhow!("decode {:?} is not supported", param_ty)),
+            };
+            Ok(ABIParam::FixedArray(Box::new(array)))
+        }
+        ABIParamType::Address(Some(len)) => {
+            ensure_remaining(data, offset, *len, "address")?;
+            let bytes = data[*offset..*offset + len].to_vec();
+            *offset += len;
+            Ok(ABIParam::Address {
+                bytes,
+                len: Some(*len),
+            })
+        }
+        ABIParamType::Address(None) => {
+            match decode_param_in(&ParamType::Parampack, data, offset, ctx)? {
+                ABIParam::Parampack(bytes) => Ok(ABIParam::Address { bytes, len: None }),
+                param => unreachable!("decode parampack but got {param:?}"),
+            }
+        }
+        ABIParamType::Ref(id) => {
+            let types = ctx.types.ok_or(AbiError::UnresolvedTypeRef(*id))?;
+            let max_depth = ctx.limits.max_depth.min(MAX_TYPE_REF_DEPTH);
+            check_limit("max_depth", ctx.depth, max_depth)?;
+            decode_abi_param_in(types.resolve(param_ty)?, data, offset, ctx)
+        }
+        ABIParamType::Timestamp => match decode_param_in(&ParamType::I64, data, offset, ctx)? {
+            ABIParam::I64(v) => Ok(ABIParam::Timestamp(v)),
+            param => unreachable!("decode i64 but got {param:?}"),
+        },
+        ABIParamType::Duration => match decode_param_in(&ParamType::U64, data, offset, ctx)? {
+            ABIParam::U64(v) => Ok(ABIParam::Duration(v)),
+            param => unreachable!("decode u64 but got {param:?}"),
+        },
+        ABIParamType::Bytes => match decode_param_in(&ParamType::Parampack, data, offset, ctx)? {
+            ABIParam::Parampack(v) => Ok(ABIParam::Bytes(v)),
+            param => unreachable!("decode parampack but got {param:?}"),
+        },
+        _ => match param_ty.to_param_type() {
+            Some(param_ty) => decode_param_in(&param_ty, data, offset, ctx),
+            None => Err(anyhow!("decode {:?} is not supported", param_ty)),
+        },
+    }
+}
+
 pub fn decode_param(
     param_ty: &ParamType,
     data: &[u8],
     offset: &mut usize,
 ) -> anyhow::Result<ABIParam> {
+    decode_param_with_endianness(param_ty, data, offset, Endianness::Little)
+}
+
+/// The same as `decode_param`, but with the byte order used by the encoder.
+pub fn decode_param_with_endianness(
+    param_ty: &ParamType,
+    data: &[u8],
+    offset: &mut usize,
+    endianness: Endianness,
+) -> anyhow::Result<ABIParam> {
+    decode_param_with_options(
+        param_ty,
+        data,
+        offset,
+        EncodingOptions::with_endianness(endianness),
+    )
+}
+
+/// The same as `decode_param`, but with the encoding options used by the encoder.
+pub fn decode_param_with_options(
+    param_ty: &ParamType,
+    data: &[u8],
+    offset: &mut usize,
+    options: EncodingOptions,
+) -> anyhow::Result<ABIParam> {
+    decode_param_in(
+        param_ty,
+        data,
+        offset,
+        DecodeContext::new(options, DecodeLimits::default()),
+    )
+}
+
+fn decode_param_in(
+    param_ty: &ParamType,
+    data: &[u8],
+    offset: &mut usize,
+    ctx: DecodeContext<'_>,
+) -> anyhow::Result<ABIParam> {
+    let options = ctx.options;
     match param_ty {
-        ParamType::U8 => decode_int!(data, offset, U8, u8, 1),
-        ParamType::I8 => decode_int!(data, offset, I8, i8, 1),
-        ParamType::U16 => decode_int!(data, offset, U16, u16, 2),
-        ParamType::I16 => decode_int!(data, offset, I16, i16, 2),
-        ParamType::U32 => decode_int!(data, offset, U32, u32, 4),
-        ParamType::I32 => decode_int!(data, offset, I32, i32, 4),
-        ParamType::U64 => decode_int!(data, offset, U64, u64, 8),
-        ParamType::I64 => decode_int!(data, offset, I64, i64, 8),
-        ParamType::U128 => decode_int!(data, offset, U128, u128, 16),
-        ParamType::I128 => decode_int!(data, offset, I128, i128, 16),
+        ParamType::U8 => decode_int!(data, offset, U8, u8, 1, options, read_uleb128),
+        ParamType::I8 => decode_int!(data, offset, I8, i8, 1, options, read_sleb128),
+        ParamType::U16 => decode_int!(data, offset, U16, u16, 2, options, read_uleb128),
+        ParamType::I16 => decode_int!(data, offset, I16, i16, 2, options, read_sleb128),
+        ParamType::U32 => decode_int!(data, offset, U32, u32, 4, options, read_uleb128),
+        ParamType::I32 => decode_int!(data, offset, I32, i32, 4, options, read_sleb128),
+        ParamType::U64 => decode_int!(data, offset, U64, u64, 8, options, read_uleb128),
+        ParamType::I64 => decode_int!(data, offset, I64, i64, 8, options, read_sleb128),
+        ParamType::U128 => decode_int!(data, offset, U128, u128, 16, options, read_uleb128),
+        ParamType::I128 => decode_int!(data, offset, I128, i128, 16, options, read_sleb128),
+        ParamType::F32 => decode_num!(data, offset, F32, f32, 4, options.endianness),
+        ParamType::F64 => decode_num!(data, offset, F64, f64, 8, options.endianness),
         ParamType::Bool => {
+            ensure_remaining(data, offset, 1, "bool")?;
             let param = ABIParam::Bool(data[*offset] != 0);
             *offset += 1;
             Ok(param)
         }
         ParamType::Str => {
             let (len, total_offset) = read_uleb128_len_and_offset(data, offset)?;
+            ensure_remaining(data, &total_offset, len, "string")?;
+            let bytes = &data[total_offset..total_offset + len];
+            // Validate the untrusted bytes and report where the invalid sequence starts.
+            let s = core::str::from_utf8(bytes).map_err(|err| AbiError::InvalidUtf8 {
+                offset: total_offset + err.valid_up_to(),
+            })?;
+            *offset = total_offset + len;
+            Ok(ABIParam::Str(s.to_string()))
+        }
+        ParamType::U8Array
+        | ParamType::I8Array
+        | ParamType::U16Array
+        | ParamType::I16Array
+        | ParamType::U32Array
+        | ParamType::I32Array
+        | ParamType::U64Array
+        | ParamType::I64Array
+        | ParamType::U128Array
+        | ParamType::I128Array
+        | ParamType::BoolArray
+        | ParamType::StrArray
+        | ParamType::F64Array => decode_array_in(param_ty, data, offset, ctx, None),
+        ParamType::StrU8Map => decode_map!(data, offset, Str, U8, StrU8Map, ctx),
+        ParamType::StrI8Map => decode_map!(data, offset, Str, I8, StrI8Map, ctx),
+        ParamType::StrU16Map => decode_map!(data, offset, Str, U16, StrU16Map, ctx),
+        ParamType::StrI16Map => decode_map!(data, offset, Str, I16, StrI16Map, ctx),
+        ParamType::StrU32Map => decode_map!(data, offset, Str, U32, StrU32Map, ctx),
+        ParamType::StrI32Map => decode_map!(data, offset, Str, I32, StrI32Map, ctx),
+        ParamType::StrU64Map => decode_map!(data, offset, Str, U64, StrU64Map, ctx),
+        ParamType::StrI64Map => decode_map!(data, offset, Str, I64, StrI64Map, ctx),
+        ParamType::StrU128Map => decode_map!(data, offset, Str, U128, StrU128Map, ctx),
+        ParamType::StrI128Map => decode_map!(data, offset, Str, I128, StrI128Map, ctx),
+        ParamType::StrBoolMap => decode_map!(data, offset, Str, Bool, StrBoolMap, ctx),
+        ParamType::StrStrMap => decode_map!(data, offset, Str, Str, StrStrMap, ctx),
+        ParamType::Parampack => {
+            let (len, total_offset) = read_uleb128_len_and_offset(data, offset)?;
+            ensure_remaining(data, &total_offset, len, "parampack")?;
             let bytes = &data[total_offset..total_offset + len];
             *offset = total_offset + len;
-            Ok(ABIParam::Str(String::from_utf8(bytes.to_vec())?))
-        }
-        ParamType::U8Array => decode_vec!(data, offset, U8, U8Array),
-        ParamType::I8Array => decode_vec!(data, offset, I8, I8Array),
-        ParamType::U16Array => decode_vec!(data, offset, U16, U16Array),
-        ParamType::I16Array => decode_vec!(data, offset, I16, I16Array),
-        ParamType::U32Array => decode_vec!(data, offset, U32, U32Array),
-        ParamType::I32Array => decode_vec!(data, offset, I32, I32Array),
-        ParamType::U64Array => decode_vec!(data, offset, U64, U64Array),
-        ParamType::I64Array => decode_vec!(data, offset, I64, I64Array),
-        ParamType::U128Array => decode_vec!(data, offset, U128, U128Array),
-        ParamType::I128Array => decode_vec!(data, offset, I128, I128Array),
-        ParamType::BoolArray => decode_vec!(data, offset, Bool, BoolArray),
-        ParamType::StrArray => decode_vec!(data, offset, Str, StrArray),
-        ParamType::StrU8Map => decode_map!(data, offset, Str, U8, StrU8Map),
-        ParamType::StrI8Map => decode_map!(data, offset, Str, I8, StrI8Map),
-        ParamType::StrU16Map => decode_map!(data, offset, Str, U16, StrU16Map),
-        ParamType::StrI16Map => decode_map!(data, offset, Str, I16, StrI16Map),
-        ParamType::StrU32Map => decode_map!(data, offset, Str, U32, StrU32Map),
-        ParamType::StrI32Map => decode_map!(data, offset, Str, I32, StrI32Map),
-        ParamType::StrU64Map => decode_map!(data, offset, Str, U64, StrU64Map),
-        ParamType::StrI64Map => decode_map!(data, offset, Str, I64, StrI64Map),
-        ParamType::StrU128Map => decode_map!(data, offset, Str, U128, StrU128Map),
-        ParamType::StrI128Map => decode_map!(data, offset, Str, I128, StrI128Map),
-        ParamType::StrBoolMap => decode_map!(data, offset, Str, Bool, StrBoolMap),
-        ParamType::StrStrMap => decode_map!(data, offset, Str, Str, StrStrMap),
-        ParamType::Parampack => unimplemented!(),
+            Ok(ABIParam::Parampack(bytes.to_vec()))
+        }
+    }
+}
+
+/// Decode the array param, the elements are length-prefixed unless the `fixed_len` of
+/// the fixed-size array is given.
+fn decode_array_in(
+    param_ty: &ParamType,
+    data: &[u8],
+    offset: &mut usize,
+    ctx: DecodeContext<'_>,
+    fixed_len: Option<usize>,
+) -> anyhow::Result<ABIParam> {
+    match param_ty {
+        ParamType::U8Array => decode_vec!(data, offset, U8, U8Array, ctx, fixed_len),
+        ParamType::I8Array => decode_vec!(data, offset, I8, I8Array, ctx, fixed_len),
+        ParamType::U16Array => decode_vec!(data, offset, U16, U16Array, ctx, fixed_len),
+        ParamType::I16Array => decode_vec!(data, offset, I16, I16Array, ctx, fixed_len),
+        ParamType::U32Array => decode_vec!(data, offset, U32, U32Array, ctx, fixed_len),
+        ParamType::I32Array => decode_vec!(data, offset, I32, I32Array, ctx, fixed_len),
+        ParamType::U64Array => decode_vec!(data, offset, U64, U64Array, ctx, fixed_len),
+        ParamType::I64Array => decode_vec!(data, offset, I64, I64Array, ctx, fixed_len),
+        ParamType::U128Array => decode_vec!(data, offset, U128, U128Array, ctx, fixed_len),
+        ParamType::I128Array => decode_vec!(data, offset, I128, I128Array, ctx, fixed_len),
+        ParamType::BoolArray if ctx.options.packed_bools => {
+            decode_packed_bools(data, offset, ctx, fixed_len)
+        }
+        ParamType::BoolArray => decode_vec!(data, offset, Bool, BoolArray, ctx, fixed_len),
+        ParamType::StrArray => decode_vec!(data, offset, Str, StrArray, ctx, fixed_len),
+        ParamType::F64Array => decode_vec!(data, offset, F64, F64Array, ctx, fixed_len),
+        _ => Err(anyhow!("decode {:?} is not an array", param_ty)),
+    }
+}
+
+/// Pack the bools 8 per byte, the first bool in the lowest bit.
+fn pack_bools(v: &[bool]) -> Vec<u8> {
+    v.chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, b)| byte | ((*b as u8) << i))
+        })
+        .collect()
+}
+
+/// Decode the bool array packed by `pack_bools`, which is length-prefixed unless the
+/// `fixed_len` of the fixed-size array is given. The unused high bits must be zero so
+/// that every bool array has a single packed encoding.
+fn decode_packed_bools(
+    data: &[u8],
+    offset: &mut usize,
+    ctx: DecodeContext<'_>,
+    fixed_len: Option<usize>,
+) -> anyhow::Result<ABIParam> {
+    let (len, bytes_offset) = match fixed_len {
+        Some(len) => (len, *offset),
+        None => read_uleb128_len_and_offset(data, offset)?,
+    };
+    ctx.elements(len)?;
+    let bytes_len = len.div_ceil(8);
+    ensure_remaining(data, &bytes_offset, bytes_len, "packed Bool array")?;
+    let bytes = &data[bytes_offset..bytes_offset + bytes_len];
+    if len % 8 != 0 && bytes[bytes_len - 1] >> (len % 8) != 0 {
+        return Err(anyhow!(
+            "decode packed Bool array error: the unused bits of the last byte are not zero"
+        ));
+    }
+    *offset = bytes_offset + bytes_len;
+    Ok(ABIParam::BoolArray(
+        (0..len)
+            .map(|i| bytes[i / 8] & (1 << (i % 8)) != 0)
+            .collect(),
+    ))
+}
+
+/// Encode the bytes to the canonical lowercase hex string, which is accepted by
+/// `decode_hex_str`, the `0x` prefix is only added when `with_prefix` is set.
+pub fn encode_hex_str(bytes: &[u8], with_prefix: bool) -> String {
+    let digits = hex::encode(bytes);
+    if with_prefix {
+        format!("0x{digits}")
+    } else {
+        digits
+    }
+}
+
+/// Decode the hex string of abi params such as `parampack`, an optional `0x` prefix is
+/// allowed, odd-length inputs and non-hex characters are rejected.
+pub fn decode_hex_str(hex_str: &str) -> Result<Vec<u8>, AbiError> {
+    let bad_hex = |reason: String| AbiError::BadHex {
+        value: hex_str.to_string(),
+        reason,
+    };
+    let (prefix_len, digits) = match hex_str
+        .strip_prefix("0x")
+        .or_else(|| hex_str.strip_prefix("0X"))
+    {
+        Some(digits) => (2, digits),
+        None => (0, hex_str),
+    };
+    if let Some((pos, c)) = digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(bad_hex(format!(
+            "non-hex character {c:?} at offset {}",
+            prefix_len + pos
+        )));
     }
+    if digits.len() % 2 != 0 {
+        return Err(bad_hex(format!(
+            "odd number of hex digits {}",
+            digits.len()
+        )));
+    }
+    hex::decode(digits).map_err(|err| bad_hex(err.to_string()))
 }
 
 fn get_bytes<const N: usize>(data: &[u8], offset: &usize) -> [u8; N] {
@@ -327,13 +1452,143 @@ fn get_bytes<const N: usize>(data: &[u8], offset: &usize) -> [u8; N] {
     bytes
 }
 
+pub(crate) fn uleb128_encoded_len(len: usize) -> usize {
+    let mut len = len >> 7;
+    let mut size = 1;
+    while len != 0 {
+        len >>= 7;
+        size += 1;
+    }
+    size
+}
+
+fn str_encoded_len(v: &str) -> usize {
+    uleb128_encoded_len(v.len()) + v.len()
+}
+
+fn fixed_map_encoded_len<V>(v: &BTreeMap<String, V>, value_size: usize) -> usize {
+    uleb128_encoded_len(v.len())
+        + v.keys()
+            .map(|k| str_encoded_len(k) + value_size)
+            .sum::<usize>()
+}
+
 fn buffer_starts_with_uleb128_len(len: usize) -> Vec<u8> {
     let mut buf = [0; 5];
     let len = ULEB128::from(len as u64).write_into(&mut buf).unwrap();
     buf[..len].to_vec()
 }
 
-fn read_uleb128_len_and_offset(data: &[u8], offset: &usize) -> anyhow::Result<(usize, usize)> {
+fn uleb128_bytes(mut v: u128) -> Vec<u8> {
+    let mut buf = vec![];
+    loop {
+        let byte = (v & 0x7f) as u8;
+        v >>= 7;
+        if v == 0 {
+            buf.push(byte);
+            return buf;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn sleb128_bytes(mut v: i128) -> Vec<u8> {
+    let mut buf = vec![];
+    loop {
+        let byte = (v & 0x7f) as u8;
+        v >>= 7;
+        if (v == 0 && byte & 0x40 == 0) || (v == -1 && byte & 0x40 != 0) {
+            buf.push(byte);
+            return buf;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+/// Find the LEB128 bytes at `offset`, at most 19 bytes which is enough for the 128-bit
+/// integers.
+fn read_leb128_bytes<'a>(data: &'a [u8], offset: &usize, what: &str) -> anyhow::Result<&'a [u8]> {
+    const MAX_LEN: usize = 19;
+    let remaining = &data[(*offset).min(data.len())..];
+    match remaining.iter().take(MAX_LEN).position(|b| b & 0x80 == 0) {
+        Some(pos) => Ok(&remaining[..=pos]),
+        None if remaining.len() < MAX_LEN => Err(anyhow!(
+            "decode {} error: unterminated varint at offset {}",
+            what,
+            offset
+        )),
+        None => Err(anyhow!(
+            "decode {} error: varint at offset {} is longer than {} bytes",
+            what,
+            offset,
+            MAX_LEN
+        )),
+    }
+}
+
+/// Read the ULEB128 varint, the overflowing and non-canonical encodings such as the
+/// padded `0x81 0x00` are rejected by comparing with the re-encoded bytes.
+fn read_uleb128(data: &[u8], offset: &mut usize, what: &str) -> anyhow::Result<u128> {
+    let bytes = read_leb128_bytes(data, offset, what)?;
+    let v = bytes
+        .iter()
+        .enumerate()
+        .fold(0u128, |v, (i, b)| v | ((*b as u128 & 0x7f) << (7 * i)));
+    if uleb128_bytes(v) != bytes {
+        return Err(anyhow!(
+            "decode {} error: overflowing or non-canonical varint at offset {}",
+            what,
+            offset
+        ));
+    }
+    *offset += bytes.len();
+    Ok(v)
+}
+
+/// Read the SLEB128 varint, rejecting the overflowing and non-canonical encodings in the
+/// same way as `read_uleb128`.
+fn read_sleb128(data: &[u8], offset: &mut usize, what: &str) -> anyhow::Result<i128> {
+    let bytes = read_leb128_bytes(data, offset, what)?;
+    let mut v = bytes
+        .iter()
+        .enumerate()
+        .fold(0i128, |v, (i, b)| v | ((*b as i128 & 0x7f) << (7 * i)));
+    let shift = 7 * bytes.len();
+    if shift < 128 && bytes[bytes.len() - 1] & 0x40 != 0 {
+        v |= -1i128 << shift;
+    }
+    if sleb128_bytes(v) != bytes {
+        return Err(anyhow!(
+            "decode {} error: overflowing or non-canonical varint at offset {}",
+            what,
+            offset
+        ));
+    }
+    *offset += bytes.len();
+    Ok(v)
+}
+
+/// Check the buffer has at least `len` bytes after `offset`, lengths read from untrusted
+/// bytes must pass it before they are used to slice or allocate.
+fn ensure_remaining(data: &[u8], offset: &usize, len: usize, what: &str) -> anyhow::Result<()> {
+    let remaining = data.len().saturating_sub(*offset);
+    if len > remaining {
+        return Err(anyhow!(
+            "decode {} error: need {} bytes at offset {}, but only {} bytes remain",
+            what,
+            len,
+            offset,
+            remaining
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn read_uleb128_len_and_offset(
+    data: &[u8],
+    offset: &usize,
+) -> anyhow::Result<(usize, usize)> {
+    ensure_remaining(data, offset, 1, "length prefix")?;
     let (len, len_offset) = match ULEB128::read_from(&data[*offset..]) {
         Ok(v) => v,
         Err(err) => {
@@ -344,3 +1599,856 @@ fn read_uleb128_len_and_offset(data: &[u8], offset: &usize) -> anyhow::Result<(u
     let total_offset = *offset + len_offset;
     Ok((len, total_offset))
 }
+
+#[cfg(test)]
+pub(crate) mod params_test {
+    use super::{
+        decode_abi_param_with_types, decode_hex_str, decode_param, decode_param_with_options,
+        decode_self_describing, encode_hex_str, uleb128_bytes, uleb128_encoded_len, ABIParam,
+        DecodeLimits, EncodingOptions, Endianness, MAX_TYPE_REF_DEPTH, SELF_DESCRIBING_VARIANT_TAG,
+    };
+    use crate::abi::error::AbiError;
+    use crate::abi::fixtures::sample_param;
+    use crate::abi::types::{ABIParamType, ABITypeTable};
+    use crate::encoding::datastream::ParamType;
+    use num_traits::FromPrimitive;
+    use proptest::prelude::*;
+    use std::collections::BTreeMap;
+
+    /// Generate random abi params across all the variants with `ParamType` codes, add
+    /// the new variant strategy here when a new `ABIParam` variant lands.
+    pub(crate) fn arb_abi_param() -> impl Strategy<Value = ABIParam> {
+        let finite_f32 = any::<f32>().prop_filter("finite", |v| v.is_finite());
+        let finite_f64 = any::<f64>().prop_filter("finite", |v| v.is_finite());
+        prop_oneof![
+            any::<u8>().prop_map(ABIParam::U8),
+            any::<i8>().prop_map(ABIParam::I8),
+            any::<u16>().prop_map(ABIParam::U16),
+            any::<i16>().prop_map(ABIParam::I16),
+            any::<u32>().prop_map(ABIParam::U32),
+            any::<i32>().prop_map(ABIParam::I32),
+            any::<u64>().prop_map(ABIParam::U64),
+            any::<i64>().prop_map(ABIParam::I64),
+            any::<u128>().prop_map(ABIParam::U128),
+            any::<i128>().prop_map(ABIParam::I128),
+            any::<bool>().prop_map(ABIParam::Bool),
+            any::<String>().prop_map(ABIParam::Str),
+            any::<Vec<u8>>().prop_map(ABIParam::Parampack),
+            finite_f32.prop_map(ABIParam::F32),
+            finite_f64.clone().prop_map(ABIParam::F64),
+            any::<Vec<u8>>().prop_map(ABIParam::U8Array),
+            any::<Vec<i8>>().prop_map(ABIParam::I8Array),
+            any::<Vec<u16>>().prop_map(ABIParam::U16Array),
+            any::<Vec<i16>>().prop_map(ABIParam::I16Array),
+            any::<Vec<u32>>().prop_map(ABIParam::U32Array),
+            any::<Vec<i32>>().prop_map(ABIParam::I32Array),
+            any::<Vec<u64>>().prop_map(ABIParam::U64Array),
+            any::<Vec<i64>>().prop_map(ABIParam::I64Array),
+            any::<Vec<u128>>().prop_map(ABIParam::U128Array),
+            any::<Vec<i128>>().prop_map(ABIParam::I128Array),
+            any::<Vec<bool>>().prop_map(ABIParam::BoolArray),
+            any::<Vec<String>>().prop_map(ABIParam::StrArray),
+            prop::collection::vec(finite_f64, 0..16).prop_map(ABIParam::F64Array),
+            any::<BTreeMap<String, u8>>().prop_map(ABIParam::StrU8Map),
+            any::<BTreeMap<String, i8>>().prop_map(ABIParam::StrI8Map),
+            any::<BTreeMap<String, u16>>().prop_map(ABIParam::StrU16Map),
+            any::<BTreeMap<String, i16>>().prop_map(ABIParam::StrI16Map),
+            any::<BTreeMap<String, u32>>().prop_map(ABIParam::StrU32Map),
+            any::<BTreeMap<String, i32>>().prop_map(ABIParam::StrI32Map),
+            any::<BTreeMap<String, u64>>().prop_map(ABIParam::StrU64Map),
+            any::<BTreeMap<String, i64>>().prop_map(ABIParam::StrI64Map),
+            any::<BTreeMap<String, u128>>().prop_map(ABIParam::StrU128Map),
+            any::<BTreeMap<String, i128>>().prop_map(ABIParam::StrI128Map),
+            any::<BTreeMap<String, bool>>().prop_map(ABIParam::StrBoolMap),
+            any::<BTreeMap<String, String>>().prop_map(ABIParam::StrStrMap),
+        ]
+    }
+
+    fn assert_round_trip(param: &ABIParam) {
+        let bytes = param.as_bytes();
+        assert_eq!(param.encoded_len(), bytes.len());
+        let mut offset = 0;
+        let decoded = decode_param(&param.to_param_type().unwrap(), &bytes, &mut offset).unwrap();
+        assert_eq!(&decoded, param);
+        assert_eq!(offset, bytes.len());
+    }
+
+    proptest! {
+        #[test]
+        fn test_abi_param_round_trip(param in arb_abi_param()) {
+            assert_round_trip(&param);
+        }
+
+        #[test]
+        fn test_abi_param_varint_round_trip(param in arb_abi_param()) {
+            let options = EncodingOptions::varint();
+            let bytes = param.as_bytes_with_options(options);
+            let mut offset = 0;
+            let decoded =
+                decode_param_with_options(&param.to_param_type().unwrap(), &bytes, &mut offset, options)
+                    .unwrap();
+            prop_assert_eq!(decoded, param);
+            prop_assert_eq!(offset, bytes.len());
+        }
+    }
+
+    #[test]
+    fn test_abi_param_round_trip_edge_cases() {
+        for param in [
+            ABIParam::Str("".to_string()),
+            ABIParam::Parampack(vec![]),
+            ABIParam::U8Array(vec![]),
+            ABIParam::StrArray(vec!["".to_string()]),
+            ABIParam::StrU64Map(BTreeMap::new()),
+            ABIParam::StrStrMap(BTreeMap::from([("".to_string(), "".to_string())])),
+            ABIParam::U8(u8::MAX),
+            ABIParam::I8(i8::MIN),
+            ABIParam::U64(u64::MAX),
+            ABIParam::I64(i64::MIN),
+            ABIParam::U128(u128::MAX),
+            ABIParam::I128(i128::MIN),
+            ABIParam::U128Array(vec![u128::MAX, 0]),
+            ABIParam::F64(f64::MAX),
+            ABIParam::Str("x".repeat(300)),
+        ] {
+            assert_round_trip(&param);
+        }
+    }
+
+    #[test]
+    fn test_self_describing_round_trip() {
+        let params = vec![
+            ABIParam::U32(7),
+            ABIParam::Str("hello".to_string()),
+            ABIParam::U8Array(vec![]),
+            ABIParam::StrBoolMap(BTreeMap::from([("on".to_string(), true)])),
+        ];
+        let mut bytes = vec![];
+        for param in &params {
+            bytes.append(&mut param.as_self_describing_bytes());
+        }
+        assert_eq!(&bytes[..6], &[4, 4, 7, 0, 0, 0]);
+        assert_eq!(decode_self_describing(&bytes).unwrap(), params);
+        assert!(decode_self_describing(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode_self_describing(&[0xff, 0]).is_err());
+    }
+
+    #[test]
+    fn test_decode_single_param_consumed_len() {
+        let mut bytes = vec![];
+        for param in [
+            ABIParam::U8(1),
+            ABIParam::I8(-1),
+            ABIParam::U16(2),
+            ABIParam::I16(-2),
+            ABIParam::U32(3),
+            ABIParam::I32(-3),
+            ABIParam::U64(4),
+            ABIParam::I64(-4),
+            ABIParam::U128(5),
+            ABIParam::I128(-5),
+            ABIParam::F32(0.5),
+            ABIParam::F64(-0.5),
+            ABIParam::Bool(true),
+        ] {
+            bytes.clear();
+            bytes.extend(param.as_bytes());
+            // The trailing byte belongs to the next param and must not be consumed.
+            bytes.push(0xff);
+            let ty = ABIParamType::from(param.to_param_type().unwrap());
+            assert_eq!(
+                ABIParam::decode(ty, &bytes).unwrap(),
+                (param.clone(), bytes.len() - 1)
+            );
+        }
+        // Variable-length params consume the length prefix and the payload.
+        let cases = [
+            (ABIParamType::Str, ABIParam::Str("".to_string()), 1),
+            (ABIParamType::Str, ABIParam::Str("x".repeat(200)), 202),
+            (ABIParamType::U8Array, ABIParam::U8Array(vec![1, 2, 3]), 4),
+            (ABIParamType::U32Array, ABIParam::U32Array(vec![1, 2]), 9),
+            (
+                ABIParamType::StrArray,
+                ABIParam::StrArray(vec!["ab".to_string(), "".to_string()]),
+                1 + 3 + 1,
+            ),
+            (
+                ABIParamType::StrU16Map,
+                ABIParam::StrU16Map(BTreeMap::from([("k".to_string(), 7)])),
+                1 + 2 + 2,
+            ),
+        ];
+        for (ty, param, consumed) in cases {
+            let mut bytes = param.as_bytes();
+            bytes.extend([0xff, 0xff]);
+            assert_eq!(ABIParam::decode(ty, &bytes).unwrap(), (param, consumed));
+        }
+        assert!(ABIParam::decode(ABIParamType::U32, &[1, 2]).is_err());
+    }
+
+    #[test]
+    fn test_map_encoding_is_deterministic() {
+        let keys: Vec<String> = (0..100).map(|i| format!("key{i}")).collect();
+        let forward: BTreeMap<String, u32> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, k)| (k.clone(), i as u32))
+            .collect();
+        let mut backward = BTreeMap::new();
+        for (i, k) in keys.iter().enumerate().rev() {
+            backward.insert(k.clone(), i as u32);
+        }
+        assert_eq!(
+            ABIParam::StrU32Map(forward).as_bytes(),
+            ABIParam::StrU32Map(backward).as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_parampack_hex_round_trip() {
+        let bytes = vec![0xde, 0xad, 0xbe, 0xef];
+        for hex_str in ["0xDEADbeef", "deadbeef", "0Xdeadbeef"] {
+            assert_eq!(
+                ABIParam::parampack_from_hex(hex_str).unwrap(),
+                ABIParam::Parampack(bytes.clone())
+            );
+        }
+        for with_prefix in [true, false] {
+            let hex_str = encode_hex_str(&bytes, with_prefix);
+            assert_eq!(hex_str.starts_with("0x"), with_prefix);
+            assert_eq!(decode_hex_str(&hex_str).unwrap(), bytes);
+        }
+        let param = ABIParam::parampack_from_hex("0x").unwrap();
+        assert_eq!(param, ABIParam::Parampack(vec![]));
+        // The canonical hex of the encoding starts with the length prefix.
+        assert_eq!(ABIParam::Parampack(bytes).to_hex(), "04deadbeef");
+        assert!(ABIParam::parampack_from_hex("0xabc").is_err());
+    }
+
+    #[test]
+    fn test_variant_round_trip() {
+        let variant_ty = ABIParamType::Variant(vec![
+            ABIParamType::U8,
+            ABIParamType::Str,
+            ABIParamType::Variant(vec![ABIParamType::Bool]),
+        ]);
+        for param in [
+            ABIParam::Variant {
+                tag: 0,
+                value: Box::new(ABIParam::U8(7)),
+            },
+            ABIParam::Variant {
+                tag: 1,
+                value: Box::new(ABIParam::Str("hi".to_string())),
+            },
+            ABIParam::Variant {
+                tag: 2,
+                value: Box::new(ABIParam::Variant {
+                    tag: 0,
+                    value: Box::new(ABIParam::Bool(true)),
+                }),
+            },
+        ] {
+            let bytes = param.as_bytes();
+            assert_eq!(param.encoded_len(), bytes.len());
+            assert_eq!(
+                ABIParam::decode(variant_ty.clone(), &bytes).unwrap(),
+                (param.clone(), bytes.len())
+            );
+            let self_describing_bytes = param.as_self_describing_bytes();
+            assert_eq!(
+                decode_self_describing(&self_describing_bytes).unwrap(),
+                vec![param]
+            );
+        }
+        assert_eq!(
+            ABIParam::Variant {
+                tag: 1,
+                value: Box::new(ABIParam::Str("hi".to_string())),
+            }
+            .as_bytes(),
+            vec![1, 0, 0, 0, 2, b'h', b'i']
+        );
+        assert!(ABIParam::decode(variant_ty, &[3, 0, 0, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_self_describing_variant_without_value() {
+        // The variant payload ends right after the tag, so the value has no type tag.
+        assert!(decode_self_describing(&[0x80, 0x04, 1, 0, 0, 0]).is_err());
+        assert!(decode_self_describing(&[0x80, 0x00]).is_err());
+    }
+
+    /// The self-describing bytes of `u8` 0 wrapped in `depth` nested variants.
+    pub(crate) fn nested_variant_bytes(depth: usize) -> Vec<u8> {
+        let mut bytes = ABIParam::U8(0).as_self_describing_bytes();
+        for _ in 0..depth {
+            let mut payload = 0u32.to_le_bytes().to_vec();
+            payload.extend(bytes);
+            bytes = vec![SELF_DESCRIBING_VARIANT_TAG];
+            bytes.extend(uleb128_bytes(payload.len() as u128));
+            bytes.extend(payload);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_self_describing_variant_depth() {
+        let decode = |depth: usize| {
+            decode_self_describing(&nested_variant_bytes(depth))
+                .map_err(|err| err.downcast::<AbiError>().unwrap())
+        };
+        assert!(decode(MAX_TYPE_REF_DEPTH).is_ok());
+        for depth in [MAX_TYPE_REF_DEPTH + 1, 10_000] {
+            assert_eq!(
+                decode(depth),
+                Err(AbiError::LimitExceeded {
+                    limit: "max_depth".to_string(),
+                    actual: MAX_TYPE_REF_DEPTH + 1,
+                    max: MAX_TYPE_REF_DEPTH,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_bytes_params_round_trip() {
+        for bytes in [vec![], vec![0xab; 4096]] {
+            let param = ABIParam::Bytes(bytes.clone());
+            assert_eq!(param.as_bytes(), ABIParam::Parampack(bytes).as_bytes());
+            assert_eq!(param.encoded_len(), param.as_bytes().len());
+            assert_eq!(
+                ABIParam::decode(ABIParamType::Bytes, &param.as_bytes()).unwrap(),
+                (param.clone(), param.encoded_len())
+            );
+        }
+        assert_eq!(
+            ABIParam::bytes_from_hex("0xDEAD").unwrap(),
+            ABIParam::Bytes(vec![0xde, 0xad])
+        );
+    }
+
+    #[test]
+    fn test_fixed_array_round_trip() {
+        let ty = ABIParamType::from_type_str("[u8;4]").unwrap();
+        let param = ABIParam::fixed_array(ABIParam::U8Array(vec![1, 2, 3, 4]), 4).unwrap();
+        // No length prefix.
+        assert_eq!(param.as_bytes(), [1, 2, 3, 4]);
+        assert_eq!(param.encoded_len(), 4);
+        assert_eq!(param.array_len(), Some(4));
+        assert_eq!(param.to_readable(), "[1, 2, 3, 4]");
+        assert_eq!(
+            ABIParam::decode(ty.clone(), &[1, 2, 3, 4, 5]).unwrap(),
+            (param, 4)
+        );
+        assert!(ABIParam::decode(ty, &[1, 2, 3]).is_err());
+        let strs = ABIParam::fixed_array(ABIParam::StrArray(vec!["ab".to_string()]), 1).unwrap();
+        let options = EncodingOptions::varint();
+        assert_eq!(
+            ABIParam::decode_with_options(
+                ABIParamType::from_type_str("[str;1]").unwrap(),
+                &strs.as_bytes_with_options(options),
+                options
+            )
+            .unwrap(),
+            (strs, 3)
+        );
+        assert_eq!(
+            ABIParam::fixed_array(ABIParam::U8Array(vec![1, 2, 3]), 4),
+            Err(AbiError::FixedArrayLength {
+                expected: 4,
+                got: 3
+            })
+        );
+        assert!(ABIParam::fixed_array(ABIParam::U8(1), 1).is_err());
+    }
+
+    #[test]
+    fn test_accessors() {
+        assert_eq!(ABIParam::U8(7).as_integer(), Some(7));
+        assert_eq!(ABIParam::I64(-7).as_integer(), Some(-7));
+        assert_eq!(ABIParam::I128(i128::MIN).as_integer(), Some(i128::MIN));
+        assert_eq!(
+            ABIParam::U128(i128::MAX as u128).as_integer(),
+            Some(i128::MAX)
+        );
+        assert_eq!(ABIParam::U128(u128::MAX).as_integer(), None);
+        assert_eq!(ABIParam::Timestamp(1).as_integer(), None);
+        assert_eq!(ABIParam::Bool(true).as_integer(), None);
+
+        assert_eq!(ABIParam::F32(1.5).as_f64(), Some(1.5));
+        assert_eq!(ABIParam::F64(-2.5).as_f64(), Some(-2.5));
+        assert_eq!(ABIParam::U8(1).as_f64(), None);
+
+        assert_eq!(ABIParam::Bool(true).as_bool(), Some(true));
+        assert_eq!(ABIParam::U8(1).as_bool(), None);
+
+        assert_eq!(ABIParam::Str("alice".to_string()).as_str(), Some("alice"));
+        assert_eq!(ABIParam::StrArray(vec!["a".to_string()]).as_str(), None);
+
+        for param in [
+            ABIParam::Parampack(vec![1, 2]),
+            ABIParam::Bytes(vec![1, 2]),
+            ABIParam::U8Array(vec![1, 2]),
+            ABIParam::fixed_array(ABIParam::U8Array(vec![1, 2]), 2).unwrap(),
+        ] {
+            assert_eq!(param.as_bytes_ref(), Some(&[1u8, 2][..]));
+        }
+        assert_eq!(ABIParam::I8Array(vec![1, 2]).as_bytes_ref(), None);
+        assert_eq!(ABIParam::Str("ab".to_string()).as_bytes_ref(), None);
+
+        let variant = ABIParam::Variant {
+            tag: 2,
+            value: Box::new(ABIParam::Bool(false)),
+        };
+        assert_eq!(variant.as_variant(), Some((2, &ABIParam::Bool(false))));
+        assert_eq!(ABIParam::U32(2).as_variant(), None);
+    }
+
+    #[test]
+    fn test_sample_params_round_trip() {
+        let mut tys: Vec<ABIParamType> = (0u8..=64)
+            .filter_map(|code| ParamType::from_u8(code).map(ABIParamType::from))
+            .collect();
+        tys.extend([
+            ABIParamType::Timestamp,
+            ABIParamType::Duration,
+            ABIParamType::Bytes,
+            ABIParamType::from_type_str("enum<u8,[string],{string:i64}>").unwrap(),
+            ABIParamType::from_type_str("[u16;3]").unwrap(),
+            ABIParamType::from_type_str("[string;0]").unwrap(),
+            ABIParamType::Address(Some(20)),
+            ABIParamType::Address(None),
+        ]);
+        for ty in tys {
+            let (param, bytes) = sample_param(&ty);
+            assert_eq!(param.as_bytes(), bytes, "{ty:?}");
+            assert_eq!(param.encoded_len(), bytes.len(), "{ty:?}");
+            assert_eq!(
+                ABIParam::decode(ty.clone(), &bytes).unwrap(),
+                (param, bytes.len()),
+                "{ty:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_packed_bools_round_trip() {
+        let options = EncodingOptions::packed_bools();
+        for len in [0, 1, 7, 8, 9, 16, 1000] {
+            let param = ABIParam::BoolArray((0..len).map(|i| i % 3 == 0).collect());
+            let bytes = param.as_bytes_with_options(options);
+            assert_eq!(bytes.len(), uleb128_encoded_len(len) + len.div_ceil(8));
+            let mut offset = 0;
+            assert_eq!(
+                decode_param_with_options(&ParamType::BoolArray, &bytes, &mut offset, options)
+                    .unwrap(),
+                param
+            );
+            assert_eq!(offset, bytes.len());
+        }
+        // 1000 flags take 127 bytes instead of 1002 bytes.
+        let flags = ABIParam::BoolArray(vec![true; 1000]);
+        assert_eq!(flags.as_bytes().len(), 1002);
+        assert_eq!(flags.as_bytes_with_options(options).len(), 127);
+        // The first bool in the lowest bit.
+        let param = ABIParam::BoolArray(vec![
+            true, false, true, true, false, false, false, false, true,
+        ]);
+        assert_eq!(param.as_bytes_with_options(options), [9, 0b1101, 0b1]);
+        let fixed = ABIParam::fixed_array(param.clone(), 9).unwrap();
+        assert_eq!(fixed.as_bytes_with_options(options), [0b1101, 0b1]);
+        assert_eq!(
+            ABIParam::decode_with_options(
+                ABIParamType::from_type_str("[bool;9]").unwrap(),
+                &[0b1101, 0b1],
+                options
+            )
+            .unwrap(),
+            (fixed, 2)
+        );
+        // The unused bits must be zero.
+        let mut offset = 0;
+        assert!(decode_param_with_options(
+            &ParamType::BoolArray,
+            &[9, 0b1101, 0b11],
+            &mut offset,
+            options
+        )
+        .is_err());
+        assert!(decode_param_with_options(
+            &ParamType::BoolArray,
+            &[9, 0b1101],
+            &mut offset,
+            options
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_recursive_type_depth_limit() {
+        // The list of u64 values, `#0` is either the last value or a value and the rest.
+        let types = ABITypeTable::from_type_strs(&["enum<u64,#0>"]).unwrap();
+        let list = |depth: usize| {
+            (0..depth).fold(
+                ABIParam::Variant {
+                    tag: 0,
+                    value: Box::new(ABIParam::U64(7)),
+                },
+                |rest, _| ABIParam::Variant {
+                    tag: 1,
+                    value: Box::new(rest),
+                },
+            )
+        };
+        let decode = |param: &ABIParam, limits: DecodeLimits| {
+            let bytes = param.as_bytes();
+            decode_abi_param_with_types(
+                &ABIParamType::Ref(0),
+                &bytes,
+                &mut 0,
+                EncodingOptions::default(),
+                limits,
+                &types,
+            )
+            .map_err(|err| err.downcast::<AbiError>().unwrap())
+        };
+        let param = list(MAX_TYPE_REF_DEPTH);
+        assert_eq!(decode(&param, DecodeLimits::default()).unwrap(), param);
+        // Bounded even with the unlimited depth.
+        assert_eq!(
+            decode(&list(MAX_TYPE_REF_DEPTH + 1), DecodeLimits::default()),
+            Err(AbiError::LimitExceeded {
+                limit: "max_depth".to_string(),
+                actual: MAX_TYPE_REF_DEPTH + 1,
+                max: MAX_TYPE_REF_DEPTH,
+            })
+        );
+        let limits = DecodeLimits {
+            max_depth: 8,
+            ..Default::default()
+        };
+        assert!(matches!(
+            decode(&list(8), limits),
+            Err(AbiError::LimitExceeded { limit, max: 8, .. }) if limit == "max_depth"
+        ));
+        assert_eq!(decode(&list(3), limits).unwrap(), list(3));
+        // The references can't be decoded without the types table.
+        assert!(ABIParam::decode(ABIParamType::Ref(0), &list(0).as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_merge_map() {
+        let left = ABIParam::StrU64Map([("a".to_string(), 1), ("b".to_string(), 2)].into());
+        let right = ABIParam::StrU64Map([("c".to_string(), 3)].into());
+        assert_eq!(
+            left.clone().merge_map(right).unwrap(),
+            ABIParam::StrU64Map(
+                [
+                    ("a".to_string(), 1),
+                    ("b".to_string(), 2),
+                    ("c".to_string(), 3)
+                ]
+                .into()
+            )
+        );
+        let conflict = ABIParam::StrU64Map([("b".to_string(), 4)].into());
+        assert_eq!(
+            left.clone().merge_map(conflict),
+            Err(AbiError::DuplicateMapKey("b".to_string()))
+        );
+        let mismatch = ABIParam::StrI64Map([("c".to_string(), 3)].into());
+        assert_eq!(
+            left.merge_map(mismatch).unwrap_err().to_string(),
+            "cannot merge {string:u64} with {string:i64}, expect the same map type"
+        );
+    }
+
+    #[test]
+    fn test_address_round_trip() {
+        let param = ABIParam::address(vec![0xab; 20], Some(20)).unwrap();
+        // No length prefix.
+        assert_eq!(param.as_bytes(), [0xab; 20]);
+        assert_eq!(param.encoded_len(), 20);
+        assert_eq!(param.to_readable(), format!("0x{}", "ab".repeat(20)));
+        let mut bytes = param.as_bytes();
+        bytes.push(1);
+        assert_eq!(
+            ABIParam::decode(ABIParamType::Address(Some(20)), &bytes).unwrap(),
+            (param, 20)
+        );
+        assert!(ABIParam::decode(ABIParamType::Address(Some(32)), &bytes).is_err());
+        let param = ABIParam::address(vec![1, 2, 3], None).unwrap();
+        assert_eq!(param.as_bytes(), [3, 1, 2, 3]);
+        assert_eq!(
+            ABIParam::decode(ABIParamType::Address(None), &[3, 1, 2, 3]).unwrap(),
+            (param, 4)
+        );
+        assert_eq!(
+            ABIParam::address(vec![1; 20], Some(32)),
+            Err(AbiError::AddressLength {
+                expected: 32,
+                got: 20
+            })
+        );
+    }
+
+    #[test]
+    fn test_time_params_round_trip() {
+        let timestamp = ABIParam::Timestamp(-1);
+        assert_eq!(timestamp.as_bytes(), ABIParam::I64(-1).as_bytes());
+        assert_eq!(
+            ABIParam::decode(ABIParamType::Timestamp, &timestamp.as_bytes()).unwrap(),
+            (timestamp, 8)
+        );
+        let duration = ABIParam::Duration(3600);
+        assert_eq!(duration.as_bytes(), ABIParam::U64(3600).as_bytes());
+        assert_eq!(duration.encoded_len(), 8);
+        assert_eq!(
+            ABIParam::decode(ABIParamType::Duration, &duration.as_bytes()).unwrap(),
+            (duration.clone(), 8)
+        );
+        assert_eq!(
+            decode_self_describing(&duration.as_self_describing_bytes()).unwrap(),
+            vec![ABIParam::U64(3600)]
+        );
+    }
+
+    #[test]
+    fn test_to_readable() {
+        let cases = [
+            (ABIParam::U8(255), "255"),
+            (ABIParam::I8(-128), "-128"),
+            (ABIParam::U16(65535), "65535"),
+            (ABIParam::I16(-1), "-1"),
+            (ABIParam::U32(42), "42"),
+            (ABIParam::I32(-42), "-42"),
+            (ABIParam::U64(u64::MAX), "18446744073709551615"),
+            (ABIParam::I64(i64::MIN), "-9223372036854775808"),
+            (ABIParam::U128(1), "1"),
+            (ABIParam::I128(-1), "-1"),
+            (ABIParam::F32(1.5), "1.5"),
+            (ABIParam::F64(-0.25), "-0.25"),
+            (ABIParam::Bool(true), "true"),
+            (ABIParam::Str("a \"b\"".to_string()), "\"a \\\"b\\\"\""),
+            (ABIParam::U8Array(vec![1, 2]), "[1, 2]"),
+            (ABIParam::I8Array(vec![-1]), "[-1]"),
+            (ABIParam::U16Array(vec![]), "[]"),
+            (ABIParam::I16Array(vec![-2, 3]), "[-2, 3]"),
+            (ABIParam::U32Array(vec![4]), "[4]"),
+            (ABIParam::I32Array(vec![-4]), "[-4]"),
+            (ABIParam::U64Array(vec![5, 6]), "[5, 6]"),
+            (ABIParam::I64Array(vec![-5]), "[-5]"),
+            (ABIParam::U128Array(vec![7]), "[7]"),
+            (ABIParam::I128Array(vec![-7]), "[-7]"),
+            (ABIParam::BoolArray(vec![true, false]), "[true, false]"),
+            (
+                ABIParam::StrArray(vec!["a".to_string(), "b".to_string()]),
+                "[\"a\", \"b\"]",
+            ),
+            (ABIParam::F64Array(vec![0.5]), "[0.5]"),
+            (
+                ABIParam::StrU8Map([("a".to_string(), 1)].into()),
+                "{\"a\": 1}",
+            ),
+            (
+                ABIParam::StrI8Map([("a".to_string(), -1)].into()),
+                "{\"a\": -1}",
+            ),
+            (ABIParam::StrU16Map(BTreeMap::new()), "{}"),
+            (
+                ABIParam::StrI16Map([("a".to_string(), -2)].into()),
+                "{\"a\": -2}",
+            ),
+            (
+                ABIParam::StrU32Map([("b".to_string(), 2), ("a".to_string(), 1)].into()),
+                "{\"a\": 1, \"b\": 2}",
+            ),
+            (
+                ABIParam::StrI32Map([("a".to_string(), -3)].into()),
+                "{\"a\": -3}",
+            ),
+            (
+                ABIParam::StrU64Map([("a".to_string(), 3)].into()),
+                "{\"a\": 3}",
+            ),
+            (
+                ABIParam::StrI64Map([("a".to_string(), -4)].into()),
+                "{\"a\": -4}",
+            ),
+            (
+                ABIParam::StrU128Map([("a".to_string(), 4)].into()),
+                "{\"a\": 4}",
+            ),
+            (
+                ABIParam::StrI128Map([("a".to_string(), -5)].into()),
+                "{\"a\": -5}",
+            ),
+            (
+                ABIParam::StrBoolMap([("a".to_string(), true)].into()),
+                "{\"a\": true}",
+            ),
+            (
+                ABIParam::StrStrMap([("a".to_string(), "x".to_string())].into()),
+                "{\"a\": \"x\"}",
+            ),
+            (ABIParam::Parampack(vec![0xde, 0xad]), "0xdead"),
+            (
+                ABIParam::Variant {
+                    tag: 1,
+                    value: Box::new(ABIParam::U8Array(vec![1])),
+                },
+                "1:[1]",
+            ),
+        ];
+        for (param, readable) in cases {
+            assert_eq!(param.to_readable(), readable);
+        }
+    }
+
+    #[test]
+    fn test_endianness() {
+        let param = ABIParam::U32(0x01020304);
+        assert_eq!(param.as_bytes(), vec![0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(
+            param.as_bytes_with_endianness(Endianness::Little),
+            param.as_bytes()
+        );
+        let big_endian_bytes = param.as_bytes_with_endianness(Endianness::Big);
+        assert_eq!(big_endian_bytes, vec![0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(
+            ABIParam::decode_with_endianness(ABIParamType::U32, &big_endian_bytes, Endianness::Big)
+                .unwrap(),
+            (param.clone(), 4)
+        );
+        assert_eq!(
+            ABIParam::decode(ABIParamType::U32, &big_endian_bytes).unwrap(),
+            (ABIParam::U32(0x04030201), 4)
+        );
+        let params = [
+            ABIParam::I64(-2),
+            ABIParam::F64(1.5),
+            ABIParam::U16Array(vec![1, 0x0203]),
+            ABIParam::StrI32Map([("a".to_string(), -3)].into()),
+            ABIParam::Variant {
+                tag: 1,
+                value: Box::new(ABIParam::Str("x".to_string())),
+            },
+        ];
+        let tys = [
+            ABIParamType::I64,
+            ABIParamType::F64,
+            ABIParamType::U16Array,
+            ABIParamType::StrI32Map,
+            ABIParamType::Variant(vec![ABIParamType::U8, ABIParamType::Str]),
+        ];
+        for (param, ty) in params.into_iter().zip(tys) {
+            let bytes = param.as_bytes_with_endianness(Endianness::Big);
+            assert_eq!(bytes.len(), param.encoded_len());
+            assert_eq!(
+                ABIParam::decode_with_endianness(ty, &bytes, Endianness::Big).unwrap(),
+                (param, bytes.len())
+            );
+        }
+        assert_eq!(
+            ABIParam::U16Array(vec![0x0203]).as_bytes_with_endianness(Endianness::Big),
+            vec![0x01, 0x02, 0x03]
+        );
+    }
+
+    #[test]
+    fn test_varint() {
+        let options = EncodingOptions::varint();
+        let assert_varint = |param: ABIParam, bytes: &[u8]| {
+            assert_eq!(param.as_bytes_with_options(options), bytes, "{param:?}");
+            let mut offset = 0;
+            let decoded = decode_param_with_options(
+                &param.to_param_type().unwrap(),
+                bytes,
+                &mut offset,
+                options,
+            )
+            .unwrap();
+            assert_eq!(decoded, param);
+            assert_eq!(offset, bytes.len());
+        };
+        assert_varint(ABIParam::U8(0), &[0x00]);
+        assert_varint(ABIParam::U8(127), &[0x7f]);
+        assert_varint(ABIParam::U8(128), &[0x80, 0x01]);
+        assert_varint(ABIParam::U8(u8::MAX), &[0xff, 0x01]);
+        assert_varint(ABIParam::U64(300), &[0xac, 0x02]);
+        assert_varint(ABIParam::I8(-1), &[0x7f]);
+        assert_varint(ABIParam::I8(63), &[0x3f]);
+        assert_varint(ABIParam::I8(64), &[0xc0, 0x00]);
+        assert_varint(ABIParam::I8(-64), &[0x40]);
+        assert_varint(ABIParam::I8(-65), &[0xbf, 0x7f]);
+        assert_varint(ABIParam::I8(i8::MIN), &[0x80, 0x7f]);
+        assert_varint(
+            ABIParam::U32Array(vec![1, 2, 300]),
+            &[0x03, 0x01, 0x02, 0xac, 0x02],
+        );
+        let mut u128_max = vec![0xff; 18];
+        u128_max.push(0x03);
+        assert_varint(ABIParam::U128(u128::MAX), &u128_max);
+        let mut i128_min = vec![0x80; 18];
+        i128_min.push(0x7e);
+        assert_varint(ABIParam::I128(i128::MIN), &i128_min);
+        for param in [
+            ABIParam::U16(u16::MAX),
+            ABIParam::I16(i16::MIN),
+            ABIParam::U32(u32::MAX),
+            ABIParam::I32(i32::MIN),
+            ABIParam::I32(i32::MAX),
+            ABIParam::U64(u64::MAX),
+            ABIParam::I64(i64::MIN),
+            ABIParam::I128(i128::MAX),
+            ABIParam::F32(1.5),
+            ABIParam::StrI64Map([("a".to_string(), -1), ("b".to_string(), 1 << 40)].into()),
+        ] {
+            let bytes = param.as_bytes_with_options(options);
+            assert_varint(param, &bytes);
+        }
+        // The floats are still fixed-width.
+        assert_eq!(
+            ABIParam::F64(1.0).as_bytes_with_options(options),
+            ABIParam::F64(1.0).as_bytes()
+        );
+
+        let decode_err = |param_ty: ParamType, bytes: &[u8]| {
+            decode_param_with_options(&param_ty, bytes, &mut 0, options)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            decode_err(ParamType::U8, &[0x80, 0x02]),
+            "decode u8 error: varint 256 is out of range"
+        );
+        assert_eq!(
+            decode_err(ParamType::I8, &[0x80, 0x01]),
+            "decode i8 error: varint 128 is out of range"
+        );
+        assert_eq!(
+            decode_err(ParamType::U32, &[0x81, 0x00]),
+            "decode u32 error: overflowing or non-canonical varint at offset 0"
+        );
+        assert_eq!(
+            decode_err(
+                ParamType::U128,
+                &[0xff; 18]
+                    .iter()
+                    .chain(&[0x04])
+                    .copied()
+                    .collect::<Vec<u8>>()
+            ),
+            "decode u128 error: overflowing or non-canonical varint at offset 0"
+        );
+        assert_eq!(
+            decode_err(ParamType::U64, &[0x80, 0x80]),
+            "decode u64 error: unterminated varint at offset 0"
+        );
+        assert_eq!(
+            decode_err(ParamType::U64, &[0x80; 20]),
+            "decode u64 error: varint at offset 0 is longer than 19 bytes"
+        );
+    }
+}
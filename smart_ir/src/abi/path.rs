@@ -0,0 +1,149 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The path-based accessor into the decoded param trees, so that the frontends binding form
+//! fields to nested positions needn't match the `ABIParam` variants by hand. The elements
+//! of the arrays and maps are stored unboxed, e.g., `U8Array(Vec<u8>)`, so they are returned
+//! as owned scalar params, and the variant values are borrowed. The fixed-size arrays are
+//! navigated in the same way as their dynamic arrays.
+
+use std::borrow::Cow;
+
+use crate::abi::params::ABIParam;
+
+/// The segment of the path into a param.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSeg {
+    /// The element index of an array.
+    Index(usize),
+    /// The entry key of a map.
+    Key(String),
+    /// The value of a variant, which only matches the selected tag.
+    Variant(u32),
+}
+
+macro_rules! impl_get_path {
+    (
+        arrays: $($array_id:ident => $array_elem_id:ident),*;
+        maps: $($map_id:ident => $map_value_id:ident),*;
+    ) => {
+        impl ABIParam {
+            /// Get the nested param at the path, e.g., `[Variant(1), Index(3)]` is the 4th
+            /// element of the array selected by the variant tag 1. The empty path is the
+            /// param itself. It returns `None` for the out-of-range indexes, the missing
+            /// keys, the unselected tags and the segments mismatching the param type.
+            pub fn get_path(&self, path: &[PathSeg]) -> Option<Cow<'_, ABIParam>> {
+                let (seg, rest) = match path.split_first() {
+                    Some(split) => split,
+                    None => return Some(Cow::Borrowed(self)),
+                };
+                let elem = match (self, seg) {
+                    (ABIParam::Variant { tag, value }, PathSeg::Variant(seg_tag))
+                        if tag == seg_tag =>
+                    {
+                        return value.get_path(rest);
+                    }
+                    (ABIParam::FixedArray(array), _) => return array.get_path(path),
+                    $((ABIParam::$array_id(v), PathSeg::Index(i)) => {
+                        ABIParam::$array_elem_id(v.get(*i)?.clone())
+                    })*
+                    $((ABIParam::$map_id(v), PathSeg::Key(key)) => {
+                        ABIParam::$map_value_id(v.get(key)?.clone())
+                    })*
+                    _ => return None,
+                };
+                // The elements are scalars, there is nothing to navigate into.
+                rest.is_empty().then_some(Cow::Owned(elem))
+            }
+        }
+    };
+}
+
+impl_get_path!(
+    arrays: U8Array => U8, I8Array => I8, U16Array => U16, I16Array => I16, U32Array => U32,
+        I32Array => I32, U64Array => U64, I64Array => I64, U128Array => U128,
+        I128Array => I128, BoolArray => Bool, StrArray => Str, F64Array => F64;
+    maps: StrU8Map => U8, StrI8Map => I8, StrU16Map => U16, StrI16Map => I16,
+        StrU32Map => U32, StrI32Map => I32, StrU64Map => U64, StrI64Map => I64,
+        StrU128Map => U128, StrI128Map => I128, StrBoolMap => Bool, StrStrMap => Str;
+);
+
+#[cfg(test)]
+mod path_test {
+    use super::PathSeg;
+    use crate::abi::params::ABIParam;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_get_path() {
+        // enum<u8, enum<[u64], {string:string}>>
+        let array = ABIParam::Variant {
+            tag: 1,
+            value: Box::new(ABIParam::Variant {
+                tag: 0,
+                value: Box::new(ABIParam::U64Array(vec![1, 2, 3])),
+            }),
+        };
+        let map = ABIParam::Variant {
+            tag: 1,
+            value: Box::new(ABIParam::Variant {
+                tag: 1,
+                value: Box::new(ABIParam::StrStrMap(
+                    [("name".to_string(), "alice".to_string())].into(),
+                )),
+            }),
+        };
+        assert_eq!(array.get_path(&[]), Some(Cow::Borrowed(&array)));
+        assert_eq!(
+            array.get_path(&[PathSeg::Variant(1), PathSeg::Variant(0), PathSeg::Index(2)]),
+            Some(Cow::Owned(ABIParam::U64(3)))
+        );
+        assert_eq!(
+            map.get_path(&[
+                PathSeg::Variant(1),
+                PathSeg::Variant(1),
+                PathSeg::Key("name".to_string())
+            ]),
+            Some(Cow::Owned(ABIParam::Str("alice".to_string())))
+        );
+        let ABIParam::Variant { value, .. } = &map else {
+            unreachable!()
+        };
+        assert_eq!(
+            map.get_path(&[PathSeg::Variant(1)]),
+            Some(Cow::Borrowed(value.as_ref()))
+        );
+        for path in [
+            // The unselected tags.
+            vec![PathSeg::Variant(0)],
+            vec![PathSeg::Variant(1), PathSeg::Variant(1), PathSeg::Index(0)],
+            // The out-of-range index and the key segment into an array.
+            vec![PathSeg::Variant(1), PathSeg::Variant(0), PathSeg::Index(3)],
+            vec![
+                PathSeg::Variant(1),
+                PathSeg::Variant(0),
+                PathSeg::Key("0".to_string()),
+            ],
+            // Navigate into the scalars.
+            vec![
+                PathSeg::Variant(1),
+                PathSeg::Variant(0),
+                PathSeg::Index(0),
+                PathSeg::Index(0),
+            ],
+            vec![PathSeg::Index(0)],
+        ] {
+            assert_eq!(array.get_path(&path), None, "{path:?}");
+        }
+        assert_eq!(
+            map.get_path(&[
+                PathSeg::Variant(1),
+                PathSeg::Variant(1),
+                PathSeg::Key("age".to_string())
+            ]),
+            None
+        );
+        assert_eq!(ABIParam::U8(1).get_path(&[PathSeg::Index(0)]), None);
+    }
+}
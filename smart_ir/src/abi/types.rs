@@ -0,0 +1,625 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::abi::error::AbiError;
+use crate::encoding::datastream::ParamType;
+
+/// Smart Intermediate Representation ABI param types, which describe how an
+/// `ABIParam` is laid out in the data stream bytes.
+///
+/// Different from the `ParamType` code used by the codegen and the self-describing
+/// framing, it is not `Copy` so that composite types can carry their inner types.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ABIParamType {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    Bool,
+    Str,
+    Parampack,
+    F32,
+    F64,
+
+    // Array params
+    U8Array,
+    I8Array,
+    U16Array,
+    I16Array,
+    U32Array,
+    I32Array,
+    U64Array,
+    I64Array,
+    U128Array,
+    I128Array,
+    BoolArray,
+    StrArray,
+    F64Array,
+
+    // Map params
+    StrU8Map,
+    StrI8Map,
+    StrU16Map,
+    StrI16Map,
+    StrU32Map,
+    StrI32Map,
+    StrU64Map,
+    StrI64Map,
+    StrU128Map,
+    StrI128Map,
+    StrBoolMap,
+    StrStrMap,
+
+    // Time params
+    /// The unix timestamp in seconds, encoded as `I64`.
+    Timestamp,
+    /// The duration in seconds, encoded as `U64`.
+    Duration,
+
+    // Bytes params
+    /// The arbitrary binary, encoded as `Parampack`.
+    Bytes,
+
+    // Composite params
+    /// The enum type such as `enum<u8,string,bool>`, the variant tag indexes the types.
+    Variant(Vec<ABIParamType>),
+    /// The fixed-size array type such as `[u8;32]` of the dynamic array type `[u8]` and
+    /// the element count, the elements are encoded without the length prefix.
+    FixedArray(Box<ABIParamType>, usize),
+    /// The address type `addressN` of the expected byte length such as `address20` and
+    /// `address32`, which is encoded without the length prefix, or the variable-length
+    /// `address` with `None`, which has the same layout as `Bytes`.
+    Address(Option<usize>),
+    /// The reference `#N` to the type `N` of an `ABITypeTable` such as the `types` of the
+    /// normalized abi meta, so that a variant can refer to itself, e.g., the list type
+    /// `enum<u64,#0>` at `#0`. It has no layout of its own and is resolved by the table.
+    Ref(usize),
+}
+
+/// The prefix of the type references to the types table, e.g., `#0`.
+pub const TYPE_REF_PREFIX: &str = "#";
+
+macro_rules! impl_param_type_conversion {
+    ($($id:ident),*) => {
+        impl From<ParamType> for ABIParamType {
+            fn from(param_ty: ParamType) -> Self {
+                match param_ty {
+                    $(ParamType::$id => ABIParamType::$id,)*
+                }
+            }
+        }
+
+        impl ABIParamType {
+            /// The `ParamType` code of the abi param type, time types use the codes of
+            /// the underlying integers and bytes use the code of parampack, composite
+            /// types such as variants and fixed-size arrays, the addresses and the type
+            /// references have no code and return `None`.
+            pub fn to_param_type(&self) -> Option<ParamType> {
+                match self {
+                    $(ABIParamType::$id => Some(ParamType::$id),)*
+                    ABIParamType::Timestamp => Some(ParamType::I64),
+                    ABIParamType::Duration => Some(ParamType::U64),
+                    ABIParamType::Bytes => Some(ParamType::Parampack),
+                    ABIParamType::Variant(_)
+                    | ABIParamType::FixedArray(..)
+                    | ABIParamType::Address(_)
+                    | ABIParamType::Ref(_) => None,
+                }
+            }
+        }
+    };
+}
+
+impl_param_type_conversion!(
+    U8, I8, U16, I16, U32, I32, U64, I64, U128, I128, Bool, Str, Parampack, F32, F64, U8Array,
+    I8Array, U16Array, I16Array, U32Array, I32Array, U64Array, I64Array, U128Array, I128Array,
+    BoolArray, StrArray, F64Array, StrU8Map, StrI8Map, StrU16Map, StrI16Map, StrU32Map, StrI32Map,
+    StrU64Map, StrI64Map, StrU128Map, StrI128Map, StrBoolMap, StrStrMap
+);
+
+macro_rules! impl_type_str {
+    (
+        scalars: $($scalar_id:ident => $scalar_name:literal),*;
+        arrays: $($array_id:ident => $array_elem_name:literal),*;
+        maps: $($map_id:ident => $map_value_name:literal),*;
+    ) => {
+        impl ABIParamType {
+            /// The canonical type string, which is the single textual form of the type
+            /// used by the signatures and selectors, e.g., `string`, `[u8]`, `[u8;32]`,
+            /// `{string:u64}`, `enum<u8,string>` and `address20`. It is parsed back by
+            /// `from_type_str`.
+            pub fn canonical_string(&self) -> String {
+                match self {
+                    $(ABIParamType::$scalar_id => $scalar_name.to_string(),)*
+                    $(ABIParamType::$array_id => format!("[{}]", $array_elem_name),)*
+                    $(ABIParamType::$map_id => format!("{{string:{}}}", $map_value_name),)*
+                    ABIParamType::Variant(variant_tys) => {
+                        let variant_type_strs: Vec<String> =
+                            variant_tys.iter().map(|ty| ty.canonical_string()).collect();
+                        format!("enum<{}>", variant_type_strs.join(","))
+                    }
+                    ABIParamType::FixedArray(array_ty, len) => {
+                        let array_type_str = array_ty.canonical_string();
+                        format!("[{};{len}]", &array_type_str[1..array_type_str.len() - 1])
+                    }
+                    ABIParamType::Address(None) => "address".to_string(),
+                    ABIParamType::Address(Some(len)) => format!("address{len}"),
+                    ABIParamType::Ref(id) => format!("{TYPE_REF_PREFIX}{id}"),
+                }
+            }
+
+            fn from_normalized_type_str(type_str: &str) -> Option<ABIParamType> {
+                let strip_brackets = |open: char, close: char| {
+                    type_str.strip_prefix(open).and_then(|s| s.strip_suffix(close))
+                };
+                if let Some(elem_name) = strip_brackets('[', ']') {
+                    if let Some((elem_name, len)) = elem_name.split_once(';') {
+                        // The fixed-size arrays have the same elements as the dynamic arrays.
+                        let array_ty =
+                            ABIParamType::from_normalized_type_str(&format!("[{elem_name}]"))?;
+                        // Only the canonical decimal lengths such as `[u8;4]`.
+                        let len = len
+                            .parse::<usize>()
+                            .ok()
+                            .filter(|parsed| parsed.to_string() == len)?;
+                        return Some(ABIParamType::FixedArray(Box::new(array_ty), len));
+                    }
+                    return match elem_name {
+                        $($array_elem_name => Some(ABIParamType::$array_id),)*
+                        _ => None,
+                    };
+                }
+                if let Some(entry) = strip_brackets('{', '}') {
+                    // Only the string keys are supported by the map params.
+                    return match entry.split_once(':') {
+                        Some(("string", value_name)) => match value_name {
+                            $($map_value_name => Some(ABIParamType::$map_id),)*
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                }
+                if let Some(variant_type_strs) = variant_type_names(type_str) {
+                    if variant_type_strs.is_empty() {
+                        return None;
+                    }
+                    return variant_type_strs
+                        .into_iter()
+                        .map(ABIParamType::from_normalized_type_str)
+                        .collect::<Option<Vec<ABIParamType>>>()
+                        .map(ABIParamType::Variant);
+                }
+                if let Some(id_str) = type_str.strip_prefix(TYPE_REF_PREFIX) {
+                    // Only the canonical decimal ids such as `#0`.
+                    return id_str
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|id| id.to_string() == id_str)
+                        .map(ABIParamType::Ref);
+                }
+                if let Some(len_str) = type_str.strip_prefix("address") {
+                    if len_str.is_empty() {
+                        return Some(ABIParamType::Address(None));
+                    }
+                    // Only the canonical positive decimal lengths such as `address20`.
+                    let len = len_str
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|len| len.to_string() == len_str)?;
+                    return (len > 0).then_some(ABIParamType::Address(Some(len)));
+                }
+                match type_str {
+                    $($scalar_name => Some(ABIParamType::$scalar_id),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_type_str!(
+    scalars: U8 => "u8", I8 => "i8", U16 => "u16", I16 => "i16", U32 => "u32", I32 => "i32",
+        U64 => "u64", I64 => "i64", U128 => "u128", I128 => "i128", Bool => "bool",
+        Str => "string", Parampack => "parampack", F32 => "f32", F64 => "f64",
+        Timestamp => "timestamp", Duration => "duration", Bytes => "bytes";
+    arrays: U8Array => "u8", I8Array => "i8", U16Array => "u16", I16Array => "i16",
+        U32Array => "u32", I32Array => "i32", U64Array => "u64", I64Array => "i64",
+        U128Array => "u128", I128Array => "i128", BoolArray => "bool", StrArray => "string",
+        F64Array => "f64";
+    maps: StrU8Map => "u8", StrI8Map => "i8", StrU16Map => "u16", StrI16Map => "i16",
+        StrU32Map => "u32", StrI32Map => "i32", StrU64Map => "u64", StrI64Map => "i64",
+        StrU128Map => "u128", StrI128Map => "i128", StrBoolMap => "bool", StrStrMap => "string";
+);
+
+impl ABIParamType {
+    /// Parse the abi type string, which is normalized by `normalize_type_name` first so that
+    /// the `str` alias and whitespaces are accepted, e.g., `{str: u8}` and `enum<u8, str>`.
+    pub fn from_type_str(type_str: &str) -> Result<ABIParamType, AbiError> {
+        let normalized_type_str = normalize_type_name(type_str);
+        ABIParamType::from_normalized_type_str(&normalized_type_str)
+            .ok_or(AbiError::UnsupportedType(normalized_type_str))
+    }
+}
+
+macro_rules! int_range {
+    ($ty:ty) => {
+        Some((<$ty>::MIN.to_string(), <$ty>::MAX.to_string()))
+    };
+}
+
+impl ABIParamType {
+    /// The inclusive min and max values of the integer types as decimal strings, e.g.,
+    /// `("-128", "127")` of `i8`, so that the UIs can bound the inputs. It returns `None`
+    /// for the other types, including the floats, the time types and the collections.
+    pub fn value_range(&self) -> Option<(String, String)> {
+        match self {
+            ABIParamType::U8 => int_range!(u8),
+            ABIParamType::I8 => int_range!(i8),
+            ABIParamType::U16 => int_range!(u16),
+            ABIParamType::I16 => int_range!(i16),
+            ABIParamType::U32 => int_range!(u32),
+            ABIParamType::I32 => int_range!(i32),
+            ABIParamType::U64 => int_range!(u64),
+            ABIParamType::I64 => int_range!(i64),
+            ABIParamType::U128 => int_range!(u128),
+            ABIParamType::I128 => int_range!(i128),
+            _ => None,
+        }
+    }
+
+    /// Whether every value of the type is a value of the `other` type, i.e., the type
+    /// itself, the integer types whose value ranges are within the `other` one such as
+    /// `u32` of `u64` and `i64`, and the fixed-size arrays of the `other` array type such
+    /// as `[u8;4]` of `[u8]`. Note that the subtypes are encoded differently.
+    pub fn is_subtype_of(&self, other: &ABIParamType) -> bool {
+        if self == other {
+            return true;
+        }
+        if let ABIParamType::FixedArray(array_ty, _) = self {
+            return array_ty.as_ref() == other;
+        }
+        match (self.value_range(), other.value_range()) {
+            (Some((min, max)), Some((other_min, other_max))) => {
+                // The mins fit in i128 and the maxes fit in u128.
+                let parse_min = |min: &str| min.parse::<i128>().unwrap();
+                let parse_max = |max: &str| max.parse::<u128>().unwrap();
+                parse_min(&min) >= parse_min(&other_min) && parse_max(&max) <= parse_max(&other_max)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The types table resolving the type references such as `#0`, where a type may refer to
+/// the previously declared types and to itself. The types without a finite value such as
+/// `enum<#0>` at `#0` are rejected, so that resolving or walking a type of the table never
+/// recurses forever, and the recursion over the values is bounded by their depth. The
+/// references are the indexes of the types in the table, the types have no names.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ABITypeTable {
+    types: Vec<ABIParamType>,
+}
+
+impl ABITypeTable {
+    /// Build the types table of the types indexed by the reference ids. A reference to a
+    /// later type is an `AbiError::UnresolvedTypeRef`, and a type referring to itself
+    /// without a finite value is an `AbiError::CyclicTypeRef`.
+    pub fn new(types: Vec<ABIParamType>) -> Result<ABITypeTable, AbiError> {
+        for (id, ty) in types.iter().enumerate() {
+            check_type_refs(ty, id)?;
+            if !has_finite_value(ty, id) {
+                return Err(AbiError::CyclicTypeRef(id));
+            }
+        }
+        Ok(ABITypeTable { types })
+    }
+
+    /// Build the types table of the type strings such as `enum<u64,#0>`.
+    pub fn from_type_strs<S: AsRef<str>>(type_strs: &[S]) -> Result<ABITypeTable, AbiError> {
+        let types = type_strs
+            .iter()
+            .map(|type_str| ABIParamType::from_type_str(type_str.as_ref()))
+            .collect::<Result<Vec<ABIParamType>, AbiError>>()?;
+        ABITypeTable::new(types)
+    }
+
+    /// The type of the reference id.
+    pub fn get(&self, id: usize) -> Option<&ABIParamType> {
+        self.types.get(id)
+    }
+
+    /// Follow the type references to the referenced type which is not a reference, the
+    /// other types are returned as they are.
+    pub fn resolve<'a>(&'a self, ty: &'a ABIParamType) -> Result<&'a ABIParamType, AbiError> {
+        let mut ty = ty;
+        // The bare references refer to the earlier types, so the loop ends.
+        while let ABIParamType::Ref(id) = ty {
+            ty = self.get(*id).ok_or(AbiError::UnresolvedTypeRef(*id))?;
+        }
+        Ok(ty)
+    }
+}
+
+/// Check that the type at `id` of the types table only refers to the types up to `id`.
+fn check_type_refs(ty: &ABIParamType, id: usize) -> Result<(), AbiError> {
+    match ty {
+        ABIParamType::Ref(ref_id) if *ref_id > id => Err(AbiError::UnresolvedTypeRef(*ref_id)),
+        ABIParamType::Variant(variant_tys) => variant_tys
+            .iter()
+            .try_for_each(|variant_ty| check_type_refs(variant_ty, id)),
+        _ => Ok(()),
+    }
+}
+
+/// Whether the type at `id` of the types table has a finite value, given that the earlier
+/// types have, i.e., some variant type of every variant on the way doesn't refer to `id`.
+fn has_finite_value(ty: &ABIParamType, id: usize) -> bool {
+    match ty {
+        ABIParamType::Ref(ref_id) => *ref_id < id,
+        ABIParamType::Variant(variant_tys) => variant_tys
+            .iter()
+            .any(|variant_ty| has_finite_value(variant_ty, id)),
+        _ => true,
+    }
+}
+
+/// Normalize the abi type name to the canonical spelling: the `str` alias is written
+/// as `string` and whitespaces are removed, e.g., `{str: u8}` to `{string:u8}`. The
+/// normalized names of the supported types are their `canonical_string`s, and the
+/// unsupported type names such as tuples are normalized in the same way.
+pub fn normalize_type_name(type_name: &str) -> String {
+    let mut result = String::with_capacity(type_name.len());
+    let mut ident = String::new();
+    for c in type_name.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            ident.push(c);
+            continue;
+        }
+        if !ident.is_empty() {
+            result.push_str(if ident == "str" { "string" } else { &ident });
+            ident.clear();
+        }
+        if !c.is_whitespace() {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Split the type name by the separator which is not nested in brackets.
+pub fn split_top_level(type_name: &str, sep: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (pos, c) in type_name.char_indices() {
+        match c {
+            '[' | '{' | '(' | '<' => depth += 1,
+            ']' | '}' | ')' | '>' => depth -= 1,
+            _ if c == sep && depth == 0 => {
+                parts.push(&type_name[start..pos]);
+                start = pos + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&type_name[start..]);
+    parts
+}
+
+/// Get the variant type names of the normalized enum type name, e.g., `enum<u8,string>`
+/// to `[u8, string]`, or `None` when the type name is not an enum.
+pub fn variant_type_names(type_name: &str) -> Option<Vec<&str>> {
+    let inner = type_name.strip_prefix("enum<")?.strip_suffix('>')?;
+    if inner.is_empty() {
+        return Some(vec![]);
+    }
+    Some(split_top_level(inner, ','))
+}
+
+#[cfg(test)]
+mod types_test {
+    use super::{normalize_type_name, ABIParamType, ABITypeTable};
+    use crate::abi::error::AbiError;
+    use crate::encoding::datastream::ParamType;
+    use num_traits::FromPrimitive;
+    use proptest::prelude::*;
+
+    /// Generate the abi param types with every `ParamType` code, the time types, the
+    /// fixed-size arrays and the nested variants.
+    fn arb_abi_param_type() -> impl Strategy<Value = ABIParamType> {
+        let fixed_array = (
+            prop_oneof![
+                Just(ABIParamType::U8Array),
+                Just(ABIParamType::I128Array),
+                Just(ABIParamType::StrArray),
+            ],
+            0usize..64,
+        )
+            .prop_map(|(array_ty, len)| ABIParamType::FixedArray(Box::new(array_ty), len));
+        let leaf = prop_oneof![
+            (0u8..=64).prop_filter_map("no param type", |code| {
+                ParamType::from_u8(code).map(ABIParamType::from)
+            }),
+            Just(ABIParamType::Timestamp),
+            Just(ABIParamType::Duration),
+            Just(ABIParamType::Bytes),
+            fixed_array,
+            prop::option::of(1usize..64).prop_map(ABIParamType::Address),
+            (0usize..8).prop_map(ABIParamType::Ref),
+        ];
+        leaf.prop_recursive(3, 16, 4, |inner| {
+            prop::collection::vec(inner, 1..4).prop_map(ABIParamType::Variant)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_type_str_round_trip(
+            ty in arb_abi_param_type(),
+            str_alias in any::<bool>(),
+            spaces in any::<bool>(),
+        ) {
+            let canonical = ty.canonical_string();
+            prop_assert_eq!(ABIParamType::from_type_str(&canonical).unwrap(), ty.clone());
+            prop_assert_eq!(normalize_type_name(&canonical), canonical.clone());
+            // Re-printing any spelling of the type string is idempotent.
+            let mut spelled = canonical.clone();
+            if str_alias {
+                spelled = spelled.replace("string", "str");
+            }
+            if spaces {
+                spelled = spelled.replace(',', ", ").replace(':', ": ").replace('<', " < ");
+            }
+            let parsed = ABIParamType::from_type_str(&spelled).unwrap();
+            prop_assert_eq!(&parsed, &ty);
+            prop_assert_eq!(parsed.canonical_string(), canonical);
+        }
+    }
+
+    #[test]
+    fn test_type_table() {
+        let table = ABITypeTable::from_type_strs(&["u8", "enum<u64,#1>", "#1"]).unwrap();
+        assert_eq!(
+            table.resolve(&ABIParamType::Ref(2)).unwrap(),
+            &ABIParamType::Variant(vec![ABIParamType::U64, ABIParamType::Ref(1)])
+        );
+        assert_eq!(table.resolve(&ABIParamType::U8).unwrap(), &ABIParamType::U8);
+        assert_eq!(
+            table.resolve(&ABIParamType::Ref(3)),
+            Err(AbiError::UnresolvedTypeRef(3))
+        );
+        // The references to the later types.
+        assert_eq!(
+            ABITypeTable::from_type_strs(&["enum<u8,#1>", "u8"]),
+            Err(AbiError::UnresolvedTypeRef(1))
+        );
+        // The cycles without a finite value.
+        for type_strs in [
+            vec!["#0"],
+            vec!["u8", "enum<#1>"],
+            vec!["enum<enum<#0>,#0>"],
+        ] {
+            assert_eq!(
+                ABITypeTable::from_type_strs(&type_strs),
+                Err(AbiError::CyclicTypeRef(type_strs.len() - 1))
+            );
+        }
+    }
+
+    #[test]
+    fn test_value_range() {
+        let range = |min: &str, max: &str| Some((min.to_string(), max.to_string()));
+        assert_eq!(ABIParamType::I8.value_range(), range("-128", "127"));
+        assert_eq!(
+            ABIParamType::U64.value_range(),
+            range("0", "18446744073709551615")
+        );
+        assert_eq!(
+            ABIParamType::I128.value_range(),
+            range(&i128::MIN.to_string(), &i128::MAX.to_string())
+        );
+        for ty in [
+            ABIParamType::F64,
+            ABIParamType::Str,
+            ABIParamType::U64Array,
+            ABIParamType::Timestamp,
+            ABIParamType::Variant(vec![ABIParamType::U8]),
+        ] {
+            assert_eq!(ty.value_range(), None);
+        }
+    }
+
+    #[test]
+    fn test_is_subtype_of() {
+        let ty = |type_str: &str| ABIParamType::from_type_str(type_str).unwrap();
+        for (sub_ty, super_ty) in [
+            ("u32", "u64"),
+            ("u32", "i64"),
+            ("i8", "i128"),
+            ("u64", "u64"),
+            ("[u8;4]", "[u8]"),
+            ("enum<u8,str>", "enum<u8,str>"),
+        ] {
+            assert!(
+                ty(sub_ty).is_subtype_of(&ty(super_ty)),
+                "{sub_ty} of {super_ty}"
+            );
+        }
+        for (sub_ty, super_ty) in [
+            ("u64", "u32"),
+            ("i8", "u64"),
+            ("u64", "i64"),
+            ("u8", "timestamp"),
+            ("[u8]", "[u8;4]"),
+            ("[u8;4]", "[u16]"),
+            ("f32", "f64"),
+        ] {
+            assert!(
+                !ty(sub_ty).is_subtype_of(&ty(super_ty)),
+                "{sub_ty} of {super_ty}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_type_str() {
+        for (type_str, ty) in [
+            ("u8", ABIParamType::U8),
+            ("str", ABIParamType::Str),
+            (" [ str ] ", ABIParamType::StrArray),
+            ("{str: bool}", ABIParamType::StrBoolMap),
+            ("timestamp", ABIParamType::Timestamp),
+            (
+                "[str; 4]",
+                ABIParamType::FixedArray(Box::new(ABIParamType::StrArray), 4),
+            ),
+            ("address", ABIParamType::Address(None)),
+            ("address32", ABIParamType::Address(Some(32))),
+            ("#3", ABIParamType::Ref(3)),
+            (
+                "enum<u8, enum<[u64], duration>>",
+                ABIParamType::Variant(vec![
+                    ABIParamType::U8,
+                    ABIParamType::Variant(vec![ABIParamType::U64Array, ABIParamType::Duration]),
+                ]),
+            ),
+        ] {
+            assert_eq!(ABIParamType::from_type_str(type_str).unwrap(), ty);
+        }
+        for (type_str, normalized) in [
+            ("u8*", "u8*"),
+            ("[f32]", "[f32]"),
+            ("{u8:u8}", "{u8:u8}"),
+            ("[[u8]]", "[[u8]]"),
+            ("[u8;]", "[u8;]"),
+            ("[u8;-1]", "[u8;-1]"),
+            ("[u8;+4]", "[u8;+4]"),
+            ("[u8;04]", "[u8;04]"),
+            ("[u8;00]", "[u8;00]"),
+            ("[f32;4]", "[f32;4]"),
+            ("[[u8;2];2]", "[[u8;2];2]"),
+            ("address0", "address0"),
+            ("address020", "address020"),
+            ("address+20", "address+20"),
+            ("#", "#"),
+            ("#01", "#01"),
+            ("#u8", "#u8"),
+            ("enum<>", "enum<>"),
+            ("enum<u8, u256>", "enum<u8,u256>"),
+            ("(u8, str)", "(u8,string)"),
+            ("", ""),
+        ] {
+            assert_eq!(
+                ABIParamType::from_type_str(type_str),
+                Err(AbiError::UnsupportedType(normalized.to_string()))
+            );
+        }
+    }
+}
@@ -0,0 +1,346 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The visitor of the decoded param trees, so that the tooling passes such as redaction,
+//! size accounting and pretty-printing needn't match every `ABIParam` variant by hand.
+//! `ABIParam::visit` dispatches the scalar hooks for the params and for every element of
+//! the arrays and maps, and brackets the nested params with the `enter_*` and `leave_*`
+//! hooks. All the hooks do nothing by default.
+
+use crate::abi::params::{uleb128_encoded_len, ABIParam};
+
+pub trait ABIParamVisitor {
+    /// The unsigned integers with the bit width, e.g., `8` for `u8`.
+    fn visit_uint(&mut self, _v: u128, _bits: u32) {}
+    /// The signed integers with the bit width, e.g., `64` for `i64`.
+    fn visit_int(&mut self, _v: i128, _bits: u32) {}
+    /// The floats with the bit width, `f32` values are widened to `f64`.
+    fn visit_float(&mut self, _v: f64, _bits: u32) {}
+    fn visit_bool(&mut self, _v: bool) {}
+    fn visit_str(&mut self, _v: &str) {}
+    fn visit_parampack(&mut self, _v: &[u8]) {}
+    fn visit_bytes(&mut self, _v: &[u8]) {}
+    /// The address bytes with the expected length of `addressN`, or `None` of `address`.
+    fn visit_address(&mut self, _v: &[u8], _len: Option<usize>) {}
+    fn visit_timestamp(&mut self, _v: i64) {}
+    fn visit_duration(&mut self, _v: u64) {}
+    fn enter_array(&mut self, _len: usize) {}
+    fn leave_array(&mut self) {}
+    fn enter_map(&mut self, _len: usize) {}
+    /// The map keys, which are visited as strings by default, followed by the value.
+    fn visit_map_key(&mut self, key: &str) {
+        self.visit_str(key);
+    }
+    fn leave_map(&mut self) {}
+    /// The variant tag, followed by the value and `leave_variant`.
+    fn enter_variant(&mut self, _tag: u32) {}
+    fn leave_variant(&mut self) {}
+    /// The fixed-size array with the element count, followed by the elements visited as
+    /// the dynamic array and `leave_fixed_array`.
+    fn enter_fixed_array(&mut self, _len: usize) {}
+    fn leave_fixed_array(&mut self) {}
+}
+
+macro_rules! visit_array {
+    ($visitor:ident, $v:expr, $elem:ident => $visit:expr) => {{
+        $visitor.enter_array($v.len());
+        for $elem in $v.iter() {
+            $visit;
+        }
+        $visitor.leave_array();
+    }};
+}
+
+macro_rules! visit_map {
+    ($visitor:ident, $v:expr, $elem:ident => $visit:expr) => {{
+        $visitor.enter_map($v.len());
+        for (key, $elem) in $v.iter() {
+            $visitor.visit_map_key(key);
+            $visit;
+        }
+        $visitor.leave_map();
+    }};
+}
+
+impl ABIParam {
+    /// Walk the param tree with the visitor in the encoding order.
+    pub fn visit<V: ABIParamVisitor + ?Sized>(&self, visitor: &mut V) {
+        match self {
+            ABIParam::U8(v) => visitor.visit_uint(*v as u128, 8),
+            ABIParam::I8(v) => visitor.visit_int(*v as i128, 8),
+            ABIParam::U16(v) => visitor.visit_uint(*v as u128, 16),
+            ABIParam::I16(v) => visitor.visit_int(*v as i128, 16),
+            ABIParam::U32(v) => visitor.visit_uint(*v as u128, 32),
+            ABIParam::I32(v) => visitor.visit_int(*v as i128, 32),
+            ABIParam::U64(v) => visitor.visit_uint(*v as u128, 64),
+            ABIParam::I64(v) => visitor.visit_int(*v as i128, 64),
+            ABIParam::U128(v) => visitor.visit_uint(*v, 128),
+            ABIParam::I128(v) => visitor.visit_int(*v, 128),
+            ABIParam::F32(v) => visitor.visit_float(*v as f64, 32),
+            ABIParam::F64(v) => visitor.visit_float(*v, 64),
+            ABIParam::Bool(v) => visitor.visit_bool(*v),
+            ABIParam::Str(v) => visitor.visit_str(v),
+            ABIParam::U8Array(v) => {
+                visit_array!(visitor, v, e => visitor.visit_uint(*e as u128, 8))
+            }
+            ABIParam::I8Array(v) => visit_array!(visitor, v, e => visitor.visit_int(*e as i128, 8)),
+            ABIParam::U16Array(v) => {
+                visit_array!(visitor, v, e => visitor.visit_uint(*e as u128, 16))
+            }
+            ABIParam::I16Array(v) => {
+                visit_array!(visitor, v, e => visitor.visit_int(*e as i128, 16))
+            }
+            ABIParam::U32Array(v) => {
+                visit_array!(visitor, v, e => visitor.visit_uint(*e as u128, 32))
+            }
+            ABIParam::I32Array(v) => {
+                visit_array!(visitor, v, e => visitor.visit_int(*e as i128, 32))
+            }
+            ABIParam::U64Array(v) => {
+                visit_array!(visitor, v, e => visitor.visit_uint(*e as u128, 64))
+            }
+            ABIParam::I64Array(v) => {
+                visit_array!(visitor, v, e => visitor.visit_int(*e as i128, 64))
+            }
+            ABIParam::U128Array(v) => visit_array!(visitor, v, e => visitor.visit_uint(*e, 128)),
+            ABIParam::I128Array(v) => visit_array!(visitor, v, e => visitor.visit_int(*e, 128)),
+            ABIParam::BoolArray(v) => visit_array!(visitor, v, e => visitor.visit_bool(*e)),
+            ABIParam::StrArray(v) => visit_array!(visitor, v, e => visitor.visit_str(e)),
+            ABIParam::F64Array(v) => visit_array!(visitor, v, e => visitor.visit_float(*e, 64)),
+            ABIParam::StrU8Map(v) => visit_map!(visitor, v, e => visitor.visit_uint(*e as u128, 8)),
+            ABIParam::StrI8Map(v) => visit_map!(visitor, v, e => visitor.visit_int(*e as i128, 8)),
+            ABIParam::StrU16Map(v) => {
+                visit_map!(visitor, v, e => visitor.visit_uint(*e as u128, 16))
+            }
+            ABIParam::StrI16Map(v) => {
+                visit_map!(visitor, v, e => visitor.visit_int(*e as i128, 16))
+            }
+            ABIParam::StrU32Map(v) => {
+                visit_map!(visitor, v, e => visitor.visit_uint(*e as u128, 32))
+            }
+            ABIParam::StrI32Map(v) => {
+                visit_map!(visitor, v, e => visitor.visit_int(*e as i128, 32))
+            }
+            ABIParam::StrU64Map(v) => {
+                visit_map!(visitor, v, e => visitor.visit_uint(*e as u128, 64))
+            }
+            ABIParam::StrI64Map(v) => {
+                visit_map!(visitor, v, e => visitor.visit_int(*e as i128, 64))
+            }
+            ABIParam::StrU128Map(v) => visit_map!(visitor, v, e => visitor.visit_uint(*e, 128)),
+            ABIParam::StrI128Map(v) => visit_map!(visitor, v, e => visitor.visit_int(*e, 128)),
+            ABIParam::StrBoolMap(v) => visit_map!(visitor, v, e => visitor.visit_bool(*e)),
+            ABIParam::StrStrMap(v) => visit_map!(visitor, v, e => visitor.visit_str(e)),
+            ABIParam::Parampack(v) => visitor.visit_parampack(v),
+            ABIParam::Bytes(v) => visitor.visit_bytes(v),
+            ABIParam::Timestamp(v) => visitor.visit_timestamp(*v),
+            ABIParam::Duration(v) => visitor.visit_duration(*v),
+            ABIParam::Variant { tag, value } => {
+                visitor.enter_variant(*tag);
+                value.visit(visitor);
+                visitor.leave_variant();
+            }
+            ABIParam::FixedArray(array) => {
+                visitor.enter_fixed_array(array.array_len().unwrap_or_default());
+                array.visit(visitor);
+                visitor.leave_fixed_array();
+            }
+            ABIParam::Address { bytes, len } => visitor.visit_address(bytes, *len),
+        }
+    }
+}
+
+/// The example visitor counting the total bytes of the default fixed-width encoding,
+/// which is the same as `ABIParam::encoded_len`.
+#[derive(Debug, Default)]
+pub struct EncodedLenVisitor {
+    pub len: usize,
+    /// The next array is the elements of a fixed-size array, which has no length prefix.
+    in_fixed_array: bool,
+}
+
+impl ABIParamVisitor for EncodedLenVisitor {
+    fn visit_uint(&mut self, _v: u128, bits: u32) {
+        self.len += bits as usize / 8;
+    }
+
+    fn visit_int(&mut self, _v: i128, bits: u32) {
+        self.len += bits as usize / 8;
+    }
+
+    fn visit_float(&mut self, _v: f64, bits: u32) {
+        self.len += bits as usize / 8;
+    }
+
+    fn visit_bool(&mut self, _v: bool) {
+        self.len += 1;
+    }
+
+    fn visit_str(&mut self, v: &str) {
+        self.len += uleb128_encoded_len(v.len()) + v.len();
+    }
+
+    fn visit_parampack(&mut self, v: &[u8]) {
+        self.len += uleb128_encoded_len(v.len()) + v.len();
+    }
+
+    fn visit_bytes(&mut self, v: &[u8]) {
+        self.len += uleb128_encoded_len(v.len()) + v.len();
+    }
+
+    fn visit_address(&mut self, v: &[u8], len: Option<usize>) {
+        self.len += len.map_or(uleb128_encoded_len(v.len()), |_| 0) + v.len();
+    }
+
+    fn visit_timestamp(&mut self, _v: i64) {
+        self.len += 8;
+    }
+
+    fn visit_duration(&mut self, _v: u64) {
+        self.len += 8;
+    }
+
+    fn enter_array(&mut self, len: usize) {
+        if !core::mem::take(&mut self.in_fixed_array) {
+            self.len += uleb128_encoded_len(len);
+        }
+    }
+
+    fn enter_map(&mut self, len: usize) {
+        self.len += uleb128_encoded_len(len);
+    }
+
+    fn enter_variant(&mut self, _tag: u32) {
+        self.len += 4;
+    }
+
+    fn enter_fixed_array(&mut self, _len: usize) {
+        self.in_fixed_array = true;
+    }
+}
+
+#[cfg(test)]
+mod visitor_test {
+    use super::{ABIParamVisitor, EncodedLenVisitor};
+    use crate::abi::params::ABIParam;
+
+    #[test]
+    fn test_encoded_len_visitor() {
+        for param in [
+            ABIParam::U8(1),
+            ABIParam::I128(-1),
+            ABIParam::F32(1.5),
+            ABIParam::Bool(true),
+            ABIParam::Str("hello".to_string()),
+            ABIParam::Str("x".repeat(200)),
+            ABIParam::U32Array(vec![1, 2, 3]),
+            ABIParam::StrArray(vec!["a".to_string(), "bc".to_string()]),
+            ABIParam::F64Array(vec![]),
+            ABIParam::StrU64Map([("a".to_string(), 1), ("b".to_string(), 2)].into()),
+            ABIParam::StrStrMap([("k".to_string(), "v".to_string())].into()),
+            ABIParam::Parampack(vec![0xde, 0xad]),
+            ABIParam::Bytes(vec![0xbe; 300]),
+            ABIParam::Address {
+                bytes: vec![0xaa; 20],
+                len: Some(20),
+            },
+            ABIParam::Address {
+                bytes: vec![0xaa; 200],
+                len: None,
+            },
+            ABIParam::Timestamp(-1),
+            ABIParam::Duration(60),
+            ABIParam::Variant {
+                tag: 1,
+                value: Box::new(ABIParam::Variant {
+                    tag: 0,
+                    value: Box::new(ABIParam::I16Array(vec![-1; 130])),
+                }),
+            },
+            ABIParam::Variant {
+                tag: 0,
+                value: Box::new(ABIParam::FixedArray(Box::new(ABIParam::U64Array(vec![
+                    7;
+                    200
+                ])))),
+            },
+        ] {
+            let mut visitor = EncodedLenVisitor::default();
+            param.visit(&mut visitor);
+            assert_eq!(visitor.len, param.encoded_len(), "{param:?}");
+            assert_eq!(visitor.len, param.as_bytes().len(), "{param:?}");
+        }
+    }
+
+    /// Redact the strings and record the nesting events.
+    #[derive(Default)]
+    struct EventsVisitor {
+        events: Vec<String>,
+    }
+
+    impl ABIParamVisitor for EventsVisitor {
+        fn visit_uint(&mut self, v: u128, bits: u32) {
+            self.events.push(format!("u{bits}:{v}"));
+        }
+
+        fn visit_str(&mut self, v: &str) {
+            self.events.push(format!("str:{}", "*".repeat(v.len())));
+        }
+
+        fn visit_map_key(&mut self, key: &str) {
+            self.events.push(format!("key:{key}"));
+        }
+
+        fn enter_map(&mut self, len: usize) {
+            self.events.push(format!("map:{len}"));
+        }
+
+        fn leave_map(&mut self) {
+            self.events.push("end map".to_string());
+        }
+
+        fn enter_variant(&mut self, tag: u32) {
+            self.events.push(format!("variant:{tag}"));
+        }
+
+        fn leave_variant(&mut self) {
+            self.events.push("end variant".to_string());
+        }
+    }
+
+    #[test]
+    fn test_visit_order() {
+        let param = ABIParam::Variant {
+            tag: 2,
+            value: Box::new(ABIParam::StrStrMap(
+                [
+                    ("a".to_string(), "secret".to_string()),
+                    ("b".to_string(), "pwd".to_string()),
+                ]
+                .into(),
+            )),
+        };
+        let mut visitor = EventsVisitor::default();
+        param.visit(&mut visitor);
+        ABIParam::U16(7).visit(&mut visitor);
+        assert_eq!(
+            visitor.events,
+            vec![
+                "variant:2",
+                "map:2",
+                "key:a",
+                "str:******",
+                "key:b",
+                "str:***",
+                "end map",
+                "end variant",
+                "u16:7",
+            ]
+        );
+        // The default map key hook visits the keys as strings.
+        let mut visitor = EncodedLenVisitor::default();
+        ABIParam::StrBoolMap([("key".to_string(), true)].into()).visit(&mut visitor);
+        assert_eq!(visitor.len, 1 + 4 + 1);
+    }
+}
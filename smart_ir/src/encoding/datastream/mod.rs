@@ -5,7 +5,8 @@
 use crate::ir::cfg;
 use num_derive::FromPrimitive;
 
-/// Data stream encoding/decoding version
+/// Data stream encoding/decoding version, which is written as the leading byte of the
+/// encoded params and must be checked by the decoders.
 pub const DEFAULT_VERSION: u8 = 0;
 
 /// Parameter type list and the representation of type code
@@ -25,6 +26,8 @@ pub enum ParamType {
     Bool = 10,
     Str = 11,
     Parampack = 12,
+    F32 = 13,
+    F64 = 14,
 
     // Array params
     U8Array = 32,
@@ -39,6 +42,7 @@ pub enum ParamType {
     I128Array = 41,
     BoolArray = 42,
     StrArray = 43,
+    F64Array = 44,
 
     // Map params
     StrU8Map = 64,
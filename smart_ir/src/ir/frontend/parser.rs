@@ -7,7 +7,14 @@ use lalrpop_util::lalrpop_mod;
 lalrpop_mod!(#[allow(clippy::all)]#[allow(clippy::uninlined_format_args)]pub parser,"/ir/frontend/ir.rs");
 
 pub fn compile(input: &str) -> Box<frontend::Module> {
-    parser::ModParser::new().parse(input).unwrap()
+    try_compile(input).unwrap()
+}
+
+/// Parse the textual IR and return the parse error message instead of panicking.
+pub fn try_compile(input: &str) -> Result<Box<frontend::Module>, String> {
+    parser::ModParser::new()
+        .parse(input)
+        .map_err(|err| err.to_string())
 }
 
 #[cfg(test)]
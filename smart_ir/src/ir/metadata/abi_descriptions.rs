@@ -0,0 +1,74 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ir::cfg::Literal;
+use crate::ir::cfg::MetaData;
+use crate::ir::cfg::MetaDataNode;
+use crate::ir::context::IRContext;
+
+/// The doc comments of the function and its params carried in the abi for the client docs,
+/// the first string is the function description and the rest are the param descriptions
+/// by position, an empty string means no description, e.g., `!ir_abi_descriptions !0` with
+/// `meta !0 = !{"Transfer the tokens": str, "": str, "The amount": str, }`.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct AbiDescriptions {
+    method: String,
+    params: Vec<String>,
+}
+
+impl AbiDescriptions {
+    pub fn method(mut self, value: String) -> Self {
+        self.method = value;
+        self
+    }
+
+    pub fn params(mut self, value: Vec<String>) -> Self {
+        self.params = value;
+        self
+    }
+
+    pub fn get_method(&self) -> &String {
+        &self.method
+    }
+
+    pub fn get_params(&self) -> &Vec<String> {
+        &self.params
+    }
+    pub fn from(metadata: &MetaData) -> Result<Self, String> {
+        let mut lits = metadata.data.iter();
+        let method = match lits.next() {
+            Some(lit) => lit.get_string()?,
+            None => String::new(),
+        };
+        let mut params = vec![];
+        for lit in lits {
+            params.push(lit.get_string()?);
+        }
+        Ok(Self { method, params })
+    }
+    pub fn to_metadata(&self) -> MetaData {
+        let mut metadata = MetaData::default();
+        metadata.push_field(Literal::Str(self.method.clone()));
+        for param in self.params.iter() {
+            metadata.push_field(Literal::Str(param.clone()));
+        }
+        metadata
+    }
+    pub fn get_metadata_key(&self) -> String {
+        "ir_abi_descriptions".to_string()
+    }
+    pub fn add_to_context(ctx: &IRContext, md_node: &mut dyn MetaDataNode, loc: &AbiDescriptions) {
+        let md_idx = ctx.add_metadata(loc.to_metadata());
+        let metadata = md_node.get_metadata_mut();
+        metadata.insert("ir_abi_descriptions".to_string(), md_idx);
+    }
+    pub fn get_from_context(
+        ctx: &IRContext,
+        md_node: &dyn MetaDataNode,
+    ) -> Option<AbiDescriptions> {
+        let metadata = md_node.get_metadata();
+        let md_idx = metadata.get("ir_abi_descriptions")?;
+        AbiDescriptions::from(ctx.get_metadata(md_idx).as_ref()?).ok()
+    }
+}
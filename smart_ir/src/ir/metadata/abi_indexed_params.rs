@@ -0,0 +1,56 @@
+// Copyright (c) The Ant Group Core Contributors
+// Copyright (c) The Smart Intermediate Representation Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ir::cfg::Literal;
+use crate::ir::cfg::MetaData;
+use crate::ir::cfg::{IntLiteral, MetaDataNode};
+use crate::ir::context::IRContext;
+
+/// The positions of the function params which are marked as indexed in the abi for
+/// the off-chain filtering, e.g., `!ir_abi_indexed_params !0` with `meta !0 = !{0: u32, }`.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct AbiIndexedParams {
+    indexes: Vec<u32>,
+}
+
+impl AbiIndexedParams {
+    pub fn indexes(mut self, value: Vec<u32>) -> Self {
+        self.indexes = value;
+        self
+    }
+
+    pub fn get_indexes(&self) -> &Vec<u32> {
+        &self.indexes
+    }
+    pub fn from(metadata: &MetaData) -> Result<Self, String> {
+        let mut indexes = vec![];
+        for lit in metadata.data.iter() {
+            indexes.push(lit.get_u32()?);
+        }
+        Ok(Self { indexes })
+    }
+    pub fn to_metadata(&self) -> MetaData {
+        let mut metadata = MetaData::default();
+        for index in self.indexes.iter() {
+            metadata.push_field(Literal::Int(IntLiteral::U32(*index)));
+        }
+        metadata
+    }
+    pub fn get_metadata_key(&self) -> String {
+        "ir_abi_indexed_params".to_string()
+    }
+    pub fn add_to_context(ctx: &IRContext, md_node: &mut dyn MetaDataNode, loc: &AbiIndexedParams) {
+        let md_idx = ctx.add_metadata(loc.to_metadata());
+        let metadata = md_node.get_metadata_mut();
+        metadata.insert("ir_abi_indexed_params".to_string(), md_idx);
+    }
+    pub fn get_from_context(
+        ctx: &IRContext,
+        md_node: &dyn MetaDataNode,
+    ) -> Option<AbiIndexedParams> {
+        let metadata = md_node.get_metadata();
+        let md_idx = metadata.get("ir_abi_indexed_params")?;
+        AbiIndexedParams::from(ctx.get_metadata(md_idx).as_ref()?).ok()
+    }
+}
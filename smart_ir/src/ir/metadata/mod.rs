@@ -2,6 +2,8 @@
 // Copyright (c) The Smart Intermediate Representation Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod abi_descriptions;
+pub mod abi_indexed_params;
 pub mod asset;
 pub mod debug_info;
 pub mod extend_hostapi;